@@ -1,4 +1,4 @@
-use msfs::sim_connect::{client_data_definition, SimConnect, SimConnectRecv};
+use msfs::sim_connect::{client_data_definition, RequestId, SimConnect, SimConnectRecv};
 
 #[client_data_definition]
 #[derive(Debug)]
@@ -41,7 +41,7 @@ fn reader() -> Result<(), Box<dyn std::error::Error>> {
         }
     })?;
 
-    sim.request_client_data::<Data>(0, "data")?;
+    let _data = sim.request_client_data::<Data>(RequestId(0), "data")?;
 
     loop {
         sim.call_dispatch()?;
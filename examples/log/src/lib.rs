@@ -1,6 +1,8 @@
 use msfs::{
     self,
-    sim_connect::{data_definition, Period, SimConnectRecv, SIMCONNECT_OBJECT_ID_USER},
+    sim_connect::{
+        data_definition, OwnedSimConnectRecv, Period, RequestId, SIMCONNECT_OBJECT_ID_USER,
+    },
 };
 
 #[data_definition]
@@ -21,8 +23,8 @@ struct ControlSurfaces {
 async fn module(mut module: msfs::StandaloneModule) -> Result<(), Box<dyn std::error::Error>> {
     let mut sim = module.open_simconnect("LOG")?;
 
-    sim.request_data_on_sim_object::<ControlSurfaces>(
-        0,
+    let _control_surfaces = sim.request_data_on_sim_object::<ControlSurfaces>(
+        RequestId(0),
         SIMCONNECT_OBJECT_ID_USER,
         Period::SimFrame,
     )?;
@@ -31,7 +33,7 @@ async fn module(mut module: msfs::StandaloneModule) -> Result<(), Box<dyn std::e
 
     while let Some(event) = module.next_event().await {
         match event {
-            SimConnectRecv::SimObjectData(event) => {
+            OwnedSimConnectRecv::SimObjectData(event) => {
                 let data = event.into::<ControlSurfaces>(&sim).unwrap();
                 println!("WASM: SimObjectData {:?}", data);
             }
@@ -1,5 +1,5 @@
 use msfs::sim_connect::{
-    data_definition, Period, SimConnect, SimConnectRecv, SIMCONNECT_OBJECT_ID_USER,
+    data_definition, Period, RequestId, SimConnect, SimConnectRecv, SIMCONNECT_OBJECT_ID_USER,
 };
 
 #[data_definition]
@@ -35,8 +35,9 @@ struct Controls {
 #[data_definition]
 #[derive(Debug)]
 struct Throttle(
-    #[name = "GENERAL ENG THROTTLE LEVER POSITION:1"] #[unit = "Percent"] f64,
-    #[name = "GENERAL ENG THROTTLE LEVER POSITION:2"] #[unit = "Percent"] f64,
+    #[name = "GENERAL ENG THROTTLE LEVER POSITION:{}"]
+    #[unit = "Percent"]
+    [f64; 2],
 );
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -48,20 +49,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             1 => {
                 println!("{:?}", event.into::<Controls>(sim).unwrap());
             }
-            2 => {
-                println!("{:?}", event.into::<Throttle>(sim).unwrap());
-            }
             _ => {}
         },
+        SimConnectRecv::Exception(e) => println!("{}", e.decode(sim)),
         _ => println!("{:?}", recv),
     })?;
 
-    sim.request_data_on_sim_object::<Data>(0, SIMCONNECT_OBJECT_ID_USER, Period::SimFrame)?;
-    sim.request_data_on_sim_object::<Controls>(1, SIMCONNECT_OBJECT_ID_USER, Period::SimFrame)?;
-    sim.request_data_on_sim_object::<Throttle>(2, SIMCONNECT_OBJECT_ID_USER, Period::SimFrame)?;
+    let _data = sim.request_data_on_sim_object::<Data>(
+        RequestId(0),
+        SIMCONNECT_OBJECT_ID_USER,
+        Period::SimFrame,
+    )?;
+    let _controls = sim.request_data_on_sim_object::<Controls>(
+        RequestId(1),
+        SIMCONNECT_OBJECT_ID_USER,
+        Period::SimFrame,
+    )?;
+    let mut throttle = sim.subscribe::<Throttle>(SIMCONNECT_OBJECT_ID_USER, Period::SimFrame)?;
 
     loop {
         sim.call_dispatch()?;
+        if let Some(throttle) = throttle.latest().transpose()? {
+            println!("{:?}", throttle);
+        }
         std::thread::sleep(std::time::Duration::from_millis(10));
     }
 }
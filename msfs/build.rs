@@ -1,5 +1,15 @@
 fn main() {
     let wasm = std::env::var("TARGET").unwrap().starts_with("wasm32-");
+    let windows = std::env::var("CARGO_CFG_TARGET_OS").unwrap() == "windows";
+
+    // Other platforms have no SimConnect library to link and can only use `TcpBackend`, so
+    // they build against the pre-generated bindings and don't need the SDK.
+    println!("cargo::rustc-check-cfg=cfg(vendored_bindings)");
+    if !wasm && !windows {
+        println!("cargo:rustc-cfg=vendored_bindings");
+        return;
+    }
+
     let msfs_sdk = msfs_sdk::calculate_msfs_sdk_path().unwrap();
     println!("Found MSFS SDK: {msfs_sdk:?}");
 
@@ -69,7 +79,7 @@ fn main() {
     }
 
     // SimConnect native linkage
-    if !wasm {
+    if windows {
         println!("cargo:rustc-link-search={msfs_sdk}/SimConnect SDK/lib/static");
         println!("cargo:rustc-link-lib=SimConnect");
         println!("cargo:rustc-link-lib=shlwapi");
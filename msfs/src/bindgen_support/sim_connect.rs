@@ -0,0 +1,689 @@
+// Pre-generated subset of the MSFS SDK bindings, used when the crate is built for
+// a platform other than wasm or Windows. These targets have no SimConnect library
+// to link against and can only talk to the simulator through `TcpBackend`, so the
+// SDK is not required to build them.
+//
+// Keep in sync with the bindgen output for `wrapper.h`. Types are fixed to their
+// Windows sizes (`DWORD` is 32 bits) because they describe the wire format, and
+// their sizes and offsets are checked against `SimConnect.h` by `tests/layout.rs`.
+// The SimConnect functions are left out, as there is no library to link them from.
+
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+
+pub type DWORD = c_uint;
+pub type UINT64 = u64;
+pub type BOOL = c_int;
+pub type HRESULT = i32;
+pub type HANDLE = u64;
+pub type HWND = *mut c_void;
+pub type LPCSTR = *const c_char;
+pub type FsContext = u64;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct sGaugeDrawData {
+    pub mx: f64,
+    pub my: f64,
+    pub t: f64,
+    pub dt: f64,
+    pub winWidth: c_int,
+    pub winHeight: c_int,
+    pub fbWidth: c_int,
+    pub fbHeight: c_int,
+}
+pub const PANEL_SERVICE_PRE_QUERY: u32 = 0;
+pub const PANEL_SERVICE_POST_QUERY: u32 = 1;
+pub const PANEL_SERVICE_PRE_INSTALL: u32 = 2;
+pub const PANEL_SERVICE_POST_INSTALL: u32 = 3;
+pub const PANEL_SERVICE_PRE_INITIALIZE: u32 = 4;
+pub const PANEL_SERVICE_POST_INITIALIZE: u32 = 5;
+pub const PANEL_SERVICE_PRE_UPDATE: u32 = 6;
+pub const PANEL_SERVICE_POST_UPDATE: u32 = 7;
+pub const PANEL_SERVICE_PRE_GENERATE: u32 = 8;
+pub const PANEL_SERVICE_POST_GENERATE: u32 = 9;
+pub const PANEL_SERVICE_PRE_DRAW: u32 = 10;
+pub const PANEL_SERVICE_POST_DRAW: u32 = 11;
+pub const PANEL_SERVICE_PRE_KILL: u32 = 12;
+pub const PANEL_SERVICE_POST_KILL: u32 = 13;
+
+pub type SIMCONNECT_OBJECT_ID = DWORD;
+pub type SIMCONNECT_CLIENT_EVENT_ID = DWORD;
+pub type SIMCONNECT_NOTIFICATION_GROUP_ID = DWORD;
+pub type SIMCONNECT_INPUT_GROUP_ID = DWORD;
+pub type SIMCONNECT_DATA_DEFINITION_ID = DWORD;
+pub type SIMCONNECT_DATA_REQUEST_ID = DWORD;
+pub type SIMCONNECT_CLIENT_DATA_ID = DWORD;
+pub type SIMCONNECT_CLIENT_DATA_DEFINITION_ID = DWORD;
+pub type SIMCONNECT_DATA_SET_FLAG = DWORD;
+pub type SIMCONNECT_DATA_REQUEST_FLAG = DWORD;
+pub type SIMCONNECT_CLIENT_DATA_REQUEST_FLAG = DWORD;
+pub type SIMCONNECT_CLIENT_DATA_SET_FLAG = DWORD;
+pub type SIMCONNECT_CREATE_CLIENT_DATA_FLAG = DWORD;
+pub type SIMCONNECT_EVENT_FLAG = DWORD;
+pub type SIMCONNECT_STATE = c_uint;
+pub const SIMCONNECT_STATE_SIMCONNECT_STATE_OFF: SIMCONNECT_STATE = 0;
+pub const SIMCONNECT_STATE_SIMCONNECT_STATE_ON: SIMCONNECT_STATE = 1;
+
+pub const SIMCONNECT_UNUSED: DWORD = 0xFFFFFFFF;
+pub const SIMCONNECT_OBJECT_ID_USER: DWORD = 0;
+pub const SIMCONNECT_CAMERA_IGNORE_FIELD: f32 = f32::MAX;
+pub const SIMCONNECT_CLIENTDATA_MAX_SIZE: DWORD = 8192;
+pub const SIMCONNECT_GROUP_PRIORITY_HIGHEST: DWORD = 1;
+pub const SIMCONNECT_GROUP_PRIORITY_HIGHEST_MASKABLE: DWORD = 10000000;
+pub const SIMCONNECT_GROUP_PRIORITY_STANDARD: DWORD = 1900000000;
+pub const SIMCONNECT_GROUP_PRIORITY_DEFAULT: DWORD = 2000000000;
+pub const SIMCONNECT_GROUP_PRIORITY_LOWEST: DWORD = 4000000000;
+pub const MAX_METAR_LENGTH: DWORD = 2000;
+pub const MAX_THERMAL_SIZE: f32 = 100000.0;
+pub const MAX_THERMAL_RATE: f32 = 1000.0;
+pub const INITPOSITION_AIRSPEED_CRUISE: DWORD = 0xFFFFFFFF;
+pub const INITPOSITION_AIRSPEED_KEEP: DWORD = 0xFFFFFFFE;
+pub const SIMCONNECT_CLIENTDATATYPE_INT8: DWORD = 0xFFFFFFFF;
+pub const SIMCONNECT_CLIENTDATATYPE_INT16: DWORD = 0xFFFFFFFE;
+pub const SIMCONNECT_CLIENTDATATYPE_INT32: DWORD = 0xFFFFFFFD;
+pub const SIMCONNECT_CLIENTDATATYPE_INT64: DWORD = 0xFFFFFFFC;
+pub const SIMCONNECT_CLIENTDATATYPE_FLOAT32: DWORD = 0xFFFFFFFB;
+pub const SIMCONNECT_CLIENTDATATYPE_FLOAT64: DWORD = 0xFFFFFFFA;
+pub const SIMCONNECT_CLIENTDATAOFFSET_AUTO: DWORD = 0xFFFFFFFF;
+pub const SIMCONNECT_OPEN_CONFIGINDEX_LOCAL: c_int = -1;
+
+pub const SIMCONNECT_EVENT_FLAG_DEFAULT: DWORD = 0;
+pub const SIMCONNECT_EVENT_FLAG_FAST_REPEAT_TIMER: DWORD = 1;
+pub const SIMCONNECT_EVENT_FLAG_SLOW_REPEAT_TIMER: DWORD = 2;
+pub const SIMCONNECT_EVENT_FLAG_GROUPID_IS_PRIORITY: DWORD = 16;
+pub const SIMCONNECT_DATA_REQUEST_FLAG_DEFAULT: DWORD = 0;
+pub const SIMCONNECT_DATA_REQUEST_FLAG_CHANGED: DWORD = 1;
+pub const SIMCONNECT_DATA_REQUEST_FLAG_TAGGED: DWORD = 2;
+pub const SIMCONNECT_DATA_SET_FLAG_DEFAULT: DWORD = 0;
+pub const SIMCONNECT_DATA_SET_FLAG_TAGGED: DWORD = 1;
+pub const SIMCONNECT_CREATE_CLIENT_DATA_FLAG_DEFAULT: DWORD = 0;
+pub const SIMCONNECT_CREATE_CLIENT_DATA_FLAG_READ_ONLY: DWORD = 1;
+pub const SIMCONNECT_CLIENT_DATA_REQUEST_FLAG_DEFAULT: DWORD = 0;
+pub const SIMCONNECT_CLIENT_DATA_REQUEST_FLAG_CHANGED: DWORD = 1;
+pub const SIMCONNECT_CLIENT_DATA_REQUEST_FLAG_TAGGED: DWORD = 2;
+pub const SIMCONNECT_CLIENT_DATA_SET_FLAG_DEFAULT: DWORD = 0;
+pub const SIMCONNECT_CLIENT_DATA_SET_FLAG_TAGGED: DWORD = 1;
+pub const SIMCONNECT_VIEW_SYSTEM_EVENT_DATA_COCKPIT_2D: DWORD = 1;
+pub const SIMCONNECT_VIEW_SYSTEM_EVENT_DATA_COCKPIT_VIRTUAL: DWORD = 2;
+pub const SIMCONNECT_VIEW_SYSTEM_EVENT_DATA_ORTHOGONAL: DWORD = 4;
+pub const SIMCONNECT_SOUND_SYSTEM_EVENT_DATA_MASTER: DWORD = 1;
+pub const SIMCONNECT_RECV_EVENT_UNKNOWN_GROUP: DWORD = 0xFFFFFFFF;
+pub const SIMCONNECT_RECV_EXCEPTION_UNKNOWN_SENDID: DWORD = 0;
+pub const SIMCONNECT_RECV_EXCEPTION_UNKNOWN_INDEX: DWORD = 0;
+
+pub type SIMCONNECT_RECV_ID = c_uint;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NULL: SIMCONNECT_RECV_ID = 0;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION: SIMCONNECT_RECV_ID = 1;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_OPEN: SIMCONNECT_RECV_ID = 2;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_QUIT: SIMCONNECT_RECV_ID = 3;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT: SIMCONNECT_RECV_ID = 4;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_OBJECT_ADDREMOVE: SIMCONNECT_RECV_ID = 5;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FILENAME: SIMCONNECT_RECV_ID = 6;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FRAME: SIMCONNECT_RECV_ID = 7;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA: SIMCONNECT_RECV_ID = 8;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE: SIMCONNECT_RECV_ID = 9;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_WEATHER_OBSERVATION: SIMCONNECT_RECV_ID = 10;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CLOUD_STATE: SIMCONNECT_RECV_ID = 11;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ASSIGNED_OBJECT_ID: SIMCONNECT_RECV_ID = 12;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_RESERVED_KEY: SIMCONNECT_RECV_ID = 13;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CUSTOM_ACTION: SIMCONNECT_RECV_ID = 14;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SYSTEM_STATE: SIMCONNECT_RECV_ID = 15;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CLIENT_DATA: SIMCONNECT_RECV_ID = 16;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_WEATHER_MODE: SIMCONNECT_RECV_ID = 17;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_AIRPORT_LIST: SIMCONNECT_RECV_ID = 18;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_VOR_LIST: SIMCONNECT_RECV_ID = 19;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NDB_LIST: SIMCONNECT_RECV_ID = 20;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_WAYPOINT_LIST: SIMCONNECT_RECV_ID = 21;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_SERVER_STARTED: SIMCONNECT_RECV_ID = 22;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_CLIENT_STARTED: SIMCONNECT_RECV_ID = 23;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_SESSION_ENDED: SIMCONNECT_RECV_ID = 24;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_RACE_END: SIMCONNECT_RECV_ID = 25;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_RACE_LAP: SIMCONNECT_RECV_ID = 26;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_EX1: SIMCONNECT_RECV_ID = 27;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_FACILITY_DATA: SIMCONNECT_RECV_ID = 28;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_FACILITY_DATA_END: SIMCONNECT_RECV_ID = 29;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_FACILITY_MINIMAL_LIST: SIMCONNECT_RECV_ID = 30;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_JETWAY_DATA: SIMCONNECT_RECV_ID = 31;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CONTROLLERS_LIST: SIMCONNECT_RECV_ID = 32;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ACTION_CALLBACK: SIMCONNECT_RECV_ID = 33;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ENUMERATE_INPUT_EVENTS: SIMCONNECT_RECV_ID = 34;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_GET_INPUT_EVENT: SIMCONNECT_RECV_ID = 35;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SUBSCRIBE_INPUT_EVENT: SIMCONNECT_RECV_ID = 36;
+pub const SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ENUMERATE_INPUT_EVENT_PARAMS: SIMCONNECT_RECV_ID = 37;
+
+pub type SIMCONNECT_DATATYPE = c_uint;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INVALID: SIMCONNECT_DATATYPE = 0;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT32: SIMCONNECT_DATATYPE = 1;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT64: SIMCONNECT_DATATYPE = 2;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT32: SIMCONNECT_DATATYPE = 3;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64: SIMCONNECT_DATATYPE = 4;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING8: SIMCONNECT_DATATYPE = 5;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING32: SIMCONNECT_DATATYPE = 6;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING64: SIMCONNECT_DATATYPE = 7;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING128: SIMCONNECT_DATATYPE = 8;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING256: SIMCONNECT_DATATYPE = 9;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING260: SIMCONNECT_DATATYPE = 10;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRINGV: SIMCONNECT_DATATYPE = 11;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INITPOSITION: SIMCONNECT_DATATYPE = 12;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_MARKERSTATE: SIMCONNECT_DATATYPE = 13;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_WAYPOINT: SIMCONNECT_DATATYPE = 14;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_LATLONALT: SIMCONNECT_DATATYPE = 15;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_XYZ: SIMCONNECT_DATATYPE = 16;
+pub const SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_MAX: SIMCONNECT_DATATYPE = 17;
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum SIMCONNECT_EXCEPTION {
+    SIMCONNECT_EXCEPTION_NONE = 0,
+    SIMCONNECT_EXCEPTION_ERROR = 1,
+    SIMCONNECT_EXCEPTION_SIZE_MISMATCH = 2,
+    SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID = 3,
+    SIMCONNECT_EXCEPTION_UNOPENED = 4,
+    SIMCONNECT_EXCEPTION_VERSION_MISMATCH = 5,
+    SIMCONNECT_EXCEPTION_TOO_MANY_GROUPS = 6,
+    SIMCONNECT_EXCEPTION_NAME_UNRECOGNIZED = 7,
+    SIMCONNECT_EXCEPTION_TOO_MANY_EVENT_NAMES = 8,
+    SIMCONNECT_EXCEPTION_EVENT_ID_DUPLICATE = 9,
+    SIMCONNECT_EXCEPTION_TOO_MANY_MAPS = 10,
+    SIMCONNECT_EXCEPTION_TOO_MANY_OBJECTS = 11,
+    SIMCONNECT_EXCEPTION_TOO_MANY_REQUESTS = 12,
+    SIMCONNECT_EXCEPTION_WEATHER_INVALID_PORT = 13,
+    SIMCONNECT_EXCEPTION_WEATHER_INVALID_METAR = 14,
+    SIMCONNECT_EXCEPTION_WEATHER_UNABLE_TO_GET_OBSERVATION = 15,
+    SIMCONNECT_EXCEPTION_WEATHER_UNABLE_TO_CREATE_STATION = 16,
+    SIMCONNECT_EXCEPTION_WEATHER_UNABLE_TO_REMOVE_STATION = 17,
+    SIMCONNECT_EXCEPTION_INVALID_DATA_TYPE = 18,
+    SIMCONNECT_EXCEPTION_INVALID_DATA_SIZE = 19,
+    SIMCONNECT_EXCEPTION_DATA_ERROR = 20,
+    SIMCONNECT_EXCEPTION_INVALID_ARRAY = 21,
+    SIMCONNECT_EXCEPTION_CREATE_OBJECT_FAILED = 22,
+    SIMCONNECT_EXCEPTION_LOAD_FLIGHTPLAN_FAILED = 23,
+    SIMCONNECT_EXCEPTION_OPERATION_INVALID_FOR_OBJECT_TYPE = 24,
+    SIMCONNECT_EXCEPTION_ILLEGAL_OPERATION = 25,
+    SIMCONNECT_EXCEPTION_ALREADY_SUBSCRIBED = 26,
+    SIMCONNECT_EXCEPTION_INVALID_ENUM = 27,
+    SIMCONNECT_EXCEPTION_DEFINITION_ERROR = 28,
+    SIMCONNECT_EXCEPTION_DUPLICATE_ID = 29,
+    SIMCONNECT_EXCEPTION_DATUM_ID = 30,
+    SIMCONNECT_EXCEPTION_OUT_OF_BOUNDS = 31,
+    SIMCONNECT_EXCEPTION_ALREADY_CREATED = 32,
+    SIMCONNECT_EXCEPTION_OBJECT_OUTSIDE_REALITY_BUBBLE = 33,
+    SIMCONNECT_EXCEPTION_OBJECT_CONTAINER = 34,
+    SIMCONNECT_EXCEPTION_OBJECT_AI = 35,
+    SIMCONNECT_EXCEPTION_OBJECT_ATC = 36,
+    SIMCONNECT_EXCEPTION_OBJECT_SCHEDULE = 37,
+    SIMCONNECT_EXCEPTION_JETWAY_DATA = 38,
+    SIMCONNECT_EXCEPTION_ACTION_NOT_FOUND = 39,
+    SIMCONNECT_EXCEPTION_NOT_AN_ACTION = 40,
+    SIMCONNECT_EXCEPTION_INCORRECT_ACTION_PARAMS = 41,
+    SIMCONNECT_EXCEPTION_GET_INPUT_EVENT_FAILED = 42,
+    SIMCONNECT_EXCEPTION_SET_INPUT_EVENT_FAILED = 43,
+}
+
+pub type SIMCONNECT_SIMOBJECT_TYPE = c_uint;
+pub const SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_USER: SIMCONNECT_SIMOBJECT_TYPE = 0;
+pub const SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_ALL: SIMCONNECT_SIMOBJECT_TYPE = 1;
+pub const SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT: SIMCONNECT_SIMOBJECT_TYPE = 2;
+pub const SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_HELICOPTER: SIMCONNECT_SIMOBJECT_TYPE = 3;
+pub const SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_BOAT: SIMCONNECT_SIMOBJECT_TYPE = 4;
+pub const SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_GROUND: SIMCONNECT_SIMOBJECT_TYPE = 5;
+
+pub type SIMCONNECT_PERIOD = c_uint;
+pub const SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_NEVER: SIMCONNECT_PERIOD = 0;
+pub const SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_ONCE: SIMCONNECT_PERIOD = 1;
+pub const SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_VISUAL_FRAME: SIMCONNECT_PERIOD = 2;
+pub const SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_SIM_FRAME: SIMCONNECT_PERIOD = 3;
+pub const SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_SECOND: SIMCONNECT_PERIOD = 4;
+
+pub type SIMCONNECT_CLIENT_DATA_PERIOD = c_uint;
+pub const SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_NEVER: SIMCONNECT_CLIENT_DATA_PERIOD = 0;
+pub const SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_ONCE: SIMCONNECT_CLIENT_DATA_PERIOD = 1;
+pub const SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_VISUAL_FRAME: SIMCONNECT_CLIENT_DATA_PERIOD = 2;
+pub const SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_ON_SET: SIMCONNECT_CLIENT_DATA_PERIOD = 3;
+pub const SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_SECOND: SIMCONNECT_CLIENT_DATA_PERIOD = 4;
+
+pub type SIMCONNECT_MISSION_END = c_uint;
+pub type SIMCONNECT_TEXT_TYPE = c_uint;
+pub type SIMCONNECT_TEXT_RESULT = c_uint;
+pub type SIMCONNECT_WEATHER_MODE = c_uint;
+
+pub type SIMCONNECT_FACILITY_LIST_TYPE = c_uint;
+pub const SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_AIRPORT: SIMCONNECT_FACILITY_LIST_TYPE = 0;
+pub const SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_WAYPOINT: SIMCONNECT_FACILITY_LIST_TYPE = 1;
+pub const SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_NDB: SIMCONNECT_FACILITY_LIST_TYPE = 2;
+pub const SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_VOR: SIMCONNECT_FACILITY_LIST_TYPE = 3;
+pub const SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_COUNT: SIMCONNECT_FACILITY_LIST_TYPE = 4;
+
+pub type SIMCONNECT_FACILITY_DATA_TYPE = c_uint;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_AIRPORT: SIMCONNECT_FACILITY_DATA_TYPE = 0;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_RUNWAY: SIMCONNECT_FACILITY_DATA_TYPE = 1;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_START: SIMCONNECT_FACILITY_DATA_TYPE = 2;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_FREQUENCY: SIMCONNECT_FACILITY_DATA_TYPE = 3;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_HELIPAD: SIMCONNECT_FACILITY_DATA_TYPE = 4;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_APPROACH: SIMCONNECT_FACILITY_DATA_TYPE = 5;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_APPROACH_TRANSITION: SIMCONNECT_FACILITY_DATA_TYPE = 6;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_APPROACH_LEG: SIMCONNECT_FACILITY_DATA_TYPE = 7;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_FINAL_APPROACH_LEG: SIMCONNECT_FACILITY_DATA_TYPE = 8;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_MISSED_APPROACH_LEG: SIMCONNECT_FACILITY_DATA_TYPE = 9;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_DEPARTURE: SIMCONNECT_FACILITY_DATA_TYPE = 10;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_ARRIVAL: SIMCONNECT_FACILITY_DATA_TYPE = 11;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_RUNWAY_TRANSITION: SIMCONNECT_FACILITY_DATA_TYPE = 12;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_ENROUTE_TRANSITION: SIMCONNECT_FACILITY_DATA_TYPE = 13;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_TAXI_POINT: SIMCONNECT_FACILITY_DATA_TYPE = 14;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_TAXI_PARKING: SIMCONNECT_FACILITY_DATA_TYPE = 15;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_TAXI_PATH: SIMCONNECT_FACILITY_DATA_TYPE = 16;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_TAXI_NAME: SIMCONNECT_FACILITY_DATA_TYPE = 17;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_JETWAY: SIMCONNECT_FACILITY_DATA_TYPE = 18;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_VOR: SIMCONNECT_FACILITY_DATA_TYPE = 19;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_NDB: SIMCONNECT_FACILITY_DATA_TYPE = 20;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_WAYPOINT: SIMCONNECT_FACILITY_DATA_TYPE = 21;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_ROUTE: SIMCONNECT_FACILITY_DATA_TYPE = 22;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_PAVEMENT: SIMCONNECT_FACILITY_DATA_TYPE = 23;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_APPROACHLIGHTS: SIMCONNECT_FACILITY_DATA_TYPE = 24;
+pub const SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_VASI: SIMCONNECT_FACILITY_DATA_TYPE = 25;
+
+pub type SIMCONNECT_INPUT_EVENT_TYPE = c_uint;
+pub const SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_DOUBLE: SIMCONNECT_INPUT_EVENT_TYPE = 0;
+pub const SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_STRING: SIMCONNECT_INPUT_EVENT_TYPE = 1;
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV {
+    pub dwSize: DWORD,
+    pub dwVersion: DWORD,
+    pub dwID: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EXCEPTION {
+    pub _base: SIMCONNECT_RECV,
+    pub dwException: DWORD,
+    pub dwSendID: DWORD,
+    pub dwIndex: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_OPEN {
+    pub _base: SIMCONNECT_RECV,
+    pub szApplicationName: [c_char; 256],
+    pub dwApplicationVersionMajor: DWORD,
+    pub dwApplicationVersionMinor: DWORD,
+    pub dwApplicationBuildMajor: DWORD,
+    pub dwApplicationBuildMinor: DWORD,
+    pub dwSimConnectVersionMajor: DWORD,
+    pub dwSimConnectVersionMinor: DWORD,
+    pub dwSimConnectBuildMajor: DWORD,
+    pub dwSimConnectBuildMinor: DWORD,
+    pub dwReserved1: DWORD,
+    pub dwReserved2: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_QUIT {
+    pub _base: SIMCONNECT_RECV,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT {
+    pub _base: SIMCONNECT_RECV,
+    pub uGroupID: DWORD,
+    pub uEventID: DWORD,
+    pub dwData: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT_FILENAME {
+    pub _base: SIMCONNECT_RECV_EVENT,
+    pub szFileName: [c_char; 260],
+    pub dwFlags: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE {
+    pub _base: SIMCONNECT_RECV_EVENT,
+    pub eObjType: SIMCONNECT_SIMOBJECT_TYPE,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT_FRAME {
+    pub _base: SIMCONNECT_RECV_EVENT,
+    pub fFrameRate: f32,
+    pub fSimSpeed: f32,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT_EX1 {
+    pub _base: SIMCONNECT_RECV,
+    pub uGroupID: DWORD,
+    pub uEventID: DWORD,
+    pub dwData0: DWORD,
+    pub dwData1: DWORD,
+    pub dwData2: DWORD,
+    pub dwData3: DWORD,
+    pub dwData4: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT_MULTIPLAYER_SERVER_STARTED {
+    pub _base: SIMCONNECT_RECV_EVENT,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT_MULTIPLAYER_CLIENT_STARTED {
+    pub _base: SIMCONNECT_RECV_EVENT,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT_MULTIPLAYER_SESSION_ENDED {
+    pub _base: SIMCONNECT_RECV_EVENT,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _GUID {
+    pub Data1: DWORD,
+    pub Data2: u16,
+    pub Data3: u16,
+    pub Data4: [u8; 8],
+}
+pub type GUID = _GUID;
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_RACE_RESULT {
+    pub dwNumberOfRacers: DWORD,
+    pub MissionGUID: GUID,
+    pub szPlayerName: [c_char; 260],
+    pub szSessionType: [c_char; 260],
+    pub szAircraft: [c_char; 260],
+    pub szPlayerRole: [c_char; 260],
+    pub fTotalTime: f64,
+    pub fPenaltyTime: f64,
+    pub dwIsDisqualified: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT_RACE_END {
+    pub _base: SIMCONNECT_RECV_EVENT,
+    pub dwRacerNumber: DWORD,
+    pub RacerData: SIMCONNECT_DATA_RACE_RESULT,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_EVENT_RACE_LAP {
+    pub _base: SIMCONNECT_RECV_EVENT,
+    pub dwLapIndex: DWORD,
+    pub RacerData: SIMCONNECT_DATA_RACE_RESULT,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_SIMOBJECT_DATA {
+    pub _base: SIMCONNECT_RECV,
+    pub dwRequestID: DWORD,
+    pub dwObjectID: DWORD,
+    pub dwDefineID: DWORD,
+    pub dwFlags: DWORD,
+    pub dwentrynumber: DWORD,
+    pub dwoutof: DWORD,
+    pub dwDefineCount: DWORD,
+    pub dwData: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_SIMOBJECT_DATA_BYTYPE {
+    pub _base: SIMCONNECT_RECV_SIMOBJECT_DATA,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_CLIENT_DATA {
+    pub _base: SIMCONNECT_RECV_SIMOBJECT_DATA,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_ASSIGNED_OBJECT_ID {
+    pub _base: SIMCONNECT_RECV,
+    pub dwRequestID: DWORD,
+    pub dwObjectID: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_RESERVED_KEY {
+    pub _base: SIMCONNECT_RECV,
+    pub szChoiceReserved: [c_char; 30],
+    pub szReservedKey: [c_char; 50],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_SYSTEM_STATE {
+    pub _base: SIMCONNECT_RECV,
+    pub dwRequestID: DWORD,
+    pub dwInteger: DWORD,
+    pub fFloat: f32,
+    pub szString: [c_char; 260],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_FACILITIES_LIST {
+    pub _base: SIMCONNECT_RECV,
+    pub dwRequestID: DWORD,
+    pub dwArraySize: DWORD,
+    pub dwEntryNumber: DWORD,
+    pub dwOutOf: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_FACILITY_AIRPORT {
+    pub Ident: [c_char; 6],
+    pub Region: [c_char; 3],
+    pub Latitude: f64,
+    pub Longitude: f64,
+    pub Altitude: f64,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_AIRPORT_LIST {
+    pub _base: SIMCONNECT_RECV_FACILITIES_LIST,
+    pub rgData: [SIMCONNECT_DATA_FACILITY_AIRPORT; 1usize],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_FACILITY_WAYPOINT {
+    pub _base: SIMCONNECT_DATA_FACILITY_AIRPORT,
+    pub fMagVar: f32,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_WAYPOINT_LIST {
+    pub _base: SIMCONNECT_RECV_FACILITIES_LIST,
+    pub rgData: [SIMCONNECT_DATA_FACILITY_WAYPOINT; 1usize],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_FACILITY_NDB {
+    pub _base: SIMCONNECT_DATA_FACILITY_WAYPOINT,
+    pub fFrequency: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_NDB_LIST {
+    pub _base: SIMCONNECT_RECV_FACILITIES_LIST,
+    pub rgData: [SIMCONNECT_DATA_FACILITY_NDB; 1usize],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_FACILITY_VOR {
+    pub _base: SIMCONNECT_DATA_FACILITY_NDB,
+    pub Flags: DWORD,
+    pub fLocalizer: f32,
+    pub GlideLat: f64,
+    pub GlideLon: f64,
+    pub GlideAlt: f64,
+    pub fGlideSlopeAngle: f32,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_VOR_LIST {
+    pub _base: SIMCONNECT_RECV_FACILITIES_LIST,
+    pub rgData: [SIMCONNECT_DATA_FACILITY_VOR; 1usize],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_FACILITY_DATA {
+    pub _base: SIMCONNECT_RECV,
+    pub UserRequestId: DWORD,
+    pub UniqueRequestId: DWORD,
+    pub ParentUniqueRequestId: DWORD,
+    pub Type: DWORD,
+    pub IsListItem: DWORD,
+    pub ItemIndex: DWORD,
+    pub ListSize: DWORD,
+    pub Data: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_FACILITY_DATA_END {
+    pub _base: SIMCONNECT_RECV,
+    pub RequestId: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_LIST_TEMPLATE {
+    pub _base: SIMCONNECT_RECV,
+    pub dwRequestID: DWORD,
+    pub dwArraySize: DWORD,
+    pub dwEntryNumber: DWORD,
+    pub dwOutOf: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_INPUT_EVENT_DESCRIPTOR {
+    pub Name: [c_char; 64],
+    pub Hash: UINT64,
+    pub eType: SIMCONNECT_INPUT_EVENT_TYPE,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_ENUMERATE_INPUT_EVENTS {
+    pub _base: SIMCONNECT_RECV_LIST_TEMPLATE,
+    pub rgData: [SIMCONNECT_INPUT_EVENT_DESCRIPTOR; 1usize],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_GET_INPUT_EVENT {
+    pub _base: SIMCONNECT_RECV,
+    pub dwRequestID: DWORD,
+    pub eType: SIMCONNECT_INPUT_EVENT_TYPE,
+    pub Value: [DWORD; 1usize],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_SUBSCRIBE_INPUT_EVENT {
+    pub _base: SIMCONNECT_RECV,
+    pub Hash: UINT64,
+    pub eType: SIMCONNECT_INPUT_EVENT_TYPE,
+    pub Value: [DWORD; 1usize],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_RECV_ENUMERATE_INPUT_EVENT_PARAMS {
+    pub _base: SIMCONNECT_RECV,
+    pub Hash: UINT64,
+    pub Value: [c_char; 260],
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_INITPOSITION {
+    pub Latitude: f64,
+    pub Longitude: f64,
+    pub Altitude: f64,
+    pub Pitch: f64,
+    pub Bank: f64,
+    pub Heading: f64,
+    pub OnGround: DWORD,
+    pub Airspeed: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_MARKERSTATE {
+    pub szMarkerName: [c_char; 64],
+    pub dwMarkerState: DWORD,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_WAYPOINT {
+    pub Latitude: f64,
+    pub Longitude: f64,
+    pub Altitude: f64,
+    pub Flags: DWORD,
+    pub ktsSpeed: f64,
+    pub percentThrottle: f64,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_LATLONALT {
+    pub Latitude: f64,
+    pub Longitude: f64,
+    pub Altitude: f64,
+}
+
+#[repr(C, packed)]
+#[derive(Debug, Copy, Clone)]
+pub struct SIMCONNECT_DATA_XYZ {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+pub type DispatchProc = ::std::option::Option<
+    unsafe extern "C" fn(pData: *mut SIMCONNECT_RECV, cbData: DWORD, pContext: *mut c_void),
+>;
+
+pub const PAUSE_STATE_FLAG_OFF: DWORD = 0;
+pub const PAUSE_STATE_FLAG_PAUSE: DWORD = 1;
+pub const PAUSE_STATE_FLAG_PAUSE_WITH_SOUND: DWORD = 2;
+pub const PAUSE_STATE_FLAG_ACTIVE_PAUSE: DWORD = 4;
+pub const PAUSE_STATE_FLAG_SIM_PAUSE: DWORD = 8;
+pub const SIMCONNECT_RECV_ID_VOR_LIST_HAS_NAV_SIGNAL: DWORD = 1;
+pub const SIMCONNECT_RECV_ID_VOR_LIST_HAS_LOCALIZER: DWORD = 2;
+pub const SIMCONNECT_RECV_ID_VOR_LIST_HAS_GLIDE_SLOPE: DWORD = 4;
+pub const SIMCONNECT_RECV_ID_VOR_LIST_HAS_DME: DWORD = 8;
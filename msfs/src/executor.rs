@@ -1,5 +1,7 @@
 use futures::{Future, channel::mpsc};
+use std::cell::Cell;
 use std::pin::Pin;
+use std::rc::Rc;
 use std::task::Poll;
 
 pub(crate) type ExecutorFuture =
@@ -9,12 +11,42 @@ pub struct Executor<I, T> {
     pub handle: fn(I) -> ExecutorFuture,
     pub future: Option<ExecutorFuture>,
     pub tx: Option<mpsc::Sender<T>>,
+    pub wake: Option<Rc<Wake>>,
+}
+
+/// Whether an executor is polling its future, shared with whatever wakes it.
+/// A wake during a poll, such as from a `call_dispatch` made by the future,
+/// must not poll the future again from inside itself, so it is only recorded
+/// and the executor polls again once the current poll returns.
+#[derive(Default)]
+pub struct Wake {
+    polling: Cell<bool>,
+    woken: Cell<bool>,
+}
+
+impl Wake {
+    /// Poll the future of `executor` now, or once its current poll returns.
+    ///
+    /// # Safety
+    /// `executor` must be the executor this was created by, and valid.
+    #[cfg(not(vendored_bindings))]
+    pub(crate) unsafe fn wake<I, T>(
+        &self,
+        executor: *mut Executor<I, T>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.polling.get() {
+            self.woken.set(true);
+            Ok(())
+        } else {
+            unsafe { (*executor).poll() }
+        }
+    }
 }
 
 impl<I, T> Executor<I, T> {
     pub(crate) fn start(
         &mut self,
-        get_input: Box<dyn Fn(mpsc::Receiver<T>) -> I>,
+        get_input: Box<dyn Fn(mpsc::Receiver<T>, Rc<Wake>) -> I>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         if self.future.is_some() {
             eprintln!("MSFS-RS: RESTARTING EXECUTOR");
@@ -24,21 +56,16 @@ impl<I, T> Executor<I, T> {
 
         let (tx, rx) = mpsc::channel(1);
         self.tx = Some(tx);
-        let input = get_input(rx);
+        let wake = Rc::new(Wake::default());
+        self.wake = Some(wake.clone());
+        let input = get_input(rx, wake);
 
-        let mut f = (self.handle)(input);
-
-        let mut context = std::task::Context::from_waker(futures::task::noop_waker_ref());
-        match match f.as_mut().poll(&mut context) {
-            Poll::Pending => Ok(()),
-            Poll::Ready(v) => v,
-        } {
-            Ok(()) => {
-                self.future = Some(f);
-                Ok(())
-            }
-            e => e,
+        self.future = Some((self.handle)(input));
+        let result = self.poll();
+        if result.is_err() {
+            self.future.take();
         }
+        result
     }
 
     pub(crate) fn send(&mut self, data: Option<T>) -> Result<(), Box<dyn std::error::Error>> {
@@ -47,10 +74,25 @@ impl<I, T> Executor<I, T> {
         } else {
             self.tx.take();
         }
+        self.poll()
+    }
+
+    /// Poll the future without sending it anything, for when something else
+    /// it may be waiting on became ready. It is polled again for as long as it
+    /// is woken while polling.
+    pub(crate) fn poll(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let wake = self.wake.clone().unwrap();
+        wake.polling.set(true);
         let mut context = std::task::Context::from_waker(futures::task::noop_waker_ref());
-        match self.future.as_mut().unwrap().as_mut().poll(&mut context) {
-            Poll::Pending => Ok(()),
-            Poll::Ready(v) => v,
-        }
+        let result = loop {
+            wake.woken.set(false);
+            match self.future.as_mut().unwrap().as_mut().poll(&mut context) {
+                Poll::Pending if wake.woken.get() => {}
+                Poll::Pending => break Ok(()),
+                Poll::Ready(v) => break v,
+            }
+        };
+        wake.polling.set(false);
+        result
     }
 }
@@ -16,6 +16,10 @@
 //! If your MSFS SDK is not installed to `C:\MSFS SDK` you will need to set the
 //! `MSFS_SDK` env variable to the correct path.
 //!
+//! The SimConnect library from the SDK is only linked on Windows. On other
+//! platforms, external applications can use `sim_connect::TcpBackend` to
+//! reach a sim which accepts remote SimConnect clients.
+//!
 //! ## Known Issues and Work-Arounds
 //!
 //! ### Missing various exports
@@ -35,16 +39,16 @@ pub mod sys;
 
 pub use msfs::*;
 
-#[cfg(any(target_arch = "wasm32", doc))]
+#[cfg(any(target_arch = "wasm32", all(doc, not(vendored_bindings))))]
 pub mod legacy;
 
-#[cfg(any(target_arch = "wasm32", doc))]
+#[cfg(any(target_arch = "wasm32", all(doc, not(vendored_bindings))))]
 pub mod nvg;
 
-#[cfg(any(target_arch = "wasm32", doc))]
+#[cfg(any(target_arch = "wasm32", all(doc, not(vendored_bindings))))]
 pub mod network;
 
-#[cfg(any(target_arch = "wasm32", doc))]
+#[cfg(any(target_arch = "wasm32", all(doc, not(vendored_bindings))))]
 pub mod commbus;
 
 #[doc(hidden)]
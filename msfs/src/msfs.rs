@@ -17,7 +17,9 @@ impl sys::sGaugeDrawData {
     }
 }
 
-use crate::sim_connect::{SimConnect, SimConnectRecv};
+use crate::sim_connect::OwnedSimConnectRecv;
+#[cfg(not(vendored_bindings))]
+use crate::sim_connect::SimConnect;
 pub use msfs_derive::{gauge, standalone_module};
 
 /// Used in Gauges to dispatch lifetime events, mouse events, and SimConnect events.
@@ -32,7 +34,7 @@ pub enum MSFSEvent<'a> {
     PostDraw(&'a sys::sGaugeDrawData),
     PreKill,
     Mouse { x: f32, y: f32, flags: u32 },
-    SimConnect(SimConnectRecv<'a>),
+    SimConnect(OwnedSimConnectRecv),
 }
 
 /// Internal helper function to allow usage of mutable globals.
@@ -44,33 +46,39 @@ pub unsafe fn wrap_executor<E, T>(executor: *mut E, handle: impl FnOnce(&mut E)
 }
 
 /// Gauge
+// Without the SDK there is no SimConnect library to open a session with.
+#[cfg_attr(vendored_bindings, allow(dead_code))]
 pub struct Gauge {
     executor: *mut GaugeExecutor,
+    wake: std::rc::Rc<executor::Wake>,
     rx: futures::channel::mpsc::Receiver<MSFSEvent<'static>>,
 }
 
 impl Gauge {
     /// Send a request to the Microsoft Flight Simulator server to open up communications with a new client.
+    #[cfg(not(vendored_bindings))]
     pub fn open_simconnect<'a>(
         &self,
         name: &str,
     ) -> Result<std::pin::Pin<Box<crate::sim_connect::SimConnect<'a>>>, Box<dyn std::error::Error>>
     {
         let executor = self.executor;
-        let sim = crate::sim_connect::SimConnect::open(name, move |_sim, recv| {
+        let mut sim = crate::sim_connect::SimConnect::open(name, move |_sim, recv| {
             let executor = unsafe { &mut *executor };
-            let recv =
-                unsafe { std::mem::transmute::<SimConnectRecv<'_>, SimConnectRecv<'static>>(recv) };
             executor
                 .executor
-                .send(Some(MSFSEvent::SimConnect(recv)))
+                .send(Some(MSFSEvent::SimConnect(recv.into_owned())))
                 .unwrap();
         })?;
+        let wake = self.wake.clone();
+        sim.set_wake(move || {
+            unsafe { wake.wake(&raw mut (*executor).executor) }.unwrap();
+        });
         Ok(sim)
     }
 
     /// Create a NanoVG rendering context. See `Context` for more details.
-    #[cfg(any(target_arch = "wasm32", doc))]
+    #[cfg(any(target_arch = "wasm32", all(doc, not(vendored_bindings))))]
     pub fn create_nanovg(&self) -> Option<crate::nvg::Context> {
         crate::nvg::Context::create(unsafe { (*self.executor).fs_ctx.unwrap() })
     }
@@ -101,7 +109,7 @@ impl GaugeExecutor {
                 let executor = self as *mut GaugeExecutor;
                 self.fs_ctx = Some(ctx);
                 self.executor
-                    .start(Box::new(move |rx| Gauge { executor, rx }))
+                    .start(Box::new(move |rx, wake| Gauge { executor, wake, rx }))
                     .is_ok()
             }
             sys::PANEL_SERVICE_POST_KILL => self.executor.send(None).is_ok(),
@@ -136,29 +144,36 @@ impl GaugeExecutor {
     }
 }
 
+#[cfg_attr(vendored_bindings, allow(dead_code))]
 pub struct StandaloneModule {
     executor: *mut StandaloneModuleExecutor,
-    rx: futures::channel::mpsc::Receiver<SimConnectRecv<'static>>,
+    wake: std::rc::Rc<executor::Wake>,
+    rx: futures::channel::mpsc::Receiver<OwnedSimConnectRecv>,
 }
 
 impl StandaloneModule {
     /// Send a request to the Microsoft Flight Simulator server to open up communications with a new client.
+    #[cfg(not(vendored_bindings))]
     pub fn open_simconnect<'a>(
         &self,
         name: &str,
     ) -> Result<std::pin::Pin<Box<SimConnect<'a>>>, Box<dyn std::error::Error>> {
         let executor = self.executor;
-        let sim = SimConnect::open(name, move |_sim, recv| {
+        let mut sim = SimConnect::open(name, move |_sim, recv| {
             let executor = unsafe { &mut *executor };
-            let recv =
-                unsafe { std::mem::transmute::<SimConnectRecv<'_>, SimConnectRecv<'static>>(recv) };
-            executor.executor.send(Some(recv)).unwrap();
+            executor.executor.send(Some(recv.into_owned())).unwrap();
         })?;
+        let wake = self.wake.clone();
+        sim.set_wake(move || {
+            unsafe { wake.wake(&raw mut (*executor).executor) }.unwrap();
+        });
         Ok(sim)
     }
 
     /// Consume the next event from MSFS.
-    pub fn next_event(&mut self) -> impl futures::Future<Output = Option<SimConnectRecv<'_>>> + '_ {
+    pub fn next_event(
+        &mut self,
+    ) -> impl futures::Future<Output = Option<OwnedSimConnectRecv>> + '_ {
         use futures::stream::StreamExt;
         async move { self.rx.next().await }
     }
@@ -166,7 +181,7 @@ impl StandaloneModule {
 
 #[doc(hidden)]
 pub struct StandaloneModuleExecutor {
-    pub executor: executor::Executor<StandaloneModule, SimConnectRecv<'static>>,
+    pub executor: executor::Executor<StandaloneModule, OwnedSimConnectRecv>,
 }
 
 #[doc(hidden)]
@@ -174,7 +189,11 @@ impl StandaloneModuleExecutor {
     fn start(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let executor = self as *mut StandaloneModuleExecutor;
         self.executor
-            .start(Box::new(move |rx| StandaloneModule { executor, rx }))
+            .start(Box::new(move |rx, wake| StandaloneModule {
+                executor,
+                wake,
+                rx,
+            }))
     }
 
     pub fn handle_init(&mut self) {
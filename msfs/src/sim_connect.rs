@@ -1,28 +1,181 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::sys;
+use exception::Sender;
 use std::any::TypeId;
 use std::collections::HashMap;
 use std::pin::Pin;
-
-pub use sys::SIMCONNECT_OBJECT_ID_USER;
+use subscription::Routes;
+
+mod ai;
+mod backend;
+mod data;
+mod dynamic;
+mod exception;
+mod facilities;
+mod facility_data;
+#[cfg(not(vendored_bindings))]
+mod ffi;
+mod handle;
+mod id;
+mod input_event;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+mod recv;
+mod subscription;
+mod system_event;
+mod system_state;
+#[cfg(any(not(target_arch = "wasm32"), doc))]
+mod tcp;
+mod tracker;
+
+pub use ai::{AiObject, AiObjectRequest};
+pub use backend::{Backend, Call};
+#[doc(hidden)]
+pub use data::{
+    DataField, read_client_bool, read_converted, read_converted_array, write_client_bool,
+    write_converted, write_converted_array,
+};
+pub use data::{
+    DecodeError, FixedString, String8, String32, String64, String128, String256, String260,
+};
+pub use dynamic::{DataType, DynamicDataDefinition, Value};
+pub use exception::{Exception, ExceptionKind, SentCall};
+pub use facilities::{
+    Airport, FacilitiesList, Facility, FacilityEvent, FacilitySubscription, Ndb, Vor, Waypoint,
+};
+#[doc(hidden)]
+pub use facility_data::FacilityBlock;
+pub use facility_data::FacilityDataRequest;
+#[cfg(not(vendored_bindings))]
+pub use ffi::FfiBackend;
+pub use handle::RequestHandle;
+pub use id::{
+    ClientDataId, ClientEventId, DefineId, InputEventHash, InputGroupId, NotificationGroupId,
+    ObjectId, RequestId, SystemEventId,
+};
+pub use input_event::{
+    InputEvent, InputEventDescriptor, InputEventList, InputEventParamsRequest, InputEventRequest,
+    InputEventSubscription, InputEventType, InputEventValue,
+};
+#[cfg(any(test, feature = "mock"))]
+pub use mock::{MockBackend, MockFacilityBlock, MockServer, TransmittedEvent};
+pub use recv::{OwnedRecv, Recv};
+pub use subscription::{
+    ChangeSet, RequestError, Snapshot, SnapshotError, Subscription, TaggedSubscription,
+};
+pub use system_event::{PauseFlags, RaceResult, SystemEvent, SystemEventKind, SystemEvents};
+pub use system_state::{SystemState, SystemStateQuery, SystemStateRequest};
+#[cfg(any(not(target_arch = "wasm32"), doc))]
+pub use tcp::TcpBackend;
+pub use tracker::{SimObjectTracker, TrackerEvent};
+
+/// The user's aircraft, the same as [`ObjectId::USER`].
+pub const SIMCONNECT_OBJECT_ID_USER: ObjectId = ObjectId::USER;
 
 pub use msfs_derive::sim_connect_client_data_definition as client_data_definition;
 pub use msfs_derive::sim_connect_data_definition as data_definition;
+pub use msfs_derive::sim_connect_facility_definition as facility_definition;
 
 pub type DataXYZ = sys::SIMCONNECT_DATA_XYZ;
 pub type InitPosition = sys::SIMCONNECT_DATA_INITPOSITION;
+pub type DataLatLonAlt = sys::SIMCONNECT_DATA_LATLONALT;
+pub type DataWaypoint = sys::SIMCONNECT_DATA_WAYPOINT;
+pub type DataMarkerState = sys::SIMCONNECT_DATA_MARKERSTATE;
 
 /// A trait implemented by the `data_definition` attribute.
 pub trait DataDefinition: 'static {
     #[doc(hidden)]
-    const DEFINITIONS: &'static [(&'static str, &'static str, f32, sys::SIMCONNECT_DATATYPE)];
+    fn definitions() -> Vec<(String, &'static str, f32, sys::SIMCONNECT_DATATYPE)>;
+
+    /// The number of simvars in the definition, as sent in `dwDefineCount`.
+    #[doc(hidden)]
+    const DATUM_COUNT: usize;
+
+    /// The name of each field and the number of simvars it has, in order.
+    #[doc(hidden)]
+    const FIELDS: &'static [(&'static str, usize)];
+
+    /// Whether any simvar name still has a `{}` for the index it is nested at.
+    #[doc(hidden)]
+    const INDEXED: bool;
+
+    #[doc(hidden)]
+    fn decode(data: &[u8]) -> std::result::Result<Self, DecodeError>
+    where
+        Self: Sized;
+
+    #[doc(hidden)]
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// Add the simvars of a data definition nested in another, with any `{}` in
+/// their names replaced by `index` and `prefix` prepended.
+#[doc(hidden)]
+pub fn nest_definitions<T: DataDefinition>(
+    definitions: &mut Vec<(String, &'static str, f32, sys::SIMCONNECT_DATATYPE)>,
+    prefix: &str,
+    index: Option<usize>,
+) {
+    for (name, unit, epsilon, datatype) in T::definitions() {
+        let name = match index {
+            Some(index) => name.replace("{}", &index.to_string()),
+            None => name,
+        };
+        definitions.push((format!("{prefix}{name}"), unit, epsilon, datatype));
+    }
 }
 
 /// A trait implemented by the `client_data_definition` attribute.
 pub trait ClientDataDefinition: 'static {
     #[doc(hidden)]
     fn get_definitions() -> Vec<(usize, usize, f32)>;
+
+    /// Read the struct from its bytes in the client data area, field by field.
+    #[doc(hidden)]
+    fn decode(data: &[u8]) -> std::result::Result<Self, DecodeError>
+    where
+        Self: Sized;
+
+    /// Append the bytes of the struct, with any padding zeroed.
+    #[doc(hidden)]
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// The offset, size and epsilon of each datum a client data definition is
+/// added with, including one covering any padding at the end of the struct.
+fn client_data_datums<T: ClientDataDefinition>() -> Vec<(usize, usize, f32)> {
+    let mut datums = T::get_definitions();
+    // Rust may reorder fields, so padding has to be calculated as min of
+    // all fields instead of the last field.
+    let padding = datums
+        .iter()
+        .map(|&(offset, size, _)| std::mem::size_of::<T>() - (offset + size))
+        .min();
+    if let Some(padding) = padding
+        && padding > 0
+    {
+        datums.push((std::mem::size_of::<T>() - padding, padding, 0.0));
+    }
+    datums
+}
+
+/// A trait implemented by the `facility_definition` attribute.
+pub trait FacilityDefinition: Sized + 'static {
+    /// The name the block is opened with, unless a field names it otherwise.
+    #[doc(hidden)]
+    const BLOCK: &'static str;
+
+    #[doc(hidden)]
+    const DATA_TYPE: sys::SIMCONNECT_FACILITY_DATA_TYPE;
+
+    /// Add the fields of the block, opened as `block`, along with those of
+    /// the blocks nested in it.
+    #[doc(hidden)]
+    fn definitions(block: &str, definitions: &mut Vec<String>);
+
+    #[doc(hidden)]
+    fn assemble(block: &mut FacilityBlock) -> std::result::Result<Self, DecodeError>;
 }
 
 /// Rusty HRESULT wrapper.
@@ -37,23 +190,31 @@ impl std::fmt::Display for HResult {
 impl std::error::Error for HResult {}
 
 pub type Result<T> = std::result::Result<T, HResult>;
-#[inline(always)]
-fn map_err(result: sys::HRESULT) -> Result<()> {
-    if result >= 0 {
-        Ok(())
-    } else {
-        Err(HResult(result))
-    }
-}
+
+/// `E_INVALIDARG`, for input events named before they were enumerated,
+/// dynamic data definitions requested before they were added, tagged
+/// subscriptions to simvars of types which can't be received, and second
+/// subscriptions to a type of facility.
+const E_INVALIDARG: sys::HRESULT = 0x8007_0057_u32 as sys::HRESULT;
 
 type SimConnectCallback<'a> = dyn FnMut(&mut SimConnect, SimConnectRecv) + 'a;
 
 /// A SimConnect session. This provides access to data within the MSFS sim.
 pub struct SimConnect<'a> {
-    handle: sys::HANDLE,
+    sender: Sender<'a>,
     callback: Box<SimConnectCallback<'a>>,
+    routes: Routes,
+    wake: Option<Box<dyn FnMut() + 'a>>,
     data_definitions: HashMap<TypeId, sys::SIMCONNECT_DATA_DEFINITION_ID>,
     client_data_definitions: HashMap<TypeId, sys::SIMCONNECT_CLIENT_DATA_DEFINITION_ID>,
+    /// Facility definitions share their IDs with data definitions.
+    facility_definitions: HashMap<TypeId, sys::SIMCONNECT_DATA_DEFINITION_ID>,
+    /// The input events enumerated so far, by name.
+    input_events: HashMap<String, InputEventDescriptor>,
+    define_id_counter: sys::DWORD,
+    client_data_define_id_counter: sys::DWORD,
+    /// Client events and system events share one ID space in SimConnect, so
+    /// that the events received for them can be told apart.
     event_id_counter: sys::DWORD,
     client_data_id_counter: sys::DWORD,
 }
@@ -66,70 +227,123 @@ impl std::fmt::Debug for SimConnect<'_> {
 
 impl<'a> SimConnect<'a> {
     /// Send a request to the Microsoft Flight Simulator server to open up communications with a new client.
+    /// Only available in WASM modules and on Windows, where the SimConnect library is linked.
+    #[cfg(not(vendored_bindings))]
     pub fn open<F>(name: &str, callback: F) -> Result<Pin<Box<SimConnect<'a>>>>
     where
         F: FnMut(&mut SimConnect, SimConnectRecv) + 'a,
     {
-        unsafe {
-            let mut handle = 0;
-            let name = std::ffi::CString::new(name).unwrap();
-            map_err(sys::SimConnect_Open(
-                &mut handle,
-                name.as_ptr(),
-                std::ptr::null_mut(),
-                0,
-                0,
-                0,
-            ))?;
-            debug_assert!(handle != 0);
-            let mut sim = Box::pin(SimConnect {
-                handle,
-                callback: Box::new(callback),
-                data_definitions: HashMap::new(),
-                client_data_definitions: HashMap::new(),
-                event_id_counter: 0,
-                client_data_id_counter: 0,
-            });
-            sim.call_dispatch()?;
-            Ok(sim)
-        }
+        SimConnect::open_with_backend(FfiBackend::open(name)?, callback)
+    }
+
+    /// Open up communications with the sim through the given `backend`, such
+    /// as a [`TcpBackend`]. Other platforms have no SimConnect library, so
+    /// this is the only way to open a session there.
+    pub fn open_with_backend<B, F>(backend: B, callback: F) -> Result<Pin<Box<SimConnect<'a>>>>
+    where
+        B: Backend + 'a,
+        F: FnMut(&mut SimConnect, SimConnectRecv) + 'a,
+    {
+        let mut sim = Box::pin(SimConnect {
+            sender: Sender::new(Box::new(backend)),
+            callback: Box::new(callback),
+            routes: Routes::default(),
+            wake: None,
+            data_definitions: HashMap::new(),
+            client_data_definitions: HashMap::new(),
+            facility_definitions: HashMap::new(),
+            input_events: HashMap::new(),
+            define_id_counter: 0,
+            client_data_define_id_counter: 0,
+            event_id_counter: 0,
+            client_data_id_counter: 0,
+        });
+        sim.call_dispatch()?;
+        Ok(sim)
     }
 
     /// Used to process the next SimConnect message received. Only needed when not using the gauge API.
     pub fn call_dispatch(&mut self) -> Result<()> {
+        self.update_routes()?;
+        let context = self as *mut SimConnect as *mut std::ffi::c_void;
         unsafe {
-            map_err(sys::SimConnect_CallDispatch(
-                self.handle,
-                Some(dispatch_cb),
-                self as *mut SimConnect as *mut std::ffi::c_void,
-            ))
+            self.sender
+                .backend
+                .call_dispatch(Some(dispatch_cb), context)
+        }
+    }
+
+    /// Set a function to be called whenever a message is routed to a handle,
+    /// such as a [`Subscription`], instead of the callback. This lets
+    /// executors poll the futures waiting on those handles.
+    #[cfg(not(vendored_bindings))]
+    pub(crate) fn set_wake(&mut self, wake: impl FnMut() + 'a) {
+        self.wake = Some(Box::new(wake));
+    }
+
+    /// Stop the requests whose handles were dropped, end those whose timeout
+    /// has passed, and send again those which are repeated.
+    fn update_routes(&mut self) -> Result<()> {
+        let now = std::time::Instant::now();
+        if self.routes.expire(now)
+            && let Some(wake) = &mut self.wake
+        {
+            wake();
+        }
+        for stop in self.routes.take_closed() {
+            self.sender.method("drop");
+            self.sender.call(stop)?;
         }
+        for (method, call) in self.routes.take_due(now) {
+            self.sender.method(method);
+            self.sender.call(call)?;
+        }
+        Ok(())
     }
 
-    fn get_define_id<T: DataDefinition>(&mut self) -> Result<sys::SIMCONNECT_DATA_DEFINITION_ID> {
-        let handle = self.handle;
-        SimConnect::get_id::<T, _, _>(
+    fn get_define_id<T: DataDefinition>(&mut self) -> Result<DefineId> {
+        let sender = &mut self.sender;
+        SimConnect::get_id::<T, _>(
             &mut self.data_definitions,
+            &mut self.define_id_counter,
             |define_id: sys::SIMCONNECT_DATA_DEFINITION_ID| {
-                /*
-                unsafe {
-                    map_err(sys::SimConnect_ClearDataDefinition(handle, define_id))?;
+                // Datum IDs are the index of each simvar, for tagged requests.
+                for (datum_id, (datum_name, units_type, epsilon, datatype)) in
+                    T::definitions().into_iter().enumerate()
+                {
+                    let datum_name = std::ffi::CString::new(datum_name).unwrap();
+                    let units_type = std::ffi::CString::new(units_type).unwrap();
+                    sender.call(Call::AddToDataDefinition {
+                        define_id,
+                        datum_name: &datum_name,
+                        units_name: &units_type,
+                        datum_type: datatype,
+                        epsilon,
+                        datum_id: datum_id as sys::DWORD,
+                    })?;
                 }
-                */
-                for (datum_name, units_type, epsilon, datatype) in T::DEFINITIONS {
-                    let datum_name = std::ffi::CString::new(*datum_name).unwrap();
-                    let units_type = std::ffi::CString::new(*units_type).unwrap();
-                    unsafe {
-                        map_err(sys::SimConnect_AddToDataDefinition(
-                            handle,
-                            define_id,
-                            datum_name.as_ptr(),
-                            units_type.as_ptr(),
-                            *datatype,
-                            *epsilon,
-                            sys::SIMCONNECT_UNUSED,
-                        ))?;
-                    }
+                Ok(())
+            },
+        )
+        .map(DefineId)
+    }
+
+    fn get_facility_define_id<T: FacilityDefinition>(
+        &mut self,
+    ) -> Result<sys::SIMCONNECT_DATA_DEFINITION_ID> {
+        let sender = &mut self.sender;
+        SimConnect::get_id::<T, _>(
+            &mut self.facility_definitions,
+            &mut self.define_id_counter,
+            |define_id| {
+                let mut definitions = Vec::new();
+                T::definitions(T::BLOCK, &mut definitions);
+                for field_name in definitions {
+                    let field_name = std::ffi::CString::new(field_name).unwrap();
+                    sender.call(Call::AddToFacilityDefinition {
+                        define_id,
+                        field_name: &field_name,
+                    })?;
                 }
                 Ok(())
             },
@@ -139,57 +353,42 @@ impl<'a> SimConnect<'a> {
     fn get_client_data_define_id<T: ClientDataDefinition>(
         &mut self,
     ) -> Result<sys::SIMCONNECT_CLIENT_DATA_DEFINITION_ID> {
-        let handle = self.handle;
-        SimConnect::get_id::<T, _, _>(&mut self.client_data_definitions, |define_id| {
-            /*
-            unsafe {
-                map_err(sys::SimConnect_ClearClientDataDefinition(handle, define_id))?;
-            }
-            */
-
-            // Rust may reorder fields, so padding has to be calculated as min of
-            // all fields instead of the last field.
-            let mut padding = usize::MAX;
-            for (offset, size, epsilon) in T::get_definitions() {
-                padding = padding.min(std::mem::size_of::<T>() - (offset + size));
-                unsafe {
-                    map_err(sys::SimConnect_AddToClientDataDefinition(
-                        handle,
+        let sender = &mut self.sender;
+        SimConnect::get_id::<T, _>(
+            &mut self.client_data_definitions,
+            &mut self.client_data_define_id_counter,
+            |define_id| {
+                /*
+                sender.call(Call::ClearClientDataDefinition { define_id })?;
+                */
+
+                for (offset, size, epsilon) in client_data_datums::<T>() {
+                    sender.call(Call::AddToClientDataDefinition {
                         define_id,
-                        offset as sys::DWORD,
-                        size as sys::DWORD,
+                        offset: offset as sys::DWORD,
+                        size_or_type: size as sys::DWORD,
                         epsilon,
-                        sys::SIMCONNECT_UNUSED,
-                    ))?;
-                }
-            }
-            if padding > 0 && padding != usize::MAX {
-                unsafe {
-                    map_err(sys::SimConnect_AddToClientDataDefinition(
-                        handle,
-                        define_id,
-                        (std::mem::size_of::<T>() - padding) as sys::DWORD,
-                        padding as sys::DWORD,
-                        0.0,
-                        sys::SIMCONNECT_UNUSED,
-                    ))?;
+                        datum_id: sys::SIMCONNECT_UNUSED,
+                    })?;
                 }
-            }
-            Ok(())
-        })
+                Ok(())
+            },
+        )
     }
 
-    fn get_id<T: 'static, U: std::convert::TryFrom<usize> + Copy, F: Fn(U) -> Result<()>>(
-        map: &mut HashMap<TypeId, U>,
-        insert_fn: F,
-    ) -> Result<U> {
+    fn get_id<T: 'static, F: FnMut(sys::DWORD) -> Result<()>>(
+        map: &mut HashMap<TypeId, sys::DWORD>,
+        counter: &mut sys::DWORD,
+        mut insert_fn: F,
+    ) -> Result<sys::DWORD> {
         let key = TypeId::of::<T>();
-        let maybe_id = U::try_from(map.len()).unwrap_or_else(|_| unreachable!());
         match map.entry(key) {
             std::collections::hash_map::Entry::Vacant(entry) => {
-                insert_fn(maybe_id)?;
-                entry.insert(maybe_id);
-                Ok(maybe_id)
+                let id = *counter;
+                insert_fn(id)?;
+                *counter += 1;
+                entry.insert(id);
+                Ok(id)
             }
             std::collections::hash_map::Entry::Occupied(entry) => Ok(*entry.get()),
         }
@@ -198,155 +397,650 @@ impl<'a> SimConnect<'a> {
     /// Make changes to the data properties of an object.
     pub fn set_data_on_sim_object<T: DataDefinition>(
         &mut self,
-        object_id: sys::SIMCONNECT_OBJECT_ID,
+        object_id: ObjectId,
         data: &T,
     ) -> Result<()> {
+        self.sender.method("set_data_on_sim_object");
         let define_id = self.get_define_id::<T>()?;
-        unsafe {
-            map_err(sys::SimConnect_SetDataOnSimObject(
-                self.handle,
-                define_id,
-                object_id,
-                0,
-                0,
-                std::mem::size_of_val(data) as sys::DWORD,
-                data as *const T as *mut std::ffi::c_void,
-            ))
-        }
+        let mut bytes = Vec::new();
+        data.encode(&mut bytes);
+        self.sender.call(Call::SetDataOnSimObject {
+            define_id: define_id.0,
+            object_id: object_id.0,
+            flags: 0,
+            array_count: 0,
+            unit_size: bytes.len() as sys::DWORD,
+            data: &bytes,
+        })
     }
 
     /// Retrieve information about simulation objects of a given type that are
     /// within a specified radius of the user's aircraft.
     pub fn request_data_on_sim_object_type<T: DataDefinition>(
         &mut self,
-        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        request_id: RequestId,
         radius: sys::DWORD,
         r#type: sys::SIMCONNECT_SIMOBJECT_TYPE,
     ) -> Result<()> {
+        self.sender.method("request_data_on_sim_object_type");
         let define_id = self.get_define_id::<T>()?;
-        unsafe {
-            map_err(sys::SimConnect_RequestDataOnSimObjectType(
-                self.handle,
+        self.sender.call(Call::RequestDataOnSimObjectType {
+            request_id: request_id.0,
+            define_id: define_id.0,
+            radius,
+            r#type,
+        })
+    }
+
+    /// Collect the values of `T` on every object of a type within `radius`
+    /// meters of the user's aircraft into a snapshot, with the object IDs in
+    /// the order the sim sent them. The request ID is allocated
+    /// automatically, and the snapshot fails if it isn't complete within
+    /// `timeout`, which is checked each time messages are dispatched.
+    /// ```rs
+    /// let traffic = sim
+    ///     .snapshot_sim_objects::<Position>(50_000, SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT, Duration::from_secs(5))?
+    ///     .await?;
+    /// for (object_id, position) in traffic {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn snapshot_sim_objects<T: DataDefinition>(
+        &mut self,
+        radius: sys::DWORD,
+        r#type: sys::SIMCONNECT_SIMOBJECT_TYPE,
+        timeout: std::time::Duration,
+    ) -> Result<Snapshot<T>> {
+        let request_id = self.routes.allocate();
+        self.sender.method("snapshot_sim_objects");
+        let define_id = self.get_define_id::<T>()?;
+        self.sender.call(Call::RequestDataOnSimObjectType {
+            request_id,
+            define_id: define_id.0,
+            radius,
+            r#type,
+        })?;
+        let rx = self
+            .routes
+            .insert(request_id, None, Some(std::time::Instant::now() + timeout));
+        Ok(Snapshot::new(rx, define_id, request_id))
+    }
+
+    /// Keep a table of the values of `T` on every object of a type within
+    /// `radius` meters of the user's aircraft. The objects are requested again
+    /// every `interval`, and as soon as the sim adds or removes an object.
+    /// Objects missing from a response, or removed from the sim, are evicted.
+    /// ```rs
+    /// let mut traffic = sim.track_sim_objects::<Position>(
+    ///     50_000,
+    ///     SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT,
+    ///     Duration::from_secs(1),
+    /// )?;
+    /// while let Some(event) = traffic.next().await {
+    ///     match event {
+    ///         TrackerEvent::Added(object_id) | TrackerEvent::Updated(object_id) => {
+    ///             let position = traffic.get(object_id).unwrap();
+    ///             // ...
+    ///         }
+    ///         TrackerEvent::Removed(object_id) => {
+    ///             // ...
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn track_sim_objects<T: DataDefinition>(
+        &mut self,
+        radius: sys::DWORD,
+        r#type: sys::SIMCONNECT_SIMOBJECT_TYPE,
+        interval: std::time::Duration,
+    ) -> Result<SimObjectTracker<T>> {
+        let request_id = self.routes.allocate();
+        let method = "track_sim_objects";
+        self.sender.method(method);
+        let define_id = self.get_define_id::<T>()?;
+
+        let mut event_ids = [0; 2];
+        for (event_id, system_event_name) in
+            event_ids.iter_mut().zip([c"ObjectAdded", c"ObjectRemoved"])
+        {
+            *event_id = self.event_id_counter;
+            self.event_id_counter += 1;
+            self.sender.call(Call::SubscribeToSystemEvent {
+                event_id: *event_id,
+                system_event_name,
+            })?;
+        }
+        let [added_event_id, removed_event_id] = event_ids;
+
+        let request = Call::RequestDataOnSimObjectType {
+            request_id,
+            define_id: define_id.0,
+            radius,
+            r#type,
+        };
+        self.sender.call(request.clone())?;
+        let rx = self.routes.insert(request_id, None, None);
+        self.routes.repeat(request_id, method, request, interval);
+        for event_id in [added_event_id, removed_event_id] {
+            self.routes.insert_event(
+                event_id,
                 request_id,
-                define_id,
-                radius,
-                r#type,
-            ))
+                Call::UnsubscribeFromSystemEvent { event_id },
+            );
         }
+        Ok(SimObjectTracker::new(
+            rx,
+            define_id,
+            request_id,
+            SystemEventId(removed_event_id),
+        ))
     }
 
     /// Request when the SimConnect client is to receive data values for a specific object
     pub fn request_data_on_sim_object<T: DataDefinition>(
         &mut self,
-        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
-        object_id: sys::SIMCONNECT_OBJECT_ID,
+        request_id: RequestId,
+        object_id: ObjectId,
         period: Period,
-    ) -> Result<()> {
+    ) -> Result<RequestHandle> {
+        self.sender.method("request_data_on_sim_object");
+        self.send_data_request::<T>(request_id, object_id, RequestOptions::new(period))
+    }
+
+    /// Request data values for a specific object, with the full set of
+    /// [`RequestOptions`].
+    pub fn request_data_on_sim_object_with<T: DataDefinition>(
+        &mut self,
+        request_id: RequestId,
+        object_id: ObjectId,
+        options: RequestOptions<Period>,
+    ) -> Result<RequestHandle> {
+        self.sender.method("request_data_on_sim_object_with");
+        self.send_data_request::<T>(request_id, object_id, options)
+    }
+
+    fn send_data_request<T: DataDefinition>(
+        &mut self,
+        request_id: RequestId,
+        object_id: ObjectId,
+        options: RequestOptions<Period>,
+    ) -> Result<RequestHandle> {
         let define_id = self.get_define_id::<T>()?;
+        self.sender.call(Call::RequestDataOnSimObject {
+            request_id: request_id.0,
+            define_id: define_id.0,
+            object_id: object_id.0,
+            period: options.period as sys::SIMCONNECT_PERIOD,
+            flags: options.flags(),
+            origin: options.origin,
+            interval: options.interval,
+            limit: options.limit,
+        })?;
+        Ok(self.routes.handle(
+            request_id,
+            stop_data_request(request_id.0, define_id.0, object_id.0),
+        ))
+    }
 
-        unsafe {
-            map_err(sys::SimConnect_RequestDataOnSimObject(
-                self.handle,
-                request_id,
+    /// Add a [`DynamicDataDefinition`] to this session, so that it can be
+    /// requested. If it was added before, it is cleared and redefined with
+    /// its current simvars under the same ID.
+    pub fn add_dynamic_data_definition(
+        &mut self,
+        definition: &mut DynamicDataDefinition,
+    ) -> Result<()> {
+        self.sender.method("add_dynamic_data_definition");
+        let define_id = match definition.define_id {
+            Some(define_id) => {
+                self.sender.call(Call::ClearDataDefinition { define_id })?;
+                define_id
+            }
+            None => {
+                let define_id = self.define_id_counter;
+                self.define_id_counter += 1;
+                define_id
+            }
+        };
+        for datum in &definition.datums {
+            let datum_name = std::ffi::CString::new(datum.name.as_str()).unwrap();
+            let units_name = std::ffi::CString::new(datum.unit.as_str()).unwrap();
+            self.sender.call(Call::AddToDataDefinition {
                 define_id,
-                object_id,
-                period as sys::SIMCONNECT_PERIOD,
-                sys::SIMCONNECT_DATA_REQUEST_FLAG_CHANGED,
-                0,
-                0,
-                0,
-            ))
+                datum_name: &datum_name,
+                units_name: &units_name,
+                datum_type: datum.data_type.sys(),
+                epsilon: datum.epsilon,
+                datum_id: sys::SIMCONNECT_UNUSED,
+            })?;
+        }
+        definition.define_id = Some(define_id);
+        Ok(())
+    }
+
+    /// Remove all simvars from a [`DynamicDataDefinition`], both locally and
+    /// in the sim. It keeps its ID, and can be filled and added again.
+    pub fn clear_dynamic_data_definition(
+        &mut self,
+        definition: &mut DynamicDataDefinition,
+    ) -> Result<()> {
+        self.sender.method("clear_dynamic_data_definition");
+        if let Some(define_id) = definition.define_id {
+            self.sender.call(Call::ClearDataDefinition { define_id })?;
         }
+        definition.clear();
+        Ok(())
+    }
+
+    /// Request the values of a [`DynamicDataDefinition`] on an object. The
+    /// definition must have been added with `add_dynamic_data_definition`,
+    /// or this fails with `E_INVALIDARG`.
+    pub fn request_dynamic_data_on_sim_object(
+        &mut self,
+        request_id: RequestId,
+        definition: &DynamicDataDefinition,
+        object_id: ObjectId,
+        period: Period,
+    ) -> Result<RequestHandle> {
+        self.sender.method("request_dynamic_data_on_sim_object");
+        self.send_dynamic_data_request(
+            request_id,
+            definition,
+            object_id,
+            RequestOptions::new(period),
+        )
+    }
+
+    /// Request the values of a [`DynamicDataDefinition`] on an object, with
+    /// the full set of [`RequestOptions`].
+    pub fn request_dynamic_data_on_sim_object_with(
+        &mut self,
+        request_id: RequestId,
+        definition: &DynamicDataDefinition,
+        object_id: ObjectId,
+        options: RequestOptions<Period>,
+    ) -> Result<RequestHandle> {
+        self.sender
+            .method("request_dynamic_data_on_sim_object_with");
+        self.send_dynamic_data_request(request_id, definition, object_id, options)
+    }
+
+    fn send_dynamic_data_request(
+        &mut self,
+        request_id: RequestId,
+        definition: &DynamicDataDefinition,
+        object_id: ObjectId,
+        options: RequestOptions<Period>,
+    ) -> Result<RequestHandle> {
+        let define_id = definition.define_id.ok_or(HResult(E_INVALIDARG))?;
+        self.sender.call(Call::RequestDataOnSimObject {
+            request_id: request_id.0,
+            define_id,
+            object_id: object_id.0,
+            period: options.period as sys::SIMCONNECT_PERIOD,
+            flags: options.flags(),
+            origin: options.origin,
+            interval: options.interval,
+            limit: options.limit,
+        })?;
+        Ok(self.routes.handle(
+            request_id,
+            stop_data_request(request_id.0, define_id, object_id.0),
+        ))
+    }
+
+    /// Receive the values of `T` on an object every `period`, or whenever they
+    /// change if that is less often. The request ID is allocated
+    /// automatically, and the request is stopped when the subscription is
+    /// dropped. Values which can't be decoded are yielded as a [`DecodeError`].
+    /// ```rs
+    /// let mut altitude = sim.subscribe::<Altitude>(ObjectId::USER, Period::SimFrame)?;
+    /// while let Some(altitude) = altitude.next().await {
+    ///     let altitude = altitude?;
+    ///     // ...
+    /// }
+    /// ```
+    pub fn subscribe<T: DataDefinition>(
+        &mut self,
+        object_id: ObjectId,
+        period: Period,
+    ) -> Result<Subscription<T>> {
+        let request_id = self.routes.allocate();
+        self.sender.method("subscribe");
+        let define_id = self.get_define_id::<T>()?;
+        let rx = self.route_data_request(
+            request_id,
+            define_id.0,
+            object_id.0,
+            period,
+            sys::SIMCONNECT_DATA_REQUEST_FLAG_CHANGED,
+        )?;
+        Ok(Subscription::new(rx, define_id, request_id))
+    }
+
+    /// Keep a cached value of `T` on an object up to date with a tagged
+    /// request, which only sends the simvars that changed. The subscription
+    /// yields the fields which changed each time, and the request is stopped
+    /// when it is dropped. This fails with `E_INVALIDARG` if `T` has a simvar
+    /// of a type which can't be received.
+    /// ```rs
+    /// let mut engines = sim.subscribe_tagged::<Engines>(ObjectId::USER, Period::SimFrame)?;
+    /// while let Some(changes) = engines.next().await {
+    ///     if changes?.contains("n1") {
+    ///         let n1 = engines.value().unwrap().n1;
+    ///         // ...
+    ///     }
+    /// }
+    /// ```
+    pub fn subscribe_tagged<T: DataDefinition>(
+        &mut self,
+        object_id: ObjectId,
+        period: Period,
+    ) -> Result<TaggedSubscription<T>> {
+        let data_types = TaggedSubscription::<T>::data_types().ok_or(HResult(E_INVALIDARG))?;
+        let request_id = self.routes.allocate();
+        self.sender.method("subscribe_tagged");
+        let define_id = self.get_define_id::<T>()?;
+        let rx = self.route_data_request(
+            request_id,
+            define_id.0,
+            object_id.0,
+            period,
+            sys::SIMCONNECT_DATA_REQUEST_FLAG_CHANGED | sys::SIMCONNECT_DATA_REQUEST_FLAG_TAGGED,
+        )?;
+        Ok(TaggedSubscription::new(
+            rx, define_id, request_id, data_types,
+        ))
+    }
+
+    /// Request data on an object, routing it to the returned receiver until it
+    /// is dropped.
+    fn route_data_request(
+        &mut self,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+        object_id: sys::SIMCONNECT_OBJECT_ID,
+        period: Period,
+        flags: sys::DWORD,
+    ) -> Result<futures::channel::mpsc::UnboundedReceiver<OwnedSimConnectRecv>> {
+        self.sender.call(Call::RequestDataOnSimObject {
+            request_id,
+            define_id,
+            object_id,
+            period: period as sys::SIMCONNECT_PERIOD,
+            flags,
+            origin: 0,
+            interval: 0,
+            limit: 0,
+        })?;
+        Ok(self.routes.insert(
+            request_id,
+            Some(stop_data_request(request_id, define_id, object_id)),
+            None,
+        ))
     }
 
     /// Map a Prepar3D event to a specific ID. If `mask` is true, the sim itself
     /// will ignore the event, and only this SimConnect instance will receive it.
+    /// The event is added to notification group 0, which is set to
+    /// [`Priority::HIGHEST_MASKABLE`]. Use `map_client_event` along with the
+    /// notification group methods to choose the group and its priority.
     pub fn map_client_event_to_sim_event(
         &mut self,
         event_name: &str,
         mask: bool,
-    ) -> Result<sys::DWORD> {
+    ) -> Result<ClientEventId> {
+        self.sender.method("map_client_event_to_sim_event");
+        let event_id = self.send_map_client_event(event_name)?;
+
+        self.sender.call(Call::AddClientEventToNotificationGroup {
+            group_id: 0,
+            event_id: event_id.0,
+            maskable: mask,
+        })?;
+
+        self.sender.call(Call::SetNotificationGroupPriority {
+            group_id: 0,
+            priority: sys::SIMCONNECT_GROUP_PRIORITY_HIGHEST_MASKABLE,
+        })?;
+        Ok(event_id)
+    }
+
+    /// Map a Prepar3D event to a client event ID, without adding it to a
+    /// notification group. The event can be transmitted, but this client is
+    /// only notified of it once it is added to a group.
+    /// ```rs
+    /// let ap_master = sim.map_client_event("AP_MASTER")?;
+    /// let intercept = NotificationGroupId(1);
+    /// sim.add_client_event_to_notification_group(intercept, ap_master, true)?;
+    /// sim.set_notification_group_priority(intercept, Priority::HIGHEST_MASKABLE)?;
+    /// ```
+    pub fn map_client_event(&mut self, event_name: &str) -> Result<ClientEventId> {
+        self.sender.method("map_client_event");
+        self.send_map_client_event(event_name)
+    }
+
+    fn send_map_client_event(&mut self, event_name: &str) -> Result<ClientEventId> {
         let event_id = self.event_id_counter;
         self.event_id_counter += 1;
         let event_name = std::ffi::CString::new(event_name).unwrap();
 
-        unsafe {
-            map_err(sys::SimConnect_MapClientEventToSimEvent(
-                self.handle,
-                event_id,
-                event_name.as_ptr(),
-            ))?;
+        self.sender.call(Call::MapClientEventToSimEvent {
+            event_id,
+            event_name: &event_name,
+        })?;
+        Ok(ClientEventId(event_id))
+    }
 
-            map_err(sys::SimConnect_AddClientEventToNotificationGroup(
-                self.handle,
-                0,
-                event_id,
-                mask.into(),
-            ))?;
-
-            map_err(sys::SimConnect_SetNotificationGroupPriority(
-                self.handle,
-                0,
-                sys::SIMCONNECT_GROUP_PRIORITY_HIGHEST_MASKABLE,
-            ))?;
-        }
-        Ok(event_id)
+    /// Add a client event to a notification group, creating the group if it
+    /// doesn't exist yet. If `maskable` is true and the group's priority
+    /// allows it, the event is hidden from the sim and from groups of lower
+    /// priority.
+    pub fn add_client_event_to_notification_group(
+        &mut self,
+        group_id: NotificationGroupId,
+        event_id: ClientEventId,
+        maskable: bool,
+    ) -> Result<()> {
+        self.sender.method("add_client_event_to_notification_group");
+        self.sender.call(Call::AddClientEventToNotificationGroup {
+            group_id: group_id.0,
+            event_id: event_id.0,
+            maskable,
+        })
+    }
+
+    /// Remove a client event from a notification group.
+    pub fn remove_client_event(
+        &mut self,
+        group_id: NotificationGroupId,
+        event_id: ClientEventId,
+    ) -> Result<()> {
+        self.sender.method("remove_client_event");
+        self.sender.call(Call::RemoveClientEvent {
+            group_id: group_id.0,
+            event_id: event_id.0,
+        })
+    }
+
+    /// Set the priority of a notification group, which decides the order in
+    /// which the groups of every client are notified of an event.
+    pub fn set_notification_group_priority(
+        &mut self,
+        group_id: NotificationGroupId,
+        priority: Priority,
+    ) -> Result<()> {
+        self.sender.method("set_notification_group_priority");
+        self.sender.call(Call::SetNotificationGroupPriority {
+            group_id: group_id.0,
+            priority: priority.0,
+        })
+    }
+
+    /// Remove every client event from a notification group.
+    pub fn clear_notification_group(&mut self, group_id: NotificationGroupId) -> Result<()> {
+        self.sender.method("clear_notification_group");
+        self.sender.call(Call::ClearNotificationGroup {
+            group_id: group_id.0,
+        })
+    }
+
+    /// Request that the events of a notification group are sent while the sim
+    /// is in dialog mode.
+    pub fn request_notification_group(&mut self, group_id: NotificationGroupId) -> Result<()> {
+        self.sender.method("request_notification_group");
+        self.sender.call(Call::RequestNotificationGroup {
+            group_id: group_id.0,
+            reserved: 0,
+            flags: 0,
+        })
+    }
+
+    /// Map a key or joystick input to client events, sent while the input
+    /// group is turned on. `input_definition` names the input the way the sim
+    /// does, such as `"shift+ctrl+U"` or `"joystick:0:button:3"`.
+    /// ```rs
+    /// let group = InputGroupId(0);
+    /// let toggle = sim.map_client_event("CUSTOM_TOGGLE")?;
+    /// sim.map_input_event_to_client_event(group, "shift+ctrl+U", InputMapping::new(toggle, 1))?;
+    /// sim.set_input_group_state(group, true)?;
+    /// ```
+    pub fn map_input_event_to_client_event(
+        &mut self,
+        group_id: InputGroupId,
+        input_definition: &str,
+        mapping: InputMapping,
+    ) -> Result<()> {
+        self.sender.method("map_input_event_to_client_event");
+        let input_definition = std::ffi::CString::new(input_definition).unwrap();
+        let (up_event_id, up_value) = mapping
+            .up
+            .map_or((sys::SIMCONNECT_UNUSED, 0), |(event_id, value)| {
+                (event_id.0, value)
+            });
+
+        self.sender.call(Call::MapInputEventToClientEvent {
+            group_id: group_id.0,
+            input_definition: &input_definition,
+            down_event_id: mapping.down.0.0,
+            down_value: mapping.down.1,
+            up_event_id,
+            up_value,
+            maskable: mapping.maskable,
+        })
+    }
+
+    /// Set the priority of an input group, which decides the order in which
+    /// the groups of every client receive an input.
+    pub fn set_input_group_priority(
+        &mut self,
+        group_id: InputGroupId,
+        priority: Priority,
+    ) -> Result<()> {
+        self.sender.method("set_input_group_priority");
+        self.sender.call(Call::SetInputGroupPriority {
+            group_id: group_id.0,
+            priority: priority.0,
+        })
+    }
+
+    /// Turn an input group on or off. Groups are off until they are turned on.
+    pub fn set_input_group_state(&mut self, group_id: InputGroupId, on: bool) -> Result<()> {
+        self.sender.method("set_input_group_state");
+        self.sender.call(Call::SetInputGroupState {
+            group_id: group_id.0,
+            state: on.into(),
+        })
+    }
+
+    /// Remove the mapping of an input from an input group.
+    pub fn remove_input_event(
+        &mut self,
+        group_id: InputGroupId,
+        input_definition: &str,
+    ) -> Result<()> {
+        self.sender.method("remove_input_event");
+        let input_definition = std::ffi::CString::new(input_definition).unwrap();
+
+        self.sender.call(Call::RemoveInputEvent {
+            group_id: group_id.0,
+            input_definition: &input_definition,
+        })
+    }
+
+    /// Remove every input mapping from an input group.
+    pub fn clear_input_group(&mut self, group_id: InputGroupId) -> Result<()> {
+        self.sender.method("clear_input_group");
+        self.sender.call(Call::ClearInputGroup {
+            group_id: group_id.0,
+        })
+    }
+
+    /// Ask the user to reserve a key for this client, offering up to three
+    /// choices. The key which was reserved is received as
+    /// [`SimConnectRecv::ReservedKey`], and pressing it sends `event_id`.
+    pub fn request_reserved_key(
+        &mut self,
+        event_id: ClientEventId,
+        key_choice_1: &str,
+        key_choice_2: Option<&str>,
+        key_choice_3: Option<&str>,
+    ) -> Result<()> {
+        self.sender.method("request_reserved_key");
+        let key_choice_1 = std::ffi::CString::new(key_choice_1).unwrap();
+        let key_choice_2 = key_choice_2.map(|x| std::ffi::CString::new(x).unwrap());
+        let key_choice_3 = key_choice_3.map(|x| std::ffi::CString::new(x).unwrap());
+
+        self.sender.call(Call::RequestReservedKey {
+            event_id: event_id.0,
+            key_choice_1: &key_choice_1,
+            key_choice_2: key_choice_2.as_deref(),
+            key_choice_3: key_choice_3.as_deref(),
+        })
     }
 
     /// Trigger an event, previously mapped with `map_client_event_to_sim_event`
     pub fn transmit_client_event(
         &mut self,
-        object_id: sys::SIMCONNECT_OBJECT_ID,
-        event_id: sys::DWORD,
+        object_id: ObjectId,
+        event_id: ClientEventId,
         data: sys::DWORD,
     ) -> Result<()> {
-        unsafe {
-            map_err(sys::SimConnect_TransmitClientEvent(
-                self.handle,
-                object_id,
-                event_id,
-                data,
-                0,
-                0,
-            ))
-        }
+        self.sender.method("transmit_client_event");
+        self.sender.call(Call::TransmitClientEvent {
+            object_id: object_id.0,
+            event_id: event_id.0,
+            data,
+            group_id: 0,
+            flags: 0,
+        })
     }
 
     pub fn transmit_client_event_ex1(
         &mut self,
-        object_id: sys::SIMCONNECT_OBJECT_ID,
-        event_id: sys::DWORD,
+        object_id: ObjectId,
+        event_id: ClientEventId,
         data: [sys::DWORD; 5],
     ) -> Result<()> {
-        unsafe {
-            map_err(sys::SimConnect_TransmitClientEvent_EX1(
-                self.handle,
-                object_id,
-                event_id,
-                0,
-                0,
-                data[0],
-                data[1],
-                data[2],
-                data[3],
-                data[4],
-            ))
-        }
+        self.sender.method("transmit_client_event_ex1");
+        self.sender.call(Call::TransmitClientEventEx1 {
+            object_id: object_id.0,
+            event_id: event_id.0,
+            group_id: 0,
+            flags: 0,
+            data,
+        })
     }
 
-    fn get_client_data_id(&mut self, name: &str) -> Result<sys::SIMCONNECT_CLIENT_DATA_ID> {
+    fn get_client_data_id(&mut self, name: &str) -> Result<ClientDataId> {
         let client_id = self.client_data_id_counter;
         self.client_data_id_counter += 1;
         let name = std::ffi::CString::new(name).unwrap();
 
-        unsafe {
-            map_err(sys::SimConnect_MapClientDataNameToID(
-                self.handle,
-                name.as_ptr(),
-                client_id,
-            ))?;
-        }
-        Ok(client_id)
+        self.sender.call(Call::MapClientDataNameToId {
+            client_data_name: &name,
+            client_data_id: client_id,
+        })?;
+        Ok(ClientDataId(client_id))
     }
 
     /// Allocate a region of memory in the sim with the given `name`. Other
@@ -356,15 +1050,13 @@ impl<'a> SimConnect<'a> {
         &mut self,
         name: &str,
     ) -> Result<ClientDataArea<T>> {
+        self.sender.method("create_client_data");
         let client_id = self.get_client_data_id(name)?;
-        unsafe {
-            map_err(sys::SimConnect_CreateClientData(
-                self.handle,
-                client_id,
-                std::mem::size_of::<T>() as sys::DWORD,
-                0,
-            ))?;
-        }
+        self.sender.call(Call::CreateClientData {
+            client_data_id: client_id.0,
+            size: std::mem::size_of::<T>() as sys::DWORD,
+            flags: 0,
+        })?;
         Ok(ClientDataArea {
             client_id,
             phantom: std::marker::PhantomData,
@@ -377,6 +1069,7 @@ impl<'a> SimConnect<'a> {
         &mut self,
         name: &str,
     ) -> Result<ClientDataArea<T>> {
+        self.sender.method("get_client_area");
         let client_id = self.get_client_data_id(name)?;
         Ok(ClientDataArea {
             client_id,
@@ -389,25 +1082,60 @@ impl<'a> SimConnect<'a> {
     /// allocate this memory.
     pub fn request_client_data<T: ClientDataDefinition>(
         &mut self,
-        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        request_id: RequestId,
         name: &str,
-    ) -> Result<()> {
+    ) -> Result<RequestHandle> {
+        self.sender.method("request_client_data");
+        self.send_client_data_request::<T>(
+            request_id,
+            name,
+            RequestOptions::new(ClientDataPeriod::OnSet),
+        )
+    }
+
+    /// Request the client data area `name`, with the full set of
+    /// [`RequestOptions`].
+    pub fn request_client_data_with<T: ClientDataDefinition>(
+        &mut self,
+        request_id: RequestId,
+        name: &str,
+        options: RequestOptions<ClientDataPeriod>,
+    ) -> Result<RequestHandle> {
+        self.sender.method("request_client_data_with");
+        self.send_client_data_request::<T>(request_id, name, options)
+    }
+
+    fn send_client_data_request<T: ClientDataDefinition>(
+        &mut self,
+        request_id: RequestId,
+        name: &str,
+        options: RequestOptions<ClientDataPeriod>,
+    ) -> Result<RequestHandle> {
         let define_id = self.get_client_data_define_id::<T>()?;
         let client_id = self.get_client_data_id(name)?;
-        unsafe {
-            map_err(sys::SimConnect_RequestClientData(
-                self.handle,
-                client_id,
-                request_id,
+        self.sender.call(Call::RequestClientData {
+            client_data_id: client_id.0,
+            request_id: request_id.0,
+            define_id,
+            period: options.period as sys::SIMCONNECT_CLIENT_DATA_PERIOD,
+            flags: options.flags(),
+            origin: options.origin,
+            interval: options.interval,
+            limit: options.limit,
+        })?;
+        Ok(self.routes.handle(
+            request_id,
+            Call::RequestClientData {
+                client_data_id: client_id.0,
+                request_id: request_id.0,
                 define_id,
-                sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_ON_SET,
-                sys::SIMCONNECT_CLIENT_DATA_REQUEST_FLAG_CHANGED,
-                0,
-                0,
-                0,
-            ))?;
-        }
-        Ok(())
+                period: sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_NEVER,
+                flags: 0,
+                origin: 0,
+                interval: 0,
+                limit: 0,
+            },
+        ))
     }
 
     /// Set the data of an area acquired by `create_client_data` or
@@ -417,134 +1145,529 @@ impl<'a> SimConnect<'a> {
         area: &ClientDataArea<T>,
         data: &T,
     ) -> Result<()> {
-        unsafe {
-            map_err(sys::SimConnect_SetClientData(
-                self.handle,
-                area.client_id,
-                self.get_client_data_define_id::<T>()?,
-                0,
-                0,
-                std::mem::size_of::<T>() as sys::DWORD,
-                data as *const _ as *mut std::ffi::c_void,
-            ))?;
-        }
+        self.sender.method("set_client_data");
+        let define_id = self.get_client_data_define_id::<T>()?;
+        let mut bytes = Vec::with_capacity(std::mem::size_of::<T>());
+        data.encode(&mut bytes);
+        self.sender.call(Call::SetClientData {
+            client_data_id: area.client_id.0,
+            define_id,
+            flags: 0,
+            data: &bytes,
+        })?;
         Ok(())
     }
 
+    /// Create an aircraft which isn't controlled by ATC, at `init_position`.
+    /// ```rs
+    /// let aircraft = sim
+    ///     .ai_create_non_atc_aircraft("Airbus A320 Neo Asobo", "N320AB", init_position)?
+    ///     .await?;
+    /// // The aircraft is removed once `aircraft` is dropped.
+    /// ```
     pub fn ai_create_non_atc_aircraft(
         &mut self,
         container_title: &str,
         tail_number: &str,
         init_position: sys::SIMCONNECT_DATA_INITPOSITION,
-        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
-    ) -> Result<()> {
+    ) -> Result<AiObjectRequest> {
+        let request_id = self.routes.allocate();
+        self.sender.method("ai_create_non_atc_aircraft");
         let container_title = std::ffi::CString::new(container_title).unwrap();
         let tail_number = std::ffi::CString::new(tail_number).unwrap();
 
-        unsafe {
-            map_err(sys::SimConnect_AICreateNonATCAircraft(
-                self.handle,
-                container_title.as_ptr(),
-                tail_number.as_ptr(),
-                init_position,
-                request_id,
-            ))?;
-        }
-        Ok(())
+        self.sender.call(Call::AiCreateNonAtcAircraft {
+            container_title: &container_title,
+            tail_number: &tail_number,
+            init_position,
+            request_id,
+        })?;
+        Ok(self.ai_object_request(request_id))
     }
 
+    /// Create an aircraft controlled by ATC, parked at the airport `icao`.
     pub fn ai_create_parked_atc_aircraft(
         &mut self,
         container_title: &str,
         tail_number: &str,
         icao: &str,
-        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
-    ) -> Result<()> {
+    ) -> Result<AiObjectRequest> {
+        let request_id = self.routes.allocate();
+        self.sender.method("ai_create_parked_atc_aircraft");
         let container_title = std::ffi::CString::new(container_title).unwrap();
         let tail_number = std::ffi::CString::new(tail_number).unwrap();
         let icao = std::ffi::CString::new(icao).unwrap();
 
-        unsafe {
-            map_err(sys::SimConnect_AICreateParkedATCAircraft(
-                self.handle,
-                container_title.as_ptr(),
-                tail_number.as_ptr(),
-                icao.as_ptr(),
-                request_id,
-            ))?;
-        }
-        Ok(())
+        self.sender.call(Call::AiCreateParkedAtcAircraft {
+            container_title: &container_title,
+            tail_number: &tail_number,
+            airport_id: &icao,
+            request_id,
+        })?;
+        Ok(self.ai_object_request(request_id))
     }
 
-    pub fn ai_remove_object(
+    /// Create an aircraft controlled by ATC, which flies the `.PLN` flight
+    /// plan at `flight_plan_path` (without the extension), starting
+    /// `flight_plan_position` of the way along it. With `touch_and_go`, it
+    /// flies the plan again from the start once it has landed.
+    pub fn ai_create_enroute_atc_aircraft(
+        &mut self,
+        container_title: &str,
+        tail_number: &str,
+        flight_number: i32,
+        flight_plan_path: &str,
+        flight_plan_position: f64,
+        touch_and_go: bool,
+    ) -> Result<AiObjectRequest> {
+        let request_id = self.routes.allocate();
+        self.sender.method("ai_create_enroute_atc_aircraft");
+        let container_title = std::ffi::CString::new(container_title).unwrap();
+        let tail_number = std::ffi::CString::new(tail_number).unwrap();
+        let flight_plan_path = std::ffi::CString::new(flight_plan_path).unwrap();
+
+        self.sender.call(Call::AiCreateEnrouteAtcAircraft {
+            container_title: &container_title,
+            tail_number: &tail_number,
+            flight_number,
+            flight_plan_path: &flight_plan_path,
+            flight_plan_position,
+            touch_and_go,
+            request_id,
+        })?;
+        Ok(self.ai_object_request(request_id))
+    }
+
+    /// Create an object which isn't an aircraft, such as a ground vehicle or
+    /// a boat, at `init_position`.
+    pub fn ai_create_simulated_object(
+        &mut self,
+        container_title: &str,
+        init_position: sys::SIMCONNECT_DATA_INITPOSITION,
+    ) -> Result<AiObjectRequest> {
+        let request_id = self.routes.allocate();
+        self.sender.method("ai_create_simulated_object");
+        let container_title = std::ffi::CString::new(container_title).unwrap();
+
+        self.sender.call(Call::AiCreateSimulatedObject {
+            container_title: &container_title,
+            init_position,
+            request_id,
+        })?;
+        Ok(self.ai_object_request(request_id))
+    }
+
+    fn ai_object_request(
         &mut self,
-        object_id: sys::SIMCONNECT_OBJECT_ID,
         request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    ) -> AiObjectRequest {
+        let (rx, call) = self.route_answer(request_id);
+        AiObjectRequest::new(rx, request_id, call, self.routes.dropped())
+    }
+
+    /// Have an AI aircraft fly the `.PLN` flight plan at `flight_plan_path`,
+    /// without the extension. The sim doesn't answer, so any exception is
+    /// sent to the callback.
+    pub fn ai_set_aircraft_flight_plan(
+        &mut self,
+        object_id: ObjectId,
+        flight_plan_path: &str,
+        request_id: RequestId,
     ) -> Result<()> {
-        unsafe {
-            map_err(sys::SimConnect_AIRemoveObject(
-                self.handle,
-                object_id,
-                request_id,
-            ))?;
-        }
+        self.sender.method("ai_set_aircraft_flight_plan");
+        let flight_plan_path = std::ffi::CString::new(flight_plan_path).unwrap();
+
+        self.sender.call(Call::AiSetAircraftFlightPlan {
+            object_id: object_id.0,
+            flight_plan_path: &flight_plan_path,
+            request_id: request_id.0,
+        })
+    }
+
+    /// Take control of an AI object away from the sim, so that it can be
+    /// moved by setting its simvars. The sim doesn't answer, so any exception
+    /// is sent to the callback.
+    pub fn ai_release_control(&mut self, object_id: ObjectId, request_id: RequestId) -> Result<()> {
+        self.sender.method("ai_release_control");
+        self.sender.call(Call::AiReleaseControl {
+            object_id: object_id.0,
+            request_id: request_id.0,
+        })
+    }
+
+    pub fn ai_remove_object(&mut self, object_id: ObjectId, request_id: RequestId) -> Result<()> {
+        self.sender.method("ai_remove_object");
+        self.sender.call(Call::AiRemoveObject {
+            object_id: object_id.0,
+            request_id: request_id.0,
+        })?;
         Ok(())
     }
 
-    pub fn subscribe_to_system_event(&mut self, system_event_name: &str) -> Result<sys::DWORD> {
+    /// Subscribe to a system event, such as `"Pause"`. Its events are sent to
+    /// the callback with the [`id`](RequestHandle::id) of the returned handle,
+    /// until the handle is dropped. Use `system_events` to have them decoded
+    /// into a stream instead.
+    pub fn subscribe_to_system_event(
+        &mut self,
+        system_event_name: &str,
+    ) -> Result<RequestHandle<SystemEventId>> {
+        self.sender.method("subscribe_to_system_event");
         let event_id = self.event_id_counter;
         self.event_id_counter += 1;
         let system_event_name = std::ffi::CString::new(system_event_name).unwrap();
 
-        unsafe {
-            map_err(sys::SimConnect_SubscribeToSystemEvent(
-                self.handle,
-                event_id,
-                system_event_name.as_ptr(),
-            ))?;
+        self.sender.call(Call::SubscribeToSystemEvent {
+            event_id,
+            system_event_name: &system_event_name,
+        })?;
+        Ok(self.routes.handle(
+            SystemEventId(event_id),
+            Call::UnsubscribeFromSystemEvent { event_id },
+        ))
+    }
+
+    /// Subscribe to a system event, decoding its events into a stream. The
+    /// subscription ends when the stream is dropped.
+    /// ```rs
+    /// let mut pause = sim.system_events(SystemEventKind::Pause)?;
+    /// while let Some(SystemEvent::Paused(paused)) = pause.next().await {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn system_events(&mut self, kind: SystemEventKind) -> Result<SystemEvents> {
+        self.sender.method("system_events");
+        let event_id = self.event_id_counter;
+        self.event_id_counter += 1;
+        let system_event_name = std::ffi::CString::new(kind.name()).unwrap();
+
+        self.sender.call(Call::SubscribeToSystemEvent {
+            event_id,
+            system_event_name: &system_event_name,
+        })?;
+        // The events are routed by a request ID of their own, which is never
+        // sent to the sim.
+        let request_id = self.routes.allocate();
+        let rx = self.routes.insert(request_id, None, None);
+        self.routes.insert_event(
+            event_id,
+            request_id,
+            Call::UnsubscribeFromSystemEvent { event_id },
+        );
+        Ok(SystemEvents::new(rx, kind, SystemEventId(event_id)))
+    }
+
+    pub fn unsubscribe_from_system_event(&mut self, event_id: SystemEventId) -> Result<()> {
+        self.sender.method("unsubscribe_from_system_event");
+        self.sender.call(Call::UnsubscribeFromSystemEvent {
+            event_id: event_id.0,
+        })?;
+        Ok(())
+    }
+
+    pub fn set_system_event_state(&mut self, event_id: SystemEventId, on: bool) -> Result<()> {
+        self.sender.method("set_system_event_state");
+        let state = on.into();
+        self.sender.call(Call::SetSystemEventState {
+            event_id: event_id.0,
+            state,
+        })?;
+        Ok(())
+    }
+
+    /// Ask for a piece of the sim's state.
+    /// ```rs
+    /// let SystemState::AircraftLoaded(path) =
+    ///     sim.system_state(SystemStateQuery::AircraftLoaded)?.await?
+    /// else {
+    ///     unreachable!()
+    /// };
+    /// ```
+    pub fn system_state(&mut self, query: SystemStateQuery) -> Result<SystemStateRequest> {
+        let request_id = self.routes.allocate();
+        self.sender.method("system_state");
+        let state = std::ffi::CString::new(query.name()).unwrap();
+
+        self.sender.call(Call::RequestSystemState {
+            request_id,
+            state: &state,
+        })?;
+        let (rx, call) = self.route_answer(request_id);
+        Ok(SystemStateRequest::new(rx, query, request_id, call))
+    }
+
+    /// Ask for the facilities of type `T` held in the sim's facility cache,
+    /// which covers a wide area around the user's aircraft. The list is sent
+    /// in pages, which the returned future collects.
+    /// ```rs
+    /// let airports = sim.request_facilities_list::<Airport>()?.await?;
+    /// ```
+    pub fn request_facilities_list<T: Facility>(&mut self) -> Result<FacilitiesList<T>> {
+        let request_id = self.routes.allocate();
+        self.sender.method("request_facilities_list");
+
+        self.sender.call(Call::RequestFacilitiesList {
+            r#type: T::LIST_TYPE,
+            request_id,
+        })?;
+        let (rx, call) = self.route_answer(request_id);
+        Ok(FacilitiesList::new(rx, request_id, call))
+    }
+
+    /// Ask for the facilities of type `T` in the reality bubble around the
+    /// user's aircraft.
+    pub fn request_facilities_list_ex1<T: Facility>(&mut self) -> Result<FacilitiesList<T>> {
+        let request_id = self.routes.allocate();
+        self.sender.method("request_facilities_list_ex1");
+
+        self.sender.call(Call::RequestFacilitiesListEx1 {
+            r#type: T::LIST_TYPE,
+            request_id,
+        })?;
+        let (rx, call) = self.route_answer(request_id);
+        Ok(FacilitiesList::new(rx, request_id, call))
+    }
+
+    /// Subscribe to the facilities of type `T` in the sim's facility cache.
+    /// The stream yields the facilities already in the cache, followed by
+    /// those which are added to it. Use `subscribe_to_facilities_ex1` to be
+    /// told about facilities going out of range as well. SimConnect keeps one
+    /// subscription per type of facility, so this fails with `E_INVALIDARG`
+    /// while another subscription to `T` is open.
+    pub fn subscribe_to_facilities<T: Facility>(&mut self) -> Result<FacilitySubscription<T>> {
+        let request_id = self.routes.allocate();
+        self.sender.method("subscribe_to_facilities");
+        if !self
+            .routes
+            .insert_facility_subscription(T::LIST_TYPE, request_id)
+        {
+            return Err(HResult(E_INVALIDARG));
         }
-        Ok(event_id)
+
+        self.sender.call(Call::SubscribeToFacilities {
+            r#type: T::LIST_TYPE,
+            request_id,
+        })?;
+        let stop = Call::UnsubscribeToFacilities {
+            r#type: T::LIST_TYPE,
+        };
+        let rx = self.routes.insert(request_id, Some(stop), None);
+        Ok(FacilitySubscription::new(rx, request_id))
+    }
+
+    /// Subscribe to the facilities of type `T` coming into or going out of
+    /// the reality bubble around the user's aircraft. Like
+    /// `subscribe_to_facilities`, this fails with `E_INVALIDARG` while another
+    /// subscription to `T` is open.
+    /// ```rs
+    /// let mut vors = sim.subscribe_to_facilities_ex1::<Vor>()?;
+    /// while let Some(event) = vors.next().await {
+    ///     match event {
+    ///         FacilityEvent::InRange(vor) => { /* ... */ }
+    ///         FacilityEvent::OutOfRange(vor) => { /* ... */ }
+    ///     }
+    /// }
+    /// ```
+    pub fn subscribe_to_facilities_ex1<T: Facility>(&mut self) -> Result<FacilitySubscription<T>> {
+        let in_range_request_id = self.routes.allocate();
+        let out_of_range_request_id = self.routes.allocate();
+        self.sender.method("subscribe_to_facilities_ex1");
+        if !self
+            .routes
+            .insert_facility_subscription(T::LIST_TYPE, in_range_request_id)
+        {
+            return Err(HResult(E_INVALIDARG));
+        }
+
+        self.sender.call(Call::SubscribeToFacilitiesEx1 {
+            r#type: T::LIST_TYPE,
+            in_range_request_id,
+            out_of_range_request_id,
+        })?;
+        let stop = Call::UnsubscribeToFacilitiesEx1 {
+            r#type: T::LIST_TYPE,
+            in_range: true,
+            out_of_range: true,
+        };
+        let rx = self.routes.insert(in_range_request_id, Some(stop), None);
+        self.routes
+            .alias(out_of_range_request_id, in_range_request_id);
+        Ok(FacilitySubscription::new(rx, in_range_request_id))
     }
 
-    pub fn unsubscribe_from_system_event(
+    /// Ask for the data of a facility, such as an airport along with its
+    /// runways and procedures, as described by a `facility_definition`. The
+    /// data is sent block by block, which the returned future assembles.
+    /// ```rs
+    /// let airport = sim.request_facility_data::<Airport>("KSEA", None)?.await?;
+    /// ```
+    pub fn request_facility_data<T: FacilityDefinition>(
         &mut self,
-        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
-    ) -> Result<()> {
-        unsafe {
-            map_err(sys::SimConnect_UnsubscribeFromSystemEvent(
-                self.handle,
-                event_id,
-            ))?;
+        icao: &str,
+        region: Option<&str>,
+    ) -> Result<FacilityDataRequest<T>> {
+        let request_id = self.routes.allocate();
+        self.sender.method("request_facility_data");
+        let define_id = self.get_facility_define_id::<T>()?;
+        let icao = std::ffi::CString::new(icao).unwrap();
+        let region = region.map(|x| std::ffi::CString::new(x).unwrap());
+
+        self.sender.call(Call::RequestFacilityData {
+            define_id,
+            request_id,
+            icao: &icao,
+            region: region.as_deref(),
+        })?;
+        let (rx, call) = self.route_answer(request_id);
+        Ok(FacilityDataRequest::new(rx, request_id, call))
+    }
+
+    /// Ask for the input events of the user's aircraft, such as its cockpit
+    /// knobs and switches. They are cached by name as they are received, so
+    /// that they can be used by name afterwards.
+    /// ```rs
+    /// let events = sim.enumerate_input_events()?.await?;
+    /// sim.set_input_event("AUTOPILOT_MASTER", 1.0)?;
+    /// ```
+    pub fn enumerate_input_events(&mut self) -> Result<InputEventList> {
+        let request_id = self.routes.allocate();
+        self.sender.method("enumerate_input_events");
+
+        self.sender
+            .call(Call::EnumerateInputEvents { request_id })?;
+        let (rx, call) = self.route_answer(request_id);
+        Ok(InputEventList::new(rx, request_id, call))
+    }
+
+    /// Look up an input event enumerated with `enumerate_input_events`.
+    pub fn input_event(&self, name: &str) -> Option<&InputEventDescriptor> {
+        self.input_events.get(name)
+    }
+
+    /// The hash of `event`. Names which were not enumerated fail with
+    /// `E_INVALIDARG`.
+    fn input_event_hash(&self, event: InputEvent) -> Result<sys::UINT64> {
+        match event {
+            InputEvent::Name(name) => self
+                .input_events
+                .get(name)
+                .map(|descriptor| descriptor.hash.0)
+                .ok_or(HResult(E_INVALIDARG)),
+            InputEvent::Hash(hash) => Ok(hash.0),
         }
-        Ok(())
     }
 
-    pub fn set_system_event_state(
+    /// Ask for the value of an input event, by name or by hash.
+    /// ```rs
+    /// let InputEventValue::Double(position) = sim.get_input_event("LIGHTING_LANDING_1")?.await?
+    /// else {
+    ///     unreachable!()
+    /// };
+    /// ```
+    pub fn get_input_event<'e>(
         &mut self,
-        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
-        on: bool,
+        event: impl Into<InputEvent<'e>>,
+    ) -> Result<InputEventRequest> {
+        let event = event.into();
+        let request_id = self.routes.allocate();
+        self.sender.method("get_input_event");
+        let hash = self.input_event_hash(event)?;
+
+        self.sender.call(Call::GetInputEvent { request_id, hash })?;
+        let (rx, call) = self.route_answer(request_id);
+        Ok(InputEventRequest::new(rx, request_id, call))
+    }
+
+    /// Set the value of an input event, by name or by hash, as if the pilot
+    /// had moved the control.
+    pub fn set_input_event<'e>(
+        &mut self,
+        event: impl Into<InputEvent<'e>>,
+        value: impl Into<InputEventValue>,
     ) -> Result<()> {
-        let state = on.into();
-        unsafe {
-            map_err(sys::SimConnect_SetSystemEventState(
-                self.handle,
-                event_id,
-                state,
-            ))?;
-        }
+        let event = event.into();
+        let value = value.into();
+        self.sender.method("set_input_event");
+        let hash = self.input_event_hash(event)?;
+
+        self.sender.call(Call::SetInputEvent {
+            hash,
+            value: &value.encode(),
+        })?;
         Ok(())
     }
 
+    /// Subscribe to the changes of an input event, by name or by hash. The
+    /// subscription ends when the stream is dropped.
+    /// ```rs
+    /// let mut master = sim.subscribe_input_event("AUTOPILOT_MASTER")?;
+    /// while let Some(value) = master.next().await {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn subscribe_input_event<'e>(
+        &mut self,
+        event: impl Into<InputEvent<'e>>,
+    ) -> Result<InputEventSubscription> {
+        let event = event.into();
+        self.sender.method("subscribe_input_event");
+        let hash = self.input_event_hash(event)?;
+
+        // The values are routed by a request ID of their own, which is never
+        // sent to the sim. Each input event is only subscribed to once, for
+        // all of its streams.
+        let request_id = self.routes.allocate();
+        let rx = self.routes.insert(request_id, None, None);
+        if self.routes.insert_input_event(hash, request_id) {
+            self.sender.call(Call::SubscribeInputEvent { hash })?;
+        }
+        Ok(InputEventSubscription::new(rx, InputEventHash(hash)))
+    }
+
+    /// Ask for the types of the parameters of an input event, by name or by
+    /// hash.
+    pub fn enumerate_input_event_params<'e>(
+        &mut self,
+        event: impl Into<InputEvent<'e>>,
+    ) -> Result<InputEventParamsRequest> {
+        let event = event.into();
+        self.sender.method("enumerate_input_event_params");
+        let hash = self.input_event_hash(event)?;
+
+        self.sender.call(Call::EnumerateInputEventParams { hash })?;
+        // The answer carries no request ID, so it is routed by the hash.
+        let request_id = self.routes.allocate();
+        let (rx, call) = self.route_answer(request_id);
+        self.routes.insert_input_event_params(hash, request_id);
+        Ok(InputEventParamsRequest::new(rx, InputEventHash(hash), call))
+    }
+
+    /// Route the answer to the call just sent for `request_id`, along with
+    /// its exceptions, to the returned receiver. The call is returned to
+    /// describe those exceptions.
+    fn route_answer(
+        &mut self,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    ) -> (
+        futures::channel::mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        Option<SentCall>,
+    ) {
+        let call = self.sender.last().cloned();
+        let rx = self.routes.insert(request_id, None, None);
+        if let Some(call) = &call {
+            self.routes.route_exceptions(request_id, call.packet_id);
+        }
+        (rx, call)
+    }
+
     /// Load a .FLT file from disk
     pub fn load_flight(&mut self, flight_file_path: &str) -> Result<()> {
+        self.sender.method("load_flight");
         let flight_file_path = std::ffi::CString::new(flight_file_path).unwrap();
 
-        unsafe {
-            map_err(sys::SimConnect_FlightLoad(
-                self.handle,
-                flight_file_path.as_ptr(),
-            ))?;
-        }
+        self.sender.call(Call::FlightLoad {
+            file_name: &flight_file_path,
+        })?;
         Ok(())
     }
 
@@ -555,50 +1678,62 @@ impl<'a> SimConnect<'a> {
         title: Option<&str>,
         description: Option<&str>,
     ) -> Result<()> {
+        self.sender.method("save_flight");
         let flight_file_path = std::ffi::CString::new(flight_file_path).unwrap();
         let title = title.map(|x| std::ffi::CString::new(x).unwrap());
         let description = description.map(|x| std::ffi::CString::new(x).unwrap());
 
-        unsafe {
-            map_err(sys::SimConnect_FlightSave(
-                self.handle,
-                flight_file_path.as_ptr(),
-                title
-                    .as_ref()
-                    .map(|x| x.as_ptr())
-                    .unwrap_or(std::ptr::null()),
-                description
-                    .as_ref()
-                    .map(|x| x.as_ptr())
-                    .unwrap_or(std::ptr::null()),
-                0,
-            ))?;
-        }
+        self.sender.call(Call::FlightSave {
+            file_name: &flight_file_path,
+            title: title.as_deref(),
+            description: description.as_deref(),
+            flags: 0,
+        })?;
         Ok(())
     }
 
     /// Load a .PLN file from disk
     pub fn load_flight_plan(&mut self, flight_plan_file_path: &str) -> Result<()> {
+        self.sender.method("load_flight_plan");
         let flight_plan_file_path = std::ffi::CString::new(flight_plan_file_path).unwrap();
 
-        unsafe {
-            map_err(sys::SimConnect_FlightPlanLoad(
-                self.handle,
-                flight_plan_file_path.as_ptr(),
-            ))?;
-        }
+        self.sender.call(Call::FlightPlanLoad {
+            file_name: &flight_plan_file_path,
+        })?;
         Ok(())
     }
 }
 
-impl Drop for SimConnect<'_> {
-    fn drop(&mut self) {
-        unsafe {
-            map_err(sys::SimConnect_Close(self.handle)).expect("SimConnect_Close");
-        }
+/// The call which stops a request for data on a SimObject.
+fn stop_data_request(
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+    object_id: sys::SIMCONNECT_OBJECT_ID,
+) -> Call<'static> {
+    Call::RequestDataOnSimObject {
+        request_id,
+        define_id,
+        object_id,
+        period: sys::SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_NEVER,
+        flags: 0,
+        origin: 0,
+        interval: 0,
+        limit: 0,
     }
 }
 
+/// Read a nul-terminated string from a fixed-size field of a message.
+fn fixed_str(chars: &[std::ffi::c_char]) -> std::borrow::Cow<'_, str> {
+    let bytes = unsafe { std::slice::from_raw_parts(chars.as_ptr() as *const u8, chars.len()) };
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len])
+}
+
+/// View the in-memory representation of `data`, to be copied into the sim.
+fn as_bytes<T>(data: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(data as *const T as *const u8, std::mem::size_of::<T>()) }
+}
+
 macro_rules! recv {
     ($V:ident) => {
         $V! {
@@ -627,11 +1762,56 @@ macro_rules! recv {
                 SIMCONNECT_RECV_EVENT_EX1,
                 EventEx1
             ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FILENAME,
+                SIMCONNECT_RECV_EVENT_FILENAME,
+                EventFilename
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FRAME,
+                SIMCONNECT_RECV_EVENT_FRAME,
+                EventFrame
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_OBJECT_ADDREMOVE,
+                SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE,
+                EventObjectAddRemove
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_SERVER_STARTED,
+                SIMCONNECT_RECV_EVENT_MULTIPLAYER_SERVER_STARTED,
+                EventMultiplayerServerStarted
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_CLIENT_STARTED,
+                SIMCONNECT_RECV_EVENT_MULTIPLAYER_CLIENT_STARTED,
+                EventMultiplayerClientStarted
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_SESSION_ENDED,
+                SIMCONNECT_RECV_EVENT_MULTIPLAYER_SESSION_ENDED,
+                EventMultiplayerSessionEnded
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_RACE_END,
+                SIMCONNECT_RECV_EVENT_RACE_END,
+                EventRaceEnd
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_RACE_LAP,
+                SIMCONNECT_RECV_EVENT_RACE_LAP,
+                EventRaceLap
+            ),
             (
                 SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA,
                 SIMCONNECT_RECV_SIMOBJECT_DATA,
                 SimObjectData
             ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SYSTEM_STATE,
+                SIMCONNECT_RECV_SYSTEM_STATE,
+                SystemState
+            ),
             (
                 SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CLIENT_DATA,
                 SIMCONNECT_RECV_CLIENT_DATA,
@@ -642,36 +1822,121 @@ macro_rules! recv {
                 SIMCONNECT_RECV_ASSIGNED_OBJECT_ID,
                 AssignedObjectId
             ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_RESERVED_KEY,
+                SIMCONNECT_RECV_RESERVED_KEY,
+                ReservedKey
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_AIRPORT_LIST,
+                SIMCONNECT_RECV_AIRPORT_LIST,
+                AirportList
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_VOR_LIST,
+                SIMCONNECT_RECV_VOR_LIST,
+                VorList
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NDB_LIST,
+                SIMCONNECT_RECV_NDB_LIST,
+                NdbList
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_WAYPOINT_LIST,
+                SIMCONNECT_RECV_WAYPOINT_LIST,
+                WaypointList
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_FACILITY_DATA,
+                SIMCONNECT_RECV_FACILITY_DATA,
+                FacilityData
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_FACILITY_DATA_END,
+                SIMCONNECT_RECV_FACILITY_DATA_END,
+                FacilityDataEnd
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ENUMERATE_INPUT_EVENTS,
+                SIMCONNECT_RECV_ENUMERATE_INPUT_EVENTS,
+                EnumerateInputEvents
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_GET_INPUT_EVENT,
+                SIMCONNECT_RECV_GET_INPUT_EVENT,
+                GetInputEvent
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SUBSCRIBE_INPUT_EVENT,
+                SIMCONNECT_RECV_SUBSCRIBE_INPUT_EVENT,
+                SubscribeInputEvent
+            ),
+            (
+                SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ENUMERATE_INPUT_EVENT_PARAMS,
+                SIMCONNECT_RECV_ENUMERATE_INPUT_EVENT_PARAMS,
+                EnumerateInputEventParams
+            ),
         }
     };
 }
 
 extern "C" fn dispatch_cb(
     recv: *mut sys::SIMCONNECT_RECV,
-    _cb_data: sys::DWORD,
+    cb_data: sys::DWORD,
     p_context: *mut std::ffi::c_void,
 ) {
-    macro_rules! recv_cb {
-        ($( ($ID:ident, $T:ident, $E:ident), )*) => {
-            unsafe {
-                match (*recv).dwID as sys::SIMCONNECT_RECV_ID {
-                    sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NULL => Some(SimConnectRecv::Null),
-                    $(
-                        sys::$ID => Some(SimConnectRecv::$E(&*(recv as *mut sys::$T))),
-                    )*
-                    sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE => {
-                        Some(SimConnectRecv::SimObjectData(&*(recv as *mut sys::SIMCONNECT_RECV_SIMOBJECT_DATA)))
-                    }
-                    _ => None,
+    let bytes = unsafe { std::slice::from_raw_parts(recv as *const u8, cb_data as usize) };
+    let mut padded = Vec::new();
+    if let Some(recv) = SimConnectRecv::from_bytes(bytes, &mut padded) {
+        let sim = unsafe { &mut *(p_context as *mut SimConnect) };
+        if let SimConnectRecv::EnumerateInputEvents(list) = &recv {
+            let descriptors = list.descriptors().into_iter();
+            sim.input_events
+                .extend(descriptors.map(|descriptor| (descriptor.name.clone(), descriptor)));
+        }
+        match sim.routes.route(recv) {
+            Some(recv) => {
+                // The callback is moved out while it runs, so that it doesn't
+                // alias the `&mut SimConnect` it is given.
+                let mut callback = std::mem::replace(&mut sim.callback, Box::new(|_, _| {}));
+                callback(sim, recv);
+                sim.callback = callback;
+            }
+            None => {
+                if let Some(wake) = &mut sim.wake {
+                    wake();
                 }
             }
         }
+        // In WASM, messages are dispatched without calls to `call_dispatch`.
+        // Errors will surface again with the next call.
+        let _ = sim.update_routes();
     }
-    let recv = recv!(recv_cb);
+}
 
-    if let Some(recv) = recv {
-        let sim = unsafe { &mut *(p_context as *mut SimConnect) };
-        (sim.callback)(unsafe { &mut *(p_context as *mut SimConnect) }, recv);
+/// The size of an element of the array a list message ends with, or 0 for
+/// messages which aren't lists. The array is declared with one element, but a
+/// page may have none.
+fn list_element_size(id: sys::SIMCONNECT_RECV_ID) -> usize {
+    use std::mem::size_of;
+    match id {
+        sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_AIRPORT_LIST => {
+            size_of::<sys::SIMCONNECT_DATA_FACILITY_AIRPORT>()
+        }
+        sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_VOR_LIST => {
+            size_of::<sys::SIMCONNECT_DATA_FACILITY_VOR>()
+        }
+        sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NDB_LIST => {
+            size_of::<sys::SIMCONNECT_DATA_FACILITY_NDB>()
+        }
+        sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_WAYPOINT_LIST => {
+            size_of::<sys::SIMCONNECT_DATA_FACILITY_WAYPOINT>()
+        }
+        sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ENUMERATE_INPUT_EVENTS => {
+            size_of::<sys::SIMCONNECT_INPUT_EVENT_DESCRIPTOR>()
+        }
+        _ => 0,
     }
 }
 
@@ -682,17 +1947,65 @@ macro_rules! recv_enum {
         pub enum SimConnectRecv<'a> {
             Null,
             $(
-                $E(&'a sys::$T),
+                $E(&'a Recv<sys::$T>),
+            )*
+        }
+
+        /// Message received from SimConnect, copied out of the dispatch buffer.
+        #[derive(Debug, Clone)]
+        pub enum OwnedSimConnectRecv {
+            Null,
+            $(
+                $E(OwnedRecv<sys::$T>),
             )*
         }
+
+        impl<'a> SimConnectRecv<'a> {
+            /// Parse a message, using `padded` for one which ends with an empty
+            /// array.
+            fn from_bytes(bytes: &'a [u8], padded: &'a mut Vec<u8>) -> Option<Self> {
+                let header = Recv::<sys::SIMCONNECT_RECV>::new(bytes)?;
+                let id = header.dwID as sys::SIMCONNECT_RECV_ID;
+                match id {
+                    sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NULL => Some(SimConnectRecv::Null),
+                    $(
+                        sys::$ID => {
+                            Recv::new_list(bytes, list_element_size(id), padded)
+                                .map(SimConnectRecv::$E)
+                        }
+                    )*
+                    sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE => {
+                        Recv::new(bytes).map(SimConnectRecv::SimObjectData)
+                    }
+                    _ => None,
+                }
+            }
+
+            /// Copy this message out of the dispatch buffer.
+            pub fn into_owned(self) -> OwnedSimConnectRecv {
+                match self {
+                    SimConnectRecv::Null => OwnedSimConnectRecv::Null,
+                    $(
+                        SimConnectRecv::$E(recv) => OwnedSimConnectRecv::$E(recv.to_owned()),
+                    )*
+                }
+            }
+        }
     }
 }
 recv!(recv_enum);
 
 impl sys::SIMCONNECT_RECV_EVENT {
-    /// The ID for this event.
-    pub fn id(&self) -> sys::DWORD {
-        self.uEventID
+    /// The ID for this event, when it is a client event mapped with
+    /// `map_client_event_to_sim_event`.
+    pub fn id(&self) -> ClientEventId {
+        ClientEventId(self.uEventID)
+    }
+
+    /// The ID for this event, when it is a system event subscribed to with
+    /// `subscribe_to_system_event`.
+    pub fn system_event_id(&self) -> SystemEventId {
+        SystemEventId(self.uEventID)
     }
 
     /// The data for this event.
@@ -703,8 +2016,8 @@ impl sys::SIMCONNECT_RECV_EVENT {
 
 impl sys::SIMCONNECT_RECV_EVENT_EX1 {
     /// The ID for this event.
-    pub fn id(&self) -> sys::DWORD {
-        self.uEventID
+    pub fn id(&self) -> ClientEventId {
+        ClientEventId(self.uEventID)
     }
 
     /// The data for this event.
@@ -719,54 +2032,321 @@ impl sys::SIMCONNECT_RECV_EVENT_EX1 {
     }
 }
 
+impl sys::SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE {
+    /// The ID for this event.
+    pub fn id(&self) -> SystemEventId {
+        SystemEventId(self._base.uEventID)
+    }
+
+    /// The object which was added or removed.
+    pub fn object_id(&self) -> ObjectId {
+        ObjectId(self._base.dwData)
+    }
+
+    /// The type of the object which was added or removed.
+    pub fn object_type(&self) -> sys::SIMCONNECT_SIMOBJECT_TYPE {
+        self.eObjType
+    }
+}
+
+impl sys::SIMCONNECT_RECV_EVENT_FILENAME {
+    /// The ID of the system event subscription.
+    pub fn id(&self) -> SystemEventId {
+        SystemEventId(self._base.uEventID)
+    }
+
+    /// The path of the file the event is about.
+    pub fn file_name(&self) -> std::borrow::Cow<'_, str> {
+        fixed_str(&self.szFileName)
+    }
+}
+
+impl sys::SIMCONNECT_RECV_EVENT_FRAME {
+    /// The ID of the system event subscription.
+    pub fn id(&self) -> SystemEventId {
+        SystemEventId(self._base.uEventID)
+    }
+
+    /// The visual frame rate, in frames per second.
+    pub fn frame_rate(&self) -> f32 {
+        self.fFrameRate
+    }
+
+    /// The simulation rate, where 1.0 is real time.
+    pub fn sim_speed(&self) -> f32 {
+        self.fSimSpeed
+    }
+}
+
+macro_rules! multiplayer_event {
+    ($( $T:ident, )*) => {
+        $(
+            impl sys::$T {
+                /// The ID of the system event subscription.
+                pub fn id(&self) -> SystemEventId {
+                    SystemEventId(self._base.uEventID)
+                }
+            }
+        )*
+    };
+}
+
+multiplayer_event! {
+    SIMCONNECT_RECV_EVENT_MULTIPLAYER_SERVER_STARTED,
+    SIMCONNECT_RECV_EVENT_MULTIPLAYER_CLIENT_STARTED,
+    SIMCONNECT_RECV_EVENT_MULTIPLAYER_SESSION_ENDED,
+}
+
+impl sys::SIMCONNECT_DATA_RACE_RESULT {
+    /// Copy this result out of the message.
+    fn result(&self) -> RaceResult {
+        let mission_guid = { self.MissionGUID };
+        RaceResult {
+            racers: self.dwNumberOfRacers,
+            mission_guid: as_bytes(&mission_guid).try_into().unwrap(),
+            player_name: fixed_str(&self.szPlayerName).into_owned(),
+            session_type: fixed_str(&self.szSessionType).into_owned(),
+            aircraft: fixed_str(&self.szAircraft).into_owned(),
+            player_role: fixed_str(&self.szPlayerRole).into_owned(),
+            total_time: self.fTotalTime,
+            penalty_time: self.fPenaltyTime,
+            disqualified: self.dwIsDisqualified != 0,
+        }
+    }
+}
+
+impl sys::SIMCONNECT_RECV_EVENT_RACE_END {
+    /// The ID of the system event subscription.
+    pub fn id(&self) -> SystemEventId {
+        SystemEventId(self._base.uEventID)
+    }
+
+    /// The index of the racer the result is for.
+    pub fn racer_number(&self) -> sys::DWORD {
+        self.dwRacerNumber
+    }
+
+    /// The racer's result.
+    pub fn result(&self) -> RaceResult {
+        self.RacerData.result()
+    }
+}
+
+impl sys::SIMCONNECT_RECV_EVENT_RACE_LAP {
+    /// The ID of the system event subscription.
+    pub fn id(&self) -> SystemEventId {
+        SystemEventId(self._base.uEventID)
+    }
+
+    /// The index of the lap which was completed, from 0.
+    pub fn lap_index(&self) -> sys::DWORD {
+        self.dwLapIndex
+    }
+
+    /// The racer's result at the end of the lap.
+    pub fn result(&self) -> RaceResult {
+        self.RacerData.result()
+    }
+}
+
 impl sys::SIMCONNECT_RECV_ASSIGNED_OBJECT_ID {
-    pub fn id(&self) -> sys::DWORD {
-        self.dwRequestID
+    pub fn id(&self) -> RequestId {
+        RequestId(self.dwRequestID)
+    }
+
+    pub fn object_id(&self) -> ObjectId {
+        ObjectId(self.dwObjectID)
+    }
+}
+
+impl sys::SIMCONNECT_RECV_RESERVED_KEY {
+    /// The key choice which was reserved, as passed to `request_reserved_key`.
+    pub fn choice_reserved(&self) -> std::borrow::Cow<'_, str> {
+        fixed_str(&self.szChoiceReserved)
+    }
+
+    /// The key which was reserved, in the sim's format.
+    pub fn reserved_key(&self) -> std::borrow::Cow<'_, str> {
+        fixed_str(&self.szReservedKey)
+    }
+}
+
+impl sys::SIMCONNECT_RECV_SYSTEM_STATE {
+    /// The ID of the request.
+    pub fn id(&self) -> RequestId {
+        RequestId(self.dwRequestID)
+    }
+
+    /// The answer, for states which are numbers or flags.
+    pub fn integer(&self) -> sys::DWORD {
+        self.dwInteger
     }
 
-    pub fn object_id(&self) -> sys::DWORD {
-        self.dwObjectID
+    /// The answer, for states which are real numbers.
+    pub fn float(&self) -> f32 {
+        self.fFloat
+    }
+
+    /// The answer, for states which are strings such as paths.
+    pub fn string(&self) -> std::borrow::Cow<'_, str> {
+        fixed_str(&self.szString)
+    }
+}
+
+impl sys::SIMCONNECT_RECV_FACILITY_DATA {
+    /// The ID of the request.
+    pub fn id(&self) -> RequestId {
+        RequestId(self.UserRequestId)
+    }
+}
+
+impl Recv<sys::SIMCONNECT_RECV_FACILITY_DATA> {
+    /// The fields of the block, in the order they were added to the
+    /// definition.
+    pub fn data(&self) -> &[u8] {
+        &self.bytes()[std::mem::offset_of!(sys::SIMCONNECT_RECV_FACILITY_DATA, Data)..]
+    }
+}
+
+impl sys::SIMCONNECT_RECV_FACILITY_DATA_END {
+    /// The ID of the request.
+    pub fn id(&self) -> RequestId {
+        RequestId(self.RequestId)
     }
 }
 
 impl sys::SIMCONNECT_RECV_SIMOBJECT_DATA {
     /// The ID for this data.
-    pub fn id(&self) -> sys::DWORD {
-        self.dwRequestID
+    pub fn id(&self) -> RequestId {
+        RequestId(self.dwRequestID)
+    }
+
+    /// The data definition this data is for.
+    pub fn define_id(&self) -> DefineId {
+        DefineId(self.dwDefineID)
+    }
+
+    /// The object this data is for.
+    pub fn object_id(&self) -> ObjectId {
+        ObjectId(self.dwObjectID)
+    }
+}
+
+impl Recv<sys::SIMCONNECT_RECV_SIMOBJECT_DATA> {
+    /// The data payload of this message.
+    pub fn data(&self) -> &[u8] {
+        &self.bytes()[std::mem::offset_of!(sys::SIMCONNECT_RECV_SIMOBJECT_DATA, dwData)..]
+    }
+
+    /// Convert a SimObjectData event into the data it contains, copied out
+    /// field by field and checking that every field holds a valid value.
+    pub fn into<T: DataDefinition>(&self, sim: &SimConnect) -> std::result::Result<T, DecodeError> {
+        let define_id =
+            *sim.data_definitions
+                .get(&TypeId::of::<T>())
+                .ok_or(DecodeError::Unregistered {
+                    type_name: std::any::type_name::<T>(),
+                })?;
+        check_header(self.bytes(), self, DefineId(define_id), T::DATUM_COUNT)?;
+        T::decode(self.data())
     }
+}
 
+impl OwnedRecv<sys::SIMCONNECT_RECV_SIMOBJECT_DATA> {
     /// Convert a SimObjectData event into the data it contains.
-    pub fn into<T: DataDefinition>(&self, sim: &SimConnect) -> Option<&T> {
-        let define_id = sim.data_definitions[&TypeId::of::<T>()];
-        if define_id == self.dwDefineID {
-            // UB: creates unaligned reference
-            Some(unsafe { &*(std::ptr::addr_of!(self.dwData) as *const T) })
-        } else {
-            None
-        }
+    pub fn into<T: DataDefinition>(self, sim: &SimConnect) -> std::result::Result<T, DecodeError> {
+        (*self).into(sim)
+    }
+}
+
+/// Check that a data message is for the expected definition, and that its
+/// header agrees with what was received.
+fn check_header(
+    bytes: &[u8],
+    header: &sys::SIMCONNECT_RECV_SIMOBJECT_DATA,
+    define_id: DefineId,
+    define_count: usize,
+) -> std::result::Result<(), DecodeError> {
+    if header.dwFlags & sys::SIMCONNECT_DATA_REQUEST_FLAG_TAGGED != 0 {
+        return Err(DecodeError::Tagged);
+    }
+    let size = header._base.dwSize as usize;
+    if size != bytes.len() {
+        return Err(DecodeError::SizeMismatch {
+            expected: size,
+            received: bytes.len(),
+        });
+    }
+    check_define_id(header, define_id)?;
+    if header.dwDefineCount as usize != define_count {
+        return Err(DecodeError::DefineCountMismatch {
+            expected: define_count,
+            received: header.dwDefineCount as usize,
+        });
+    }
+    Ok(())
+}
+
+/// Check that a data message is for the expected definition.
+fn check_define_id(
+    header: &sys::SIMCONNECT_RECV_SIMOBJECT_DATA,
+    define_id: DefineId,
+) -> std::result::Result<(), DecodeError> {
+    if header.define_id() != define_id {
+        return Err(DecodeError::DefinitionMismatch {
+            expected: define_id,
+            received: header.define_id(),
+        });
     }
+    Ok(())
 }
 
 impl sys::SIMCONNECT_RECV_CLIENT_DATA {
     /// The ID for this data.
-    pub fn id(&self) -> sys::DWORD {
-        self._base.dwRequestID
+    pub fn id(&self) -> RequestId {
+        RequestId(self._base.dwRequestID)
     }
+}
+
+impl Recv<sys::SIMCONNECT_RECV_CLIENT_DATA> {
+    /// The data payload of this message.
+    pub fn data(&self) -> &[u8] {
+        &self.bytes()[std::mem::offset_of!(sys::SIMCONNECT_RECV_CLIENT_DATA, _base.dwData)..]
+    }
+
+    /// Convert a ClientData event into the data it contains, copied out field
+    /// by field and checking that every field holds a valid value.
+    pub fn into<T: ClientDataDefinition>(
+        &self,
+        sim: &SimConnect,
+    ) -> std::result::Result<T, DecodeError> {
+        let define_id = *sim.client_data_definitions.get(&TypeId::of::<T>()).ok_or(
+            DecodeError::Unregistered {
+                type_name: std::any::type_name::<T>(),
+            },
+        )?;
+        check_header(
+            self.bytes(),
+            &self._base,
+            DefineId(define_id),
+            client_data_datums::<T>().len(),
+        )?;
+        T::decode(self.data())
+    }
+}
 
+impl OwnedRecv<sys::SIMCONNECT_RECV_CLIENT_DATA> {
     /// Convert a ClientData event into the data it contains.
-    pub fn into<T: ClientDataDefinition>(&self, sim: &SimConnect) -> Option<&T> {
-        let define_id = sim.client_data_definitions[&TypeId::of::<T>()];
-        if define_id == self._base.dwDefineID {
-            // UB: creates unaligned reference
-            Some(unsafe { &*(std::ptr::addr_of!(self._base.dwData) as *const T) })
-        } else {
-            None
-        }
+    pub fn into<T: ClientDataDefinition>(
+        self,
+        sim: &SimConnect,
+    ) -> std::result::Result<T, DecodeError> {
+        (*self).into(sim)
     }
 }
 
 /// Specify how often data is to be sent to the client.
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Period {
     /// Specifies that the data is not to be sent
     Never = sys::SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_NEVER as isize,
@@ -783,9 +2363,166 @@ pub enum Period {
     Second = sys::SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_SECOND as isize,
 }
 
+/// Specify how often client data is to be sent to the client.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClientDataPeriod {
+    /// Specifies that the data is not to be sent
+    Never = sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_NEVER as isize,
+    /// Specifies that the data should be sent once only.
+    Once = sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_ONCE as isize,
+    /// Specifies that the data should be sent every visual (rendered) frame.
+    VisualFrame =
+        sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_VISUAL_FRAME as isize,
+    /// Specifies that the data should be sent whenever it is set.
+    OnSet = sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_ON_SET as isize,
+    /// Specifies that the data should be sent once every second.
+    Second = sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_SECOND as isize,
+}
+
+/// The priority of a group of events. Groups are notified in order from the
+/// lowest value to the highest, and only groups with a priority of
+/// [`HIGHEST_MASKABLE`](Priority::HIGHEST_MASKABLE) or lower can mask events.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Priority(pub sys::DWORD);
+
+impl Priority {
+    /// The highest priority.
+    pub const HIGHEST: Priority = Priority(sys::SIMCONNECT_GROUP_PRIORITY_HIGHEST);
+    /// The highest priority which can mask events.
+    pub const HIGHEST_MASKABLE: Priority =
+        Priority(sys::SIMCONNECT_GROUP_PRIORITY_HIGHEST_MASKABLE);
+    /// The priority most clients should use.
+    pub const STANDARD: Priority = Priority(sys::SIMCONNECT_GROUP_PRIORITY_STANDARD);
+    /// The default priority.
+    pub const DEFAULT: Priority = Priority(sys::SIMCONNECT_GROUP_PRIORITY_DEFAULT);
+    /// The lowest priority, notified after every other group.
+    pub const LOWEST: Priority = Priority(sys::SIMCONNECT_GROUP_PRIORITY_LOWEST);
+}
+
+/// The client events a key or joystick input is mapped to, with the value
+/// sent along with each.
+/// ```rs
+/// // Send `brakes` with 1 while the key is held, and with 0 once released.
+/// let mapping = InputMapping::new(brakes, 1).with_up(brakes, 0);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct InputMapping {
+    down: (ClientEventId, sys::DWORD),
+    up: Option<(ClientEventId, sys::DWORD)>,
+    maskable: bool,
+}
+
+impl InputMapping {
+    /// Send `event_id` with `value` when the input is pressed.
+    pub fn new(event_id: ClientEventId, value: sys::DWORD) -> Self {
+        Self {
+            down: (event_id, value),
+            up: None,
+            maskable: false,
+        }
+    }
+
+    /// Also send `event_id` with `value` when the input is released.
+    pub fn with_up(mut self, event_id: ClientEventId, value: sys::DWORD) -> Self {
+        self.up = Some((event_id, value));
+        self
+    }
+
+    /// Hide the input from the sim and from input groups of lower priority.
+    pub fn with_maskable(mut self, maskable: bool) -> Self {
+        self.maskable = maskable;
+        self
+    }
+}
+
+/// How often, and in which form, the data of a request is sent. `P` is
+/// [`Period`] for SimObject data and [`ClientDataPeriod`] for client data.
+/// ```rs
+/// // Every 5th sim frame, only when changed, for the first 10 samples.
+/// let options = RequestOptions::new(Period::SimFrame)
+///     .with_interval(4)
+///     .with_limit(10);
+/// let _data = sim.request_data_on_sim_object_with::<Data>(RequestId(0), ObjectId::USER, options)?;
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RequestOptions<P> {
+    period: P,
+    changed: bool,
+    tagged: bool,
+    origin: sys::DWORD,
+    interval: sys::DWORD,
+    limit: sys::DWORD,
+}
+
+impl<P> RequestOptions<P> {
+    /// Options to send data every `period`, only when it changed.
+    pub fn new(period: P) -> Self {
+        RequestOptions {
+            period,
+            changed: true,
+            tagged: false,
+            origin: 0,
+            interval: 0,
+            limit: 0,
+        }
+    }
+
+    /// Set whether data is only sent when it changed, rather than every period.
+    pub fn with_changed(mut self, changed: bool) -> Self {
+        self.changed = changed;
+        self
+    }
+
+    /// Set whether data is sent in the tagged format, as pairs of datum ID and
+    /// value.
+    pub fn with_tagged(mut self, tagged: bool) -> Self {
+        self.tagged = tagged;
+        self
+    }
+
+    /// Set the number of periods to wait before sending data the first time.
+    pub fn with_origin(mut self, origin: sys::DWORD) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Set the number of periods to skip between each time data is sent.
+    pub fn with_interval(mut self, interval: sys::DWORD) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the number of times data is sent before the request ends, or 0 to
+    /// send it until the request is stopped.
+    pub fn with_limit(mut self, limit: sys::DWORD) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// The `SIMCONNECT_DATA_REQUEST_FLAG`s, which are the same as the
+    /// `SIMCONNECT_CLIENT_DATA_REQUEST_FLAG`s.
+    fn flags(&self) -> sys::DWORD {
+        let mut flags = sys::SIMCONNECT_DATA_REQUEST_FLAG_DEFAULT;
+        if self.changed {
+            flags |= sys::SIMCONNECT_DATA_REQUEST_FLAG_CHANGED;
+        }
+        if self.tagged {
+            flags |= sys::SIMCONNECT_DATA_REQUEST_FLAG_TAGGED;
+        }
+        flags
+    }
+}
+
 /// An allocated client data memory region. Dropping this struct will not
 /// deallocate the memory which has been allocated in the sim.
 pub struct ClientDataArea<T: ClientDataDefinition> {
-    client_id: sys::SIMCONNECT_CLIENT_DATA_ID,
+    client_id: ClientDataId,
     phantom: std::marker::PhantomData<T>,
 }
+
+impl<T: ClientDataDefinition> ClientDataArea<T> {
+    /// The ID the area's name was mapped to in this session.
+    pub fn id(&self) -> ClientDataId {
+        self.client_id
+    }
+}
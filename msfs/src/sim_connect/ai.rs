@@ -0,0 +1,89 @@
+use super::{
+    Call, Exception, ObjectId, OwnedSimConnectRecv, RequestError, RequestHandle, RequestId,
+    SentCall,
+};
+use crate::sys;
+use futures::channel::mpsc;
+use futures::stream::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// An AI object created by this client, which is removed from the sim once
+/// the handle is dropped. [`cancel`](RequestHandle::cancel) removes it at
+/// once, and [`forget`](RequestHandle::forget) leaves it in the sim.
+pub type AiObject = RequestHandle<ObjectId>;
+
+/// A request to create an AI object, created by methods such as
+/// [`SimConnect::ai_create_non_atc_aircraft`](super::SimConnect::ai_create_non_atc_aircraft).
+/// It resolves once the sim has assigned the object its ID, or with the
+/// exception raised if it couldn't be created. If the request is dropped
+/// before then, the object is left in the sim.
+pub struct AiObjectRequest {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    /// The call which made the request, to describe its exceptions.
+    call: Option<SentCall>,
+    dropped: mpsc::UnboundedSender<Call<'static>>,
+}
+
+impl AiObjectRequest {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        call: Option<SentCall>,
+        dropped: mpsc::UnboundedSender<Call<'static>>,
+    ) -> Self {
+        AiObjectRequest {
+            rx,
+            request_id,
+            call,
+            dropped,
+        }
+    }
+
+    /// The request ID allocated for this request.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+}
+
+impl Future for AiObjectRequest {
+    type Output = Result<AiObject, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(OwnedSimConnectRecv::AssignedObjectId(assigned))) => {
+                    let object_id = assigned.object_id();
+                    // The object is removed under the request ID it was
+                    // created with.
+                    let remove = Call::AiRemoveObject {
+                        object_id: object_id.0,
+                        request_id: self.request_id,
+                    };
+                    return Poll::Ready(Ok(RequestHandle::new(
+                        object_id,
+                        remove,
+                        self.dropped.clone(),
+                    )));
+                }
+                Poll::Ready(Some(OwnedSimConnectRecv::Exception(exception))) => {
+                    let exception = Exception::new(&exception, self.call.take());
+                    return Poll::Ready(Err(exception.into()));
+                }
+                Poll::Ready(Some(_)) => {}
+                Poll::Ready(None) => return Poll::Ready(Err(RequestError::Closed)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for AiObjectRequest {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("AiObjectRequest")
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
@@ -0,0 +1,351 @@
+use super::Result;
+use crate::sys;
+use std::ffi::CStr;
+
+/// A single SimConnect API call, as performed by a [`Backend`]. Each variant
+/// mirrors the `SimConnect_*` function of the same name.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Call<'a> {
+    MapClientEventToSimEvent {
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        event_name: &'a CStr,
+    },
+    TransmitClientEvent {
+        object_id: sys::SIMCONNECT_OBJECT_ID,
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        data: sys::DWORD,
+        group_id: sys::SIMCONNECT_NOTIFICATION_GROUP_ID,
+        flags: sys::SIMCONNECT_EVENT_FLAG,
+    },
+    TransmitClientEventEx1 {
+        object_id: sys::SIMCONNECT_OBJECT_ID,
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        group_id: sys::SIMCONNECT_NOTIFICATION_GROUP_ID,
+        flags: sys::SIMCONNECT_EVENT_FLAG,
+        data: [sys::DWORD; 5],
+    },
+    SetSystemEventState {
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        state: sys::SIMCONNECT_STATE,
+    },
+    AddClientEventToNotificationGroup {
+        group_id: sys::SIMCONNECT_NOTIFICATION_GROUP_ID,
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        maskable: bool,
+    },
+    RemoveClientEvent {
+        group_id: sys::SIMCONNECT_NOTIFICATION_GROUP_ID,
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+    },
+    SetNotificationGroupPriority {
+        group_id: sys::SIMCONNECT_NOTIFICATION_GROUP_ID,
+        priority: sys::DWORD,
+    },
+    ClearNotificationGroup {
+        group_id: sys::SIMCONNECT_NOTIFICATION_GROUP_ID,
+    },
+    RequestNotificationGroup {
+        group_id: sys::SIMCONNECT_NOTIFICATION_GROUP_ID,
+        reserved: sys::DWORD,
+        flags: sys::DWORD,
+    },
+    MapInputEventToClientEvent {
+        group_id: sys::SIMCONNECT_INPUT_GROUP_ID,
+        input_definition: &'a CStr,
+        down_event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        down_value: sys::DWORD,
+        up_event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        up_value: sys::DWORD,
+        maskable: bool,
+    },
+    SetInputGroupPriority {
+        group_id: sys::SIMCONNECT_INPUT_GROUP_ID,
+        priority: sys::DWORD,
+    },
+    RemoveInputEvent {
+        group_id: sys::SIMCONNECT_INPUT_GROUP_ID,
+        input_definition: &'a CStr,
+    },
+    ClearInputGroup {
+        group_id: sys::SIMCONNECT_INPUT_GROUP_ID,
+    },
+    SetInputGroupState {
+        group_id: sys::SIMCONNECT_INPUT_GROUP_ID,
+        state: sys::DWORD,
+    },
+    RequestReservedKey {
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        key_choice_1: &'a CStr,
+        key_choice_2: Option<&'a CStr>,
+        key_choice_3: Option<&'a CStr>,
+    },
+    AddToDataDefinition {
+        define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+        datum_name: &'a CStr,
+        units_name: &'a CStr,
+        datum_type: sys::SIMCONNECT_DATATYPE,
+        epsilon: f32,
+        datum_id: sys::DWORD,
+    },
+    ClearDataDefinition {
+        define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+    },
+    RequestDataOnSimObject {
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+        object_id: sys::SIMCONNECT_OBJECT_ID,
+        period: sys::SIMCONNECT_PERIOD,
+        flags: sys::SIMCONNECT_DATA_REQUEST_FLAG,
+        origin: sys::DWORD,
+        interval: sys::DWORD,
+        limit: sys::DWORD,
+    },
+    RequestDataOnSimObjectType {
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+        radius: sys::DWORD,
+        r#type: sys::SIMCONNECT_SIMOBJECT_TYPE,
+    },
+    SetDataOnSimObject {
+        define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+        object_id: sys::SIMCONNECT_OBJECT_ID,
+        flags: sys::SIMCONNECT_DATA_SET_FLAG,
+        array_count: sys::DWORD,
+        unit_size: sys::DWORD,
+        data: &'a [u8],
+    },
+    SubscribeToSystemEvent {
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        system_event_name: &'a CStr,
+    },
+    UnsubscribeFromSystemEvent {
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+    },
+    AiCreateParkedAtcAircraft {
+        container_title: &'a CStr,
+        tail_number: &'a CStr,
+        airport_id: &'a CStr,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    AiCreateEnrouteAtcAircraft {
+        container_title: &'a CStr,
+        tail_number: &'a CStr,
+        flight_number: i32,
+        flight_plan_path: &'a CStr,
+        flight_plan_position: f64,
+        touch_and_go: bool,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    AiCreateNonAtcAircraft {
+        container_title: &'a CStr,
+        tail_number: &'a CStr,
+        init_position: sys::SIMCONNECT_DATA_INITPOSITION,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    AiCreateSimulatedObject {
+        container_title: &'a CStr,
+        init_position: sys::SIMCONNECT_DATA_INITPOSITION,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    AiReleaseControl {
+        object_id: sys::SIMCONNECT_OBJECT_ID,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    AiRemoveObject {
+        object_id: sys::SIMCONNECT_OBJECT_ID,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    AiSetAircraftFlightPlan {
+        object_id: sys::SIMCONNECT_OBJECT_ID,
+        flight_plan_path: &'a CStr,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    RequestSystemState {
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        state: &'a CStr,
+    },
+    MapClientDataNameToId {
+        client_data_name: &'a CStr,
+        client_data_id: sys::SIMCONNECT_CLIENT_DATA_ID,
+    },
+    CreateClientData {
+        client_data_id: sys::SIMCONNECT_CLIENT_DATA_ID,
+        size: sys::DWORD,
+        flags: sys::SIMCONNECT_CREATE_CLIENT_DATA_FLAG,
+    },
+    AddToClientDataDefinition {
+        define_id: sys::SIMCONNECT_CLIENT_DATA_DEFINITION_ID,
+        offset: sys::DWORD,
+        size_or_type: sys::DWORD,
+        epsilon: f32,
+        datum_id: sys::DWORD,
+    },
+    RequestClientData {
+        client_data_id: sys::SIMCONNECT_CLIENT_DATA_ID,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        define_id: sys::SIMCONNECT_CLIENT_DATA_DEFINITION_ID,
+        period: sys::SIMCONNECT_CLIENT_DATA_PERIOD,
+        flags: sys::SIMCONNECT_CLIENT_DATA_REQUEST_FLAG,
+        origin: sys::DWORD,
+        interval: sys::DWORD,
+        limit: sys::DWORD,
+    },
+    SetClientData {
+        client_data_id: sys::SIMCONNECT_CLIENT_DATA_ID,
+        define_id: sys::SIMCONNECT_CLIENT_DATA_DEFINITION_ID,
+        flags: sys::SIMCONNECT_CLIENT_DATA_SET_FLAG,
+        data: &'a [u8],
+    },
+    FlightLoad {
+        file_name: &'a CStr,
+    },
+    FlightSave {
+        file_name: &'a CStr,
+        title: Option<&'a CStr>,
+        description: Option<&'a CStr>,
+        flags: sys::DWORD,
+    },
+    FlightPlanLoad {
+        file_name: &'a CStr,
+    },
+    SubscribeToFacilities {
+        r#type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    UnsubscribeToFacilities {
+        r#type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+    },
+    RequestFacilitiesList {
+        r#type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    SubscribeToFacilitiesEx1 {
+        r#type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+        in_range_request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        out_of_range_request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    UnsubscribeToFacilitiesEx1 {
+        r#type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+        in_range: bool,
+        out_of_range: bool,
+    },
+    RequestFacilitiesListEx1 {
+        r#type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    AddToFacilityDefinition {
+        define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+        field_name: &'a CStr,
+    },
+    RequestFacilityData {
+        define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        icao: &'a CStr,
+        region: Option<&'a CStr>,
+    },
+    EnumerateInputEvents {
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    },
+    GetInputEvent {
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        hash: sys::UINT64,
+    },
+    SetInputEvent {
+        hash: sys::UINT64,
+        value: &'a [u8],
+    },
+    SubscribeInputEvent {
+        hash: sys::UINT64,
+    },
+    UnsubscribeInputEvent {
+        hash: sys::UINT64,
+    },
+    EnumerateInputEventParams {
+        hash: sys::UINT64,
+    },
+}
+
+impl Call<'_> {
+    /// The name of the `SimConnect_*` function this call mirrors.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Call::MapClientEventToSimEvent { .. } => "MapClientEventToSimEvent",
+            Call::TransmitClientEvent { .. } => "TransmitClientEvent",
+            Call::TransmitClientEventEx1 { .. } => "TransmitClientEventEx1",
+            Call::SetSystemEventState { .. } => "SetSystemEventState",
+            Call::AddClientEventToNotificationGroup { .. } => "AddClientEventToNotificationGroup",
+            Call::RemoveClientEvent { .. } => "RemoveClientEvent",
+            Call::SetNotificationGroupPriority { .. } => "SetNotificationGroupPriority",
+            Call::ClearNotificationGroup { .. } => "ClearNotificationGroup",
+            Call::RequestNotificationGroup { .. } => "RequestNotificationGroup",
+            Call::MapInputEventToClientEvent { .. } => "MapInputEventToClientEvent",
+            Call::SetInputGroupPriority { .. } => "SetInputGroupPriority",
+            Call::RemoveInputEvent { .. } => "RemoveInputEvent",
+            Call::ClearInputGroup { .. } => "ClearInputGroup",
+            Call::SetInputGroupState { .. } => "SetInputGroupState",
+            Call::RequestReservedKey { .. } => "RequestReservedKey",
+            Call::AddToDataDefinition { .. } => "AddToDataDefinition",
+            Call::ClearDataDefinition { .. } => "ClearDataDefinition",
+            Call::RequestDataOnSimObject { .. } => "RequestDataOnSimObject",
+            Call::RequestDataOnSimObjectType { .. } => "RequestDataOnSimObjectType",
+            Call::SetDataOnSimObject { .. } => "SetDataOnSimObject",
+            Call::SubscribeToSystemEvent { .. } => "SubscribeToSystemEvent",
+            Call::UnsubscribeFromSystemEvent { .. } => "UnsubscribeFromSystemEvent",
+            Call::AiCreateParkedAtcAircraft { .. } => "AiCreateParkedAtcAircraft",
+            Call::AiCreateEnrouteAtcAircraft { .. } => "AiCreateEnrouteAtcAircraft",
+            Call::AiCreateNonAtcAircraft { .. } => "AiCreateNonAtcAircraft",
+            Call::AiCreateSimulatedObject { .. } => "AiCreateSimulatedObject",
+            Call::AiReleaseControl { .. } => "AiReleaseControl",
+            Call::AiRemoveObject { .. } => "AiRemoveObject",
+            Call::AiSetAircraftFlightPlan { .. } => "AiSetAircraftFlightPlan",
+            Call::RequestSystemState { .. } => "RequestSystemState",
+            Call::MapClientDataNameToId { .. } => "MapClientDataNameToId",
+            Call::CreateClientData { .. } => "CreateClientData",
+            Call::AddToClientDataDefinition { .. } => "AddToClientDataDefinition",
+            Call::RequestClientData { .. } => "RequestClientData",
+            Call::SetClientData { .. } => "SetClientData",
+            Call::FlightLoad { .. } => "FlightLoad",
+            Call::FlightSave { .. } => "FlightSave",
+            Call::FlightPlanLoad { .. } => "FlightPlanLoad",
+            Call::SubscribeToFacilities { .. } => "SubscribeToFacilities",
+            Call::UnsubscribeToFacilities { .. } => "UnsubscribeToFacilities",
+            Call::RequestFacilitiesList { .. } => "RequestFacilitiesList",
+            Call::SubscribeToFacilitiesEx1 { .. } => "SubscribeToFacilitiesEx1",
+            Call::UnsubscribeToFacilitiesEx1 { .. } => "UnsubscribeToFacilitiesEx1",
+            Call::RequestFacilitiesListEx1 { .. } => "RequestFacilitiesListEx1",
+            Call::AddToFacilityDefinition { .. } => "AddToFacilityDefinition",
+            Call::RequestFacilityData { .. } => "RequestFacilityData",
+            Call::EnumerateInputEvents { .. } => "EnumerateInputEvents",
+            Call::GetInputEvent { .. } => "GetInputEvent",
+            Call::SetInputEvent { .. } => "SetInputEvent",
+            Call::SubscribeInputEvent { .. } => "SubscribeInputEvent",
+            Call::UnsubscribeInputEvent { .. } => "UnsubscribeInputEvent",
+            Call::EnumerateInputEventParams { .. } => "EnumerateInputEventParams",
+        }
+    }
+}
+
+/// The transport used by a [`SimConnect`](super::SimConnect) session to talk
+/// to the sim. Calls are made from within `call_dispatch` whenever a message
+/// is handled by making another request, so every method takes `&self`.
+pub trait Backend {
+    /// Perform a single SimConnect API call.
+    fn call(&self, call: Call<'_>) -> Result<()>;
+
+    /// The ID of the last packet sent to the sim, as referenced by the
+    /// `dwSendID` of exceptions.
+    fn last_sent_packet_id(&self) -> Result<sys::DWORD>;
+
+    /// Deliver pending messages to `dispatch`, with the same contract as
+    /// `SimConnect_CallDispatch`.
+    ///
+    /// # Safety
+    /// `context` must remain valid for as long as `dispatch` may be called
+    /// with it.
+    unsafe fn call_dispatch(
+        &self,
+        dispatch: sys::DispatchProc,
+        context: *mut std::ffi::c_void,
+    ) -> Result<()>;
+}
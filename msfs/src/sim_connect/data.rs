@@ -0,0 +1,310 @@
+use super::{DataLatLonAlt, DataMarkerState, DataWaypoint, DataXYZ, DefineId, InitPosition};
+use crate::sys;
+
+/// An error decoding the data of a SimObjectData, ClientData or FacilityData
+/// event.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The type was never registered with the session, so no event can be for
+    /// it.
+    Unregistered { type_name: &'static str },
+    /// The size in the header disagrees with the size of the message received.
+    SizeMismatch { expected: usize, received: usize },
+    /// The event is for a different data definition.
+    DefinitionMismatch {
+        expected: DefineId,
+        received: DefineId,
+    },
+    /// The event has a different number of datums than the definition.
+    DefineCountMismatch { expected: usize, received: usize },
+    /// The data ended before every field was read.
+    Truncated,
+    /// A field held a value which is not valid for its type, such as an enum
+    /// discriminant which doesn't exist.
+    InvalidValue {
+        type_name: &'static str,
+        value: String,
+    },
+    /// The event is in the tagged format, which can only be decoded by a
+    /// [`TaggedSubscription`](super::TaggedSubscription).
+    Tagged,
+    /// A tagged event has a datum ID which is not in the definition.
+    UnknownDatum { datum_id: sys::DWORD },
+    /// Facility data is missing a block which the definition requires.
+    MissingBlock { block: &'static str },
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DecodeError::Unregistered { type_name } => {
+                write!(fmt, "{type_name} is not registered with this session")
+            }
+            DecodeError::SizeMismatch { expected, received } => write!(
+                fmt,
+                "expected a message of {expected} bytes, received {received}"
+            ),
+            DecodeError::DefinitionMismatch { expected, received } => write!(
+                fmt,
+                "expected data for definition {expected}, received {received}"
+            ),
+            DecodeError::DefineCountMismatch { expected, received } => {
+                write!(fmt, "expected {expected} datums, received {received}")
+            }
+            DecodeError::Truncated => fmt.write_str("data ended before every field was read"),
+            DecodeError::InvalidValue { type_name, value } => {
+                write!(fmt, "{value} is not a valid {type_name}")
+            }
+            DecodeError::Tagged => fmt.write_str("data is in the tagged format"),
+            DecodeError::UnknownDatum { datum_id } => {
+                write!(fmt, "datum {datum_id} is not in the definition")
+            }
+            DecodeError::MissingBlock { block } => {
+                write!(fmt, "facility data has no {block} block")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A fixed-length, nul-padded string field of a `data_definition` struct,
+/// such as `String64` for `SIMCONNECT_DATATYPE_STRING64`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FixedString<const N: usize>([u8; N]);
+
+pub type String8 = FixedString<8>;
+pub type String32 = FixedString<32>;
+pub type String64 = FixedString<64>;
+pub type String128 = FixedString<128>;
+pub type String256 = FixedString<256>;
+pub type String260 = FixedString<260>;
+
+impl<const N: usize> FixedString<N> {
+    /// Create a string from `value`, truncated to fit along with its nul
+    /// terminator.
+    pub fn new(value: &str) -> Self {
+        let mut bytes = [0; N];
+        let len = value.len().min(N.saturating_sub(1));
+        bytes[..len].copy_from_slice(&value.as_bytes()[..len]);
+        FixedString(bytes)
+    }
+
+    /// The bytes of the string, up to its nul terminator.
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = self.0.iter().position(|&b| b == 0).unwrap_or(N);
+        &self.0[..len]
+    }
+
+    /// The string, if it is valid UTF-8.
+    pub fn to_str(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(self.as_bytes())
+    }
+
+    /// The string, with any invalid UTF-8 replaced.
+    pub fn to_string_lossy(&self) -> std::borrow::Cow<'_, str> {
+        String::from_utf8_lossy(self.as_bytes())
+    }
+}
+
+impl<const N: usize> Default for FixedString<N> {
+    fn default() -> Self {
+        FixedString([0; N])
+    }
+}
+
+impl<const N: usize> From<&str> for FixedString<N> {
+    fn from(value: &str) -> Self {
+        FixedString::new(value)
+    }
+}
+
+impl<const N: usize> std::fmt::Debug for FixedString<N> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.to_string_lossy(), fmt)
+    }
+}
+
+impl<const N: usize> std::fmt::Display for FixedString<N> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.to_string_lossy(), fmt)
+    }
+}
+
+/// A type which can be a field of a `data_definition` struct, read from and
+/// written to SimConnect data in its wire format.
+#[doc(hidden)]
+pub trait DataField: Sized {
+    /// Read a value from the front of `data`, advancing it.
+    fn read(data: &mut &[u8]) -> Result<Self, DecodeError>;
+
+    /// Append the value to `out`.
+    fn write(&self, out: &mut Vec<u8>);
+}
+
+pub(super) fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if data.len() < len {
+        return Err(DecodeError::Truncated);
+    }
+    let (bytes, rest) = data.split_at(len);
+    *data = rest;
+    Ok(bytes)
+}
+
+macro_rules! data_field_pod {
+    ($($T:ty),*) => {
+        $(
+            impl DataField for $T {
+                fn read(data: &mut &[u8]) -> Result<Self, DecodeError> {
+                    let bytes = take(data, std::mem::size_of::<Self>())?;
+                    Ok(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const Self) })
+                }
+
+                fn write(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(super::as_bytes(self));
+                }
+            }
+        )*
+    };
+}
+
+data_field_pod!(
+    u8,
+    i8,
+    u16,
+    i16,
+    u32,
+    i32,
+    u64,
+    i64,
+    f32,
+    f64,
+    DataXYZ,
+    DataLatLonAlt,
+    DataWaypoint,
+    DataMarkerState,
+    InitPosition
+);
+
+impl<const N: usize> DataField for FixedString<N> {
+    fn read(data: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(FixedString(take(data, N)?.try_into().unwrap()))
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.0);
+    }
+}
+
+/// Sent as `INT32`.
+impl DataField for bool {
+    fn read(data: &mut &[u8]) -> Result<Self, DecodeError> {
+        i32::read(data).map(|value| value != 0)
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        (*self as i32).write(out);
+    }
+}
+
+/// Read a `bool` field of a `client_data_definition` struct, which is stored
+/// as a single byte rather than sent as an `INT32`.
+#[doc(hidden)]
+pub fn read_client_bool(data: &mut &[u8]) -> Result<bool, DecodeError> {
+    match u8::read(data)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        value => Err(DecodeError::InvalidValue {
+            type_name: "bool",
+            value: value.to_string(),
+        }),
+    }
+}
+
+#[doc(hidden)]
+pub fn write_client_bool(value: &bool, out: &mut Vec<u8>) {
+    out.push(*value as u8);
+}
+
+/// Sent as `STRINGV`, a nul-terminated string of variable length.
+impl DataField for String {
+    fn read(data: &mut &[u8]) -> Result<Self, DecodeError> {
+        let len = data
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(DecodeError::Truncated)?;
+        let bytes = take(data, len + 1)?;
+        Ok(String::from_utf8_lossy(&bytes[..len]).into_owned())
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+        out.push(0);
+    }
+}
+
+/// Sent as one datum per element, for indexed simvars.
+impl<T: DataField, const N: usize> DataField for [T; N] {
+    fn read(data: &mut &[u8]) -> Result<Self, DecodeError> {
+        let values = (0..N)
+            .map(|_| T::read(data))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(values.try_into().ok().unwrap())
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        for value in self {
+            value.write(out);
+        }
+    }
+}
+
+/// Read a field sent as `R`, such as a C-like enum or newtype, converting it
+/// with `TryFrom`.
+#[doc(hidden)]
+pub fn read_converted<R, T>(data: &mut &[u8]) -> Result<T, DecodeError>
+where
+    R: DataField + Copy + std::fmt::Debug,
+    T: TryFrom<R>,
+{
+    let raw = R::read(data)?;
+    T::try_from(raw).map_err(|_| DecodeError::InvalidValue {
+        type_name: std::any::type_name::<T>(),
+        value: format!("{raw:?}"),
+    })
+}
+
+/// Write a field sent as `R`, converting it with `From`.
+#[doc(hidden)]
+pub fn write_converted<R, T>(value: &T, out: &mut Vec<u8>)
+where
+    R: DataField + From<T>,
+    T: Clone,
+{
+    R::from(value.clone()).write(out);
+}
+
+#[doc(hidden)]
+pub fn read_converted_array<R, T, const N: usize>(data: &mut &[u8]) -> Result<[T; N], DecodeError>
+where
+    R: DataField + Copy + std::fmt::Debug,
+    T: TryFrom<R>,
+{
+    let values = (0..N)
+        .map(|_| read_converted::<R, T>(data))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(values.try_into().ok().unwrap())
+}
+
+#[doc(hidden)]
+pub fn write_converted_array<R, T, const N: usize>(values: &[T; N], out: &mut Vec<u8>)
+where
+    R: DataField + From<T>,
+    T: Clone,
+{
+    for value in values {
+        write_converted::<R, T>(value, out);
+    }
+}
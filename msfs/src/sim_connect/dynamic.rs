@@ -0,0 +1,271 @@
+use super::data::{
+    DataField, DecodeError, String8, String32, String64, String128, String256, String260, take,
+};
+use super::{DataLatLonAlt, DataMarkerState, DataWaypoint, DataXYZ, DefineId, InitPosition, Recv};
+use crate::sys;
+use std::collections::HashMap;
+
+/// The type a datum of a [`DynamicDataDefinition`] is sent as.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DataType {
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    String8,
+    String32,
+    String64,
+    String128,
+    String256,
+    String260,
+    /// A nul-terminated string of variable length.
+    StringV,
+    InitPosition,
+    MarkerState,
+    Waypoint,
+    LatLonAlt,
+    Xyz,
+}
+
+impl DataType {
+    /// The `SIMCONNECT_DATATYPE` for this type.
+    pub fn sys(self) -> sys::SIMCONNECT_DATATYPE {
+        match self {
+            DataType::Int32 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT32,
+            DataType::Int64 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT64,
+            DataType::Float32 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT32,
+            DataType::Float64 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64,
+            DataType::String8 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING8,
+            DataType::String32 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING32,
+            DataType::String64 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING64,
+            DataType::String128 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING128,
+            DataType::String256 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING256,
+            DataType::String260 => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING260,
+            DataType::StringV => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRINGV,
+            DataType::InitPosition => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INITPOSITION,
+            DataType::MarkerState => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_MARKERSTATE,
+            DataType::Waypoint => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_WAYPOINT,
+            DataType::LatLonAlt => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_LATLONALT,
+            DataType::Xyz => sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_XYZ,
+        }
+    }
+
+    /// The type for a `SIMCONNECT_DATATYPE`, if it is one which can be received.
+    pub(super) fn from_sys(data_type: sys::SIMCONNECT_DATATYPE) -> Option<Self> {
+        Some(match data_type {
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT32 => DataType::Int32,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT64 => DataType::Int64,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT32 => DataType::Float32,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64 => DataType::Float64,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING8 => DataType::String8,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING32 => DataType::String32,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING64 => DataType::String64,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING128 => DataType::String128,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING256 => DataType::String256,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING260 => DataType::String260,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRINGV => DataType::StringV,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INITPOSITION => DataType::InitPosition,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_MARKERSTATE => DataType::MarkerState,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_WAYPOINT => DataType::Waypoint,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_LATLONALT => DataType::LatLonAlt,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_XYZ => DataType::Xyz,
+            _ => return None,
+        })
+    }
+
+    /// The size of a datum of this type in received data, or `None` for
+    /// `StringV`, whose size depends on its value.
+    pub fn size(self) -> Option<usize> {
+        Some(match self {
+            DataType::Int32 | DataType::Float32 => 4,
+            DataType::Int64 | DataType::Float64 | DataType::String8 => 8,
+            DataType::String32 => 32,
+            DataType::String64 => 64,
+            DataType::String128 => 128,
+            DataType::String256 => 256,
+            DataType::String260 => 260,
+            DataType::StringV => return None,
+            DataType::InitPosition => std::mem::size_of::<InitPosition>(),
+            DataType::MarkerState => std::mem::size_of::<DataMarkerState>(),
+            DataType::Waypoint => std::mem::size_of::<DataWaypoint>(),
+            DataType::LatLonAlt => std::mem::size_of::<DataLatLonAlt>(),
+            DataType::Xyz => std::mem::size_of::<DataXYZ>(),
+        })
+    }
+
+    /// Take the bytes of a datum of this type from the front of `data`,
+    /// advancing it.
+    pub(super) fn take<'a>(self, data: &mut &'a [u8]) -> Result<&'a [u8], DecodeError> {
+        let len = match self.size() {
+            Some(size) => size,
+            None => {
+                data.iter()
+                    .position(|&b| b == 0)
+                    .ok_or(DecodeError::Truncated)?
+                    + 1
+            }
+        };
+        take(data, len)
+    }
+
+    /// Read a datum of this type from the front of `data`, advancing it.
+    fn read(self, data: &mut &[u8]) -> Result<Value, DecodeError> {
+        Ok(match self {
+            DataType::Int32 => Value::Int32(DataField::read(data)?),
+            DataType::Int64 => Value::Int64(DataField::read(data)?),
+            DataType::Float32 => Value::Float32(DataField::read(data)?),
+            DataType::Float64 => Value::Float64(DataField::read(data)?),
+            DataType::String8 => Value::String(String8::read(data)?.to_string_lossy().into()),
+            DataType::String32 => Value::String(String32::read(data)?.to_string_lossy().into()),
+            DataType::String64 => Value::String(String64::read(data)?.to_string_lossy().into()),
+            DataType::String128 => Value::String(String128::read(data)?.to_string_lossy().into()),
+            DataType::String256 => Value::String(String256::read(data)?.to_string_lossy().into()),
+            DataType::String260 => Value::String(String260::read(data)?.to_string_lossy().into()),
+            DataType::StringV => Value::String(DataField::read(data)?),
+            DataType::InitPosition => Value::InitPosition(DataField::read(data)?),
+            DataType::MarkerState => Value::MarkerState(DataField::read(data)?),
+            DataType::Waypoint => Value::Waypoint(DataField::read(data)?),
+            DataType::LatLonAlt => Value::LatLonAlt(DataField::read(data)?),
+            DataType::Xyz => Value::Xyz(DataField::read(data)?),
+        })
+    }
+}
+
+/// The value of a datum of a [`DynamicDataDefinition`].
+#[derive(Debug, Clone)]
+pub enum Value {
+    Int32(i32),
+    Int64(i64),
+    Float32(f32),
+    Float64(f64),
+    String(String),
+    InitPosition(InitPosition),
+    MarkerState(DataMarkerState),
+    Waypoint(DataWaypoint),
+    LatLonAlt(DataLatLonAlt),
+    Xyz(DataXYZ),
+}
+
+impl Value {
+    /// The value as a number, if it is one.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Int32(v) => Some(v as f64),
+            Value::Int64(v) => Some(v as f64),
+            Value::Float32(v) => Some(v as f64),
+            Value::Float64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value as a string, if it is one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Datum {
+    pub(super) name: String,
+    pub(super) unit: String,
+    pub(super) epsilon: f32,
+    pub(super) data_type: DataType,
+}
+
+/// A data definition built at runtime, for when the simvars to read are not
+/// known at compile time.
+/// ```rs
+/// let mut definition = DynamicDataDefinition::new();
+/// definition.add("PLANE ALTITUDE", "feet", DataType::Float64);
+/// definition.add("ATC ID", "", DataType::String32);
+/// sim.add_dynamic_data_definition(&mut definition)?;
+/// let _request = sim.request_dynamic_data_on_sim_object(RequestId(0), &definition, ObjectId::USER, Period::Second)?;
+///
+/// // In the callback:
+/// SimConnectRecv::SimObjectData(event) => {
+///     let values = definition.decode(event)?;
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DynamicDataDefinition {
+    pub(super) datums: Vec<Datum>,
+    pub(super) define_id: Option<sys::SIMCONNECT_DATA_DEFINITION_ID>,
+}
+
+impl DynamicDataDefinition {
+    /// Create an empty definition.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a simvar to the definition. Changes only take effect once the
+    /// definition is added to a `SimConnect` session again.
+    pub fn add(&mut self, name: &str, unit: &str, data_type: DataType) -> &mut Self {
+        self.add_with_epsilon(name, unit, data_type, 0.0)
+    }
+
+    /// Add a simvar to the definition, only considered changed when it moves
+    /// by more than `epsilon`.
+    pub fn add_with_epsilon(
+        &mut self,
+        name: &str,
+        unit: &str,
+        data_type: DataType,
+        epsilon: f32,
+    ) -> &mut Self {
+        self.datums.push(Datum {
+            name: name.to_string(),
+            unit: unit.to_string(),
+            epsilon,
+            data_type,
+        });
+        self
+    }
+
+    /// Remove all simvars from the definition.
+    pub fn clear(&mut self) {
+        self.datums.clear();
+    }
+
+    /// The names of the simvars in the definition, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.datums.iter().map(|datum| datum.name.as_str())
+    }
+
+    /// The ID this definition was given by `add_dynamic_data_definition`.
+    pub fn define_id(&self) -> Option<DefineId> {
+        self.define_id.map(DefineId)
+    }
+
+    /// Decode the values of a SimObjectData event for this definition, in the
+    /// order they were added. The event must be for the simvars the
+    /// definition had when it was last added to the session.
+    pub fn decode(
+        &self,
+        data: &Recv<sys::SIMCONNECT_RECV_SIMOBJECT_DATA>,
+    ) -> Result<Vec<Value>, DecodeError> {
+        let define_id = self.define_id.ok_or(DecodeError::Unregistered {
+            type_name: std::any::type_name::<Self>(),
+        })?;
+        super::check_header(data.bytes(), data, DefineId(define_id), self.datums.len())?;
+        let mut bytes = data.data();
+        self.datums
+            .iter()
+            .map(|datum| datum.data_type.read(&mut bytes))
+            .collect()
+    }
+
+    /// Decode the values of a SimObjectData event for this definition, keyed
+    /// by simvar name.
+    pub fn decode_map(
+        &self,
+        data: &Recv<sys::SIMCONNECT_RECV_SIMOBJECT_DATA>,
+    ) -> Result<HashMap<String, Value>, DecodeError> {
+        let values = self.decode(data)?;
+        Ok(self.names().map(str::to_string).zip(values).collect())
+    }
+}
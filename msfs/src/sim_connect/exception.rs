@@ -0,0 +1,224 @@
+use super::{Backend, Call, Result};
+use crate::sys;
+use std::collections::VecDeque;
+
+/// How many sent calls are remembered to correlate exceptions with.
+const MAX_SENT_CALLS: usize = 1024;
+
+macro_rules! exception_kinds {
+    ($( ($E:ident, $V:ident, $name:literal), )*) => {
+        /// The kind of an exception raised by the sim, see `SIMCONNECT_EXCEPTION`.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum ExceptionKind {
+            $(
+                $V,
+            )*
+            /// An exception not known to this version of the crate.
+            Unknown(sys::DWORD),
+        }
+
+        impl From<sys::DWORD> for ExceptionKind {
+            fn from(exception: sys::DWORD) -> Self {
+                $(
+                    if exception == sys::SIMCONNECT_EXCEPTION::$E as sys::DWORD {
+                        return ExceptionKind::$V;
+                    }
+                )*
+                ExceptionKind::Unknown(exception)
+            }
+        }
+
+        impl std::fmt::Display for ExceptionKind {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+                match self {
+                    $(
+                        ExceptionKind::$V => fmt.write_str($name),
+                    )*
+                    ExceptionKind::Unknown(exception) => write!(fmt, "UNKNOWN({exception})"),
+                }
+            }
+        }
+    };
+}
+
+exception_kinds! {
+    (SIMCONNECT_EXCEPTION_NONE, None, "NONE"),
+    (SIMCONNECT_EXCEPTION_ERROR, Error, "ERROR"),
+    (SIMCONNECT_EXCEPTION_SIZE_MISMATCH, SizeMismatch, "SIZE_MISMATCH"),
+    (SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID, UnrecognizedId, "UNRECOGNIZED_ID"),
+    (SIMCONNECT_EXCEPTION_UNOPENED, Unopened, "UNOPENED"),
+    (SIMCONNECT_EXCEPTION_VERSION_MISMATCH, VersionMismatch, "VERSION_MISMATCH"),
+    (SIMCONNECT_EXCEPTION_TOO_MANY_GROUPS, TooManyGroups, "TOO_MANY_GROUPS"),
+    (SIMCONNECT_EXCEPTION_NAME_UNRECOGNIZED, NameUnrecognized, "NAME_UNRECOGNIZED"),
+    (SIMCONNECT_EXCEPTION_TOO_MANY_EVENT_NAMES, TooManyEventNames, "TOO_MANY_EVENT_NAMES"),
+    (SIMCONNECT_EXCEPTION_EVENT_ID_DUPLICATE, EventIdDuplicate, "EVENT_ID_DUPLICATE"),
+    (SIMCONNECT_EXCEPTION_TOO_MANY_MAPS, TooManyMaps, "TOO_MANY_MAPS"),
+    (SIMCONNECT_EXCEPTION_TOO_MANY_OBJECTS, TooManyObjects, "TOO_MANY_OBJECTS"),
+    (SIMCONNECT_EXCEPTION_TOO_MANY_REQUESTS, TooManyRequests, "TOO_MANY_REQUESTS"),
+    (SIMCONNECT_EXCEPTION_WEATHER_INVALID_PORT, WeatherInvalidPort, "WEATHER_INVALID_PORT"),
+    (SIMCONNECT_EXCEPTION_WEATHER_INVALID_METAR, WeatherInvalidMetar, "WEATHER_INVALID_METAR"),
+    (
+        SIMCONNECT_EXCEPTION_WEATHER_UNABLE_TO_GET_OBSERVATION,
+        WeatherUnableToGetObservation,
+        "WEATHER_UNABLE_TO_GET_OBSERVATION"
+    ),
+    (
+        SIMCONNECT_EXCEPTION_WEATHER_UNABLE_TO_CREATE_STATION,
+        WeatherUnableToCreateStation,
+        "WEATHER_UNABLE_TO_CREATE_STATION"
+    ),
+    (
+        SIMCONNECT_EXCEPTION_WEATHER_UNABLE_TO_REMOVE_STATION,
+        WeatherUnableToRemoveStation,
+        "WEATHER_UNABLE_TO_REMOVE_STATION"
+    ),
+    (SIMCONNECT_EXCEPTION_INVALID_DATA_TYPE, InvalidDataType, "INVALID_DATA_TYPE"),
+    (SIMCONNECT_EXCEPTION_INVALID_DATA_SIZE, InvalidDataSize, "INVALID_DATA_SIZE"),
+    (SIMCONNECT_EXCEPTION_DATA_ERROR, DataError, "DATA_ERROR"),
+    (SIMCONNECT_EXCEPTION_INVALID_ARRAY, InvalidArray, "INVALID_ARRAY"),
+    (SIMCONNECT_EXCEPTION_CREATE_OBJECT_FAILED, CreateObjectFailed, "CREATE_OBJECT_FAILED"),
+    (SIMCONNECT_EXCEPTION_LOAD_FLIGHTPLAN_FAILED, LoadFlightplanFailed, "LOAD_FLIGHTPLAN_FAILED"),
+    (
+        SIMCONNECT_EXCEPTION_OPERATION_INVALID_FOR_OBJECT_TYPE,
+        OperationInvalidForObjectType,
+        "OPERATION_INVALID_FOR_OBJECT_TYPE"
+    ),
+    (SIMCONNECT_EXCEPTION_ILLEGAL_OPERATION, IllegalOperation, "ILLEGAL_OPERATION"),
+    (SIMCONNECT_EXCEPTION_ALREADY_SUBSCRIBED, AlreadySubscribed, "ALREADY_SUBSCRIBED"),
+    (SIMCONNECT_EXCEPTION_INVALID_ENUM, InvalidEnum, "INVALID_ENUM"),
+    (SIMCONNECT_EXCEPTION_DEFINITION_ERROR, DefinitionError, "DEFINITION_ERROR"),
+    (SIMCONNECT_EXCEPTION_DUPLICATE_ID, DuplicateId, "DUPLICATE_ID"),
+    (SIMCONNECT_EXCEPTION_DATUM_ID, DatumId, "DATUM_ID"),
+    (SIMCONNECT_EXCEPTION_OUT_OF_BOUNDS, OutOfBounds, "OUT_OF_BOUNDS"),
+    (SIMCONNECT_EXCEPTION_ALREADY_CREATED, AlreadyCreated, "ALREADY_CREATED"),
+    (
+        SIMCONNECT_EXCEPTION_OBJECT_OUTSIDE_REALITY_BUBBLE,
+        ObjectOutsideRealityBubble,
+        "OBJECT_OUTSIDE_REALITY_BUBBLE"
+    ),
+    (SIMCONNECT_EXCEPTION_OBJECT_CONTAINER, ObjectContainer, "OBJECT_CONTAINER"),
+    (SIMCONNECT_EXCEPTION_OBJECT_AI, ObjectAi, "OBJECT_AI"),
+    (SIMCONNECT_EXCEPTION_OBJECT_ATC, ObjectAtc, "OBJECT_ATC"),
+    (SIMCONNECT_EXCEPTION_OBJECT_SCHEDULE, ObjectSchedule, "OBJECT_SCHEDULE"),
+    (SIMCONNECT_EXCEPTION_JETWAY_DATA, JetwayData, "JETWAY_DATA"),
+    (SIMCONNECT_EXCEPTION_ACTION_NOT_FOUND, ActionNotFound, "ACTION_NOT_FOUND"),
+    (SIMCONNECT_EXCEPTION_NOT_AN_ACTION, NotAnAction, "NOT_AN_ACTION"),
+    (SIMCONNECT_EXCEPTION_INCORRECT_ACTION_PARAMS, IncorrectActionParams, "INCORRECT_ACTION_PARAMS"),
+    (SIMCONNECT_EXCEPTION_GET_INPUT_EVENT_FAILED, GetInputEventFailed, "GET_INPUT_EVENT_FAILED"),
+    (SIMCONNECT_EXCEPTION_SET_INPUT_EVENT_FAILED, SetInputEventFailed, "SET_INPUT_EVENT_FAILED"),
+}
+
+/// A call sent to the sim, remembered so that exceptions can be traced back
+/// to it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SentCall {
+    /// The ID of the packet, as returned by `SimConnect_GetLastSentPacketID`.
+    pub packet_id: sys::DWORD,
+    /// The `SimConnect` method which made the call.
+    pub method: &'static str,
+    /// The name of the underlying SimConnect API call, such as
+    /// `MapClientEventToSimEvent`.
+    pub call: &'static str,
+}
+
+/// An exception raised by the sim, decoded from a `SIMCONNECT_RECV_EXCEPTION`.
+#[derive(Debug, Clone)]
+pub struct Exception {
+    pub kind: ExceptionKind,
+    /// The index of the first parameter of the call which caused the
+    /// exception, starting at 1, or 0 if it is unknown.
+    pub index: sys::DWORD,
+    /// The ID of the packet which caused the exception.
+    pub send_id: sys::DWORD,
+    /// The call which caused the exception, if it is still remembered.
+    pub call: Option<SentCall>,
+}
+
+impl std::fmt::Display for Exception {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.call {
+            Some(call) => {
+                write!(fmt, "{} from `{}` (", self.kind, call.method)?;
+                if self.index != 0 {
+                    write!(fmt, "parameter {} of ", self.index)?;
+                }
+                write!(fmt, "{})", call.call)
+            }
+            None => write!(fmt, "{} from packet {}", self.kind, self.send_id),
+        }
+    }
+}
+
+impl std::error::Error for Exception {}
+
+impl Exception {
+    /// Decode `exception`, raised by the sent `call`.
+    pub(super) fn new(exception: &sys::SIMCONNECT_RECV_EXCEPTION, call: Option<SentCall>) -> Self {
+        Exception {
+            kind: ExceptionKind::from(exception.dwException),
+            index: exception.dwIndex,
+            send_id: exception.dwSendID,
+            call,
+        }
+    }
+}
+
+/// Sends calls through a [`Backend`], remembering the most recent ones. Only
+/// the names of the method and the call are kept, so that the calls made
+/// every frame don't allocate.
+pub(super) struct Sender<'a> {
+    pub(super) backend: Box<dyn Backend + 'a>,
+    method: &'static str,
+    sent: VecDeque<SentCall>,
+}
+
+impl<'a> Sender<'a> {
+    pub(super) fn new(backend: Box<dyn Backend + 'a>) -> Self {
+        Sender {
+            backend,
+            method: "",
+            sent: VecDeque::with_capacity(MAX_SENT_CALLS),
+        }
+    }
+
+    /// Set the `SimConnect` method responsible for the following calls.
+    pub(super) fn method(&mut self, method: &'static str) {
+        self.method = method;
+    }
+
+    pub(super) fn call(&mut self, call: Call<'_>) -> Result<()> {
+        let name = call.name();
+        self.backend.call(call)?;
+
+        let packet_id = self.backend.last_sent_packet_id()?;
+        if self.sent.len() == MAX_SENT_CALLS {
+            self.sent.pop_front();
+        }
+        self.sent.push_back(SentCall {
+            packet_id,
+            method: self.method,
+            call: name,
+        });
+        Ok(())
+    }
+
+    /// The call which was sent last.
+    pub(super) fn last(&self) -> Option<&SentCall> {
+        self.sent.back()
+    }
+
+    /// Find the call which was sent in the packet `packet_id`.
+    pub(super) fn find(&self, packet_id: sys::DWORD) -> Option<&SentCall> {
+        self.sent
+            .iter()
+            .rev()
+            .find(|call| call.packet_id == packet_id)
+    }
+}
+
+impl sys::SIMCONNECT_RECV_EXCEPTION {
+    /// Decode this exception, tracing it back to the call which caused it.
+    pub fn decode(&self, sim: &super::SimConnect) -> Exception {
+        Exception::new(self, sim.sender.find(self.dwSendID).cloned())
+    }
+}
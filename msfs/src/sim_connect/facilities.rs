@@ -0,0 +1,550 @@
+use super::{Exception, OwnedSimConnectRecv, Recv, RequestError, RequestId, SentCall, fixed_str};
+use crate::sys;
+use futures::channel::mpsc;
+use futures::stream::{FusedStream, Stream};
+use std::collections::{BTreeMap, VecDeque};
+use std::ffi::c_char;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A kind of facility which the sim can list: [`Airport`], [`Waypoint`],
+/// [`Ndb`] or [`Vor`].
+pub trait Facility: Sized + Unpin + 'static {
+    #[doc(hidden)]
+    const LIST_TYPE: sys::SIMCONNECT_FACILITY_LIST_TYPE;
+    #[doc(hidden)]
+    type Data: Copy;
+
+    #[doc(hidden)]
+    fn from_data(data: &Self::Data) -> Self;
+    #[doc(hidden)]
+    fn to_data(&self) -> Self::Data;
+    /// The header and facilities of `recv`, if it is a page of a list of this
+    /// kind of facility.
+    #[doc(hidden)]
+    fn page(
+        recv: &OwnedSimConnectRecv,
+    ) -> Option<(&sys::SIMCONNECT_RECV_FACILITIES_LIST, Vec<Self>)>;
+
+    /// The ICAO identifier of the facility.
+    fn ident(&self) -> &str;
+    /// The ICAO region of the facility.
+    fn region(&self) -> &str;
+}
+
+/// An airport, as listed by the sim.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Airport {
+    pub ident: String,
+    pub region: String,
+    /// In degrees.
+    pub latitude: f64,
+    /// In degrees.
+    pub longitude: f64,
+    /// In meters.
+    pub altitude: f64,
+}
+
+/// A waypoint, as listed by the sim.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waypoint {
+    pub ident: String,
+    pub region: String,
+    /// In degrees.
+    pub latitude: f64,
+    /// In degrees.
+    pub longitude: f64,
+    /// In meters.
+    pub altitude: f64,
+    /// The magnetic variation, in degrees.
+    pub mag_var: f32,
+}
+
+/// An NDB, as listed by the sim.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ndb {
+    pub ident: String,
+    pub region: String,
+    /// In degrees.
+    pub latitude: f64,
+    /// In degrees.
+    pub longitude: f64,
+    /// In meters.
+    pub altitude: f64,
+    /// The magnetic variation, in degrees.
+    pub mag_var: f32,
+    /// In Hz.
+    pub frequency: u32,
+}
+
+/// A VOR, as listed by the sim.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vor {
+    pub ident: String,
+    pub region: String,
+    /// In degrees.
+    pub latitude: f64,
+    /// In degrees.
+    pub longitude: f64,
+    /// In meters.
+    pub altitude: f64,
+    /// The magnetic variation, in degrees.
+    pub mag_var: f32,
+    /// In Hz.
+    pub frequency: u32,
+    /// The `SIMCONNECT_RECV_ID_VOR_LIST_HAS_*` flags of the VOR.
+    pub flags: sys::DWORD,
+    /// The heading of the localizer, in degrees.
+    pub localizer: f32,
+    /// The latitude of the glide slope transmitter, in degrees.
+    pub glide_lat: f64,
+    /// The longitude of the glide slope transmitter, in degrees.
+    pub glide_lon: f64,
+    /// The altitude of the glide slope transmitter, in meters.
+    pub glide_alt: f64,
+    /// The angle of the glide slope, in degrees.
+    pub glide_slope_angle: f32,
+}
+
+impl Vor {
+    pub fn has_nav_signal(&self) -> bool {
+        self.flags & sys::SIMCONNECT_RECV_ID_VOR_LIST_HAS_NAV_SIGNAL != 0
+    }
+
+    pub fn has_localizer(&self) -> bool {
+        self.flags & sys::SIMCONNECT_RECV_ID_VOR_LIST_HAS_LOCALIZER != 0
+    }
+
+    pub fn has_glide_slope(&self) -> bool {
+        self.flags & sys::SIMCONNECT_RECV_ID_VOR_LIST_HAS_GLIDE_SLOPE != 0
+    }
+
+    pub fn has_dme(&self) -> bool {
+        self.flags & sys::SIMCONNECT_RECV_ID_VOR_LIST_HAS_DME != 0
+    }
+}
+
+/// Write `value` into a fixed-size, nul-terminated field, truncating it if
+/// needed.
+fn fixed_chars<const N: usize>(value: &str) -> [c_char; N] {
+    let mut chars = [0; N];
+    for (c, &b) in chars.iter_mut().zip(value.as_bytes().iter().take(N - 1)) {
+        *c = b as c_char;
+    }
+    chars
+}
+
+fn airport_data(
+    ident: &str,
+    region: &str,
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+) -> sys::SIMCONNECT_DATA_FACILITY_AIRPORT {
+    sys::SIMCONNECT_DATA_FACILITY_AIRPORT {
+        Ident: fixed_chars(ident),
+        Region: fixed_chars(region),
+        Latitude: latitude,
+        Longitude: longitude,
+        Altitude: altitude,
+    }
+}
+
+/// Read the facilities which follow the header of a list message.
+fn entries<T: Facility>(bytes: &[u8], list: &sys::SIMCONNECT_RECV_FACILITIES_LIST) -> Vec<T> {
+    let offset = std::mem::size_of::<sys::SIMCONNECT_RECV_FACILITIES_LIST>();
+    bytes
+        .get(offset..)
+        .unwrap_or_default()
+        .chunks_exact(std::mem::size_of::<T::Data>())
+        .take(list.dwArraySize as usize)
+        .map(|chunk| T::from_data(&unsafe { std::ptr::read_unaligned(chunk.as_ptr().cast()) }))
+        .collect()
+}
+
+macro_rules! facility {
+    ($T:ident, $Data:ident, $List:ident, $E:ident, $list_type:ident) => {
+        impl Facility for $T {
+            const LIST_TYPE: sys::SIMCONNECT_FACILITY_LIST_TYPE = sys::$list_type;
+            type Data = sys::$Data;
+
+            fn from_data(data: &sys::$Data) -> Self {
+                $T::decode(data)
+            }
+
+            fn to_data(&self) -> sys::$Data {
+                self.encode()
+            }
+
+            fn page(
+                recv: &OwnedSimConnectRecv,
+            ) -> Option<(&sys::SIMCONNECT_RECV_FACILITIES_LIST, Vec<Self>)> {
+                match recv {
+                    OwnedSimConnectRecv::$E(list) => Some((&list._base, list.facilities())),
+                    _ => None,
+                }
+            }
+
+            fn ident(&self) -> &str {
+                &self.ident
+            }
+
+            fn region(&self) -> &str {
+                &self.region
+            }
+        }
+
+        impl Recv<sys::$List> {
+            /// The facilities in this page of the list.
+            pub fn facilities(&self) -> Vec<$T> {
+                entries(self.bytes(), &self._base)
+            }
+        }
+    };
+}
+
+facility!(
+    Airport,
+    SIMCONNECT_DATA_FACILITY_AIRPORT,
+    SIMCONNECT_RECV_AIRPORT_LIST,
+    AirportList,
+    SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_AIRPORT
+);
+facility!(
+    Waypoint,
+    SIMCONNECT_DATA_FACILITY_WAYPOINT,
+    SIMCONNECT_RECV_WAYPOINT_LIST,
+    WaypointList,
+    SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_WAYPOINT
+);
+facility!(
+    Ndb,
+    SIMCONNECT_DATA_FACILITY_NDB,
+    SIMCONNECT_RECV_NDB_LIST,
+    NdbList,
+    SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_NDB
+);
+facility!(
+    Vor,
+    SIMCONNECT_DATA_FACILITY_VOR,
+    SIMCONNECT_RECV_VOR_LIST,
+    VorList,
+    SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_VOR
+);
+
+impl Airport {
+    fn decode(data: &sys::SIMCONNECT_DATA_FACILITY_AIRPORT) -> Self {
+        Airport {
+            ident: fixed_str(&data.Ident).into_owned(),
+            region: fixed_str(&data.Region).into_owned(),
+            latitude: data.Latitude,
+            longitude: data.Longitude,
+            altitude: data.Altitude,
+        }
+    }
+
+    fn encode(&self) -> sys::SIMCONNECT_DATA_FACILITY_AIRPORT {
+        airport_data(
+            &self.ident,
+            &self.region,
+            self.latitude,
+            self.longitude,
+            self.altitude,
+        )
+    }
+}
+
+impl Waypoint {
+    fn decode(data: &sys::SIMCONNECT_DATA_FACILITY_WAYPOINT) -> Self {
+        let Airport {
+            ident,
+            region,
+            latitude,
+            longitude,
+            altitude,
+        } = Airport::decode(&data._base);
+        Waypoint {
+            ident,
+            region,
+            latitude,
+            longitude,
+            altitude,
+            mag_var: data.fMagVar,
+        }
+    }
+
+    fn encode(&self) -> sys::SIMCONNECT_DATA_FACILITY_WAYPOINT {
+        sys::SIMCONNECT_DATA_FACILITY_WAYPOINT {
+            _base: airport_data(
+                &self.ident,
+                &self.region,
+                self.latitude,
+                self.longitude,
+                self.altitude,
+            ),
+            fMagVar: self.mag_var,
+        }
+    }
+}
+
+impl Ndb {
+    fn decode(data: &sys::SIMCONNECT_DATA_FACILITY_NDB) -> Self {
+        let Waypoint {
+            ident,
+            region,
+            latitude,
+            longitude,
+            altitude,
+            mag_var,
+        } = Waypoint::decode(&data._base);
+        Ndb {
+            ident,
+            region,
+            latitude,
+            longitude,
+            altitude,
+            mag_var,
+            frequency: data.fFrequency,
+        }
+    }
+
+    fn encode(&self) -> sys::SIMCONNECT_DATA_FACILITY_NDB {
+        sys::SIMCONNECT_DATA_FACILITY_NDB {
+            _base: sys::SIMCONNECT_DATA_FACILITY_WAYPOINT {
+                _base: airport_data(
+                    &self.ident,
+                    &self.region,
+                    self.latitude,
+                    self.longitude,
+                    self.altitude,
+                ),
+                fMagVar: self.mag_var,
+            },
+            fFrequency: self.frequency,
+        }
+    }
+}
+
+impl Vor {
+    fn decode(data: &sys::SIMCONNECT_DATA_FACILITY_VOR) -> Self {
+        let Ndb {
+            ident,
+            region,
+            latitude,
+            longitude,
+            altitude,
+            mag_var,
+            frequency,
+        } = Ndb::decode(&data._base);
+        Vor {
+            ident,
+            region,
+            latitude,
+            longitude,
+            altitude,
+            mag_var,
+            frequency,
+            flags: data.Flags,
+            localizer: data.fLocalizer,
+            glide_lat: data.GlideLat,
+            glide_lon: data.GlideLon,
+            glide_alt: data.GlideAlt,
+            glide_slope_angle: data.fGlideSlopeAngle,
+        }
+    }
+
+    fn encode(&self) -> sys::SIMCONNECT_DATA_FACILITY_VOR {
+        sys::SIMCONNECT_DATA_FACILITY_VOR {
+            _base: sys::SIMCONNECT_DATA_FACILITY_NDB {
+                _base: sys::SIMCONNECT_DATA_FACILITY_WAYPOINT {
+                    _base: airport_data(
+                        &self.ident,
+                        &self.region,
+                        self.latitude,
+                        self.longitude,
+                        self.altitude,
+                    ),
+                    fMagVar: self.mag_var,
+                },
+                fFrequency: self.frequency,
+            },
+            Flags: self.flags,
+            fLocalizer: self.localizer,
+            GlideLat: self.glide_lat,
+            GlideLon: self.glide_lon,
+            GlideAlt: self.glide_alt,
+            fGlideSlopeAngle: self.glide_slope_angle,
+        }
+    }
+}
+
+impl sys::SIMCONNECT_RECV_FACILITIES_LIST {
+    /// The ID of the request.
+    pub fn id(&self) -> RequestId {
+        RequestId(self.dwRequestID)
+    }
+
+    /// The index of this page of the list, starting at 0.
+    pub fn entry_number(&self) -> sys::DWORD {
+        self.dwEntryNumber
+    }
+
+    /// The number of pages the list is sent in.
+    pub fn out_of(&self) -> sys::DWORD {
+        self.dwOutOf
+    }
+}
+
+/// A request for a list of facilities, created by
+/// [`SimConnect::request_facilities_list`](super::SimConnect::request_facilities_list).
+/// It resolves once every page of the list has been received.
+pub struct FacilitiesList<T> {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    /// The call which made the request, to describe its exceptions.
+    call: Option<SentCall>,
+    /// The pages received so far, by entry number.
+    pages: BTreeMap<sys::DWORD, Vec<T>>,
+}
+
+impl<T: Facility> FacilitiesList<T> {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        call: Option<SentCall>,
+    ) -> Self {
+        FacilitiesList {
+            rx,
+            request_id,
+            call,
+            pages: BTreeMap::new(),
+        }
+    }
+
+    /// The request ID allocated for this request.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+}
+
+impl<T: Facility> Future for FacilitiesList<T> {
+    type Output = Result<Vec<T>, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(OwnedSimConnectRecv::Exception(exception))) => {
+                    let exception = Exception::new(&exception, self.call.take());
+                    return Poll::Ready(Err(exception.into()));
+                }
+                Poll::Ready(Some(recv)) => {
+                    let Some((list, facilities)) = T::page(&recv) else {
+                        continue;
+                    };
+                    // An empty list is still sent as a single page.
+                    let out_of = list.out_of().max(1) as usize;
+                    self.pages.insert(list.entry_number(), facilities);
+                    if self.pages.len() >= out_of {
+                        let pages = std::mem::take(&mut self.pages);
+                        return Poll::Ready(Ok(pages.into_values().flatten().collect()));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(Err(RequestError::Closed)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for FacilitiesList<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("FacilitiesList")
+            .field("type", &std::any::type_name::<T>())
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
+
+/// A change to the facilities near the user's aircraft.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FacilityEvent<T> {
+    /// The facility came into range.
+    InRange(T),
+    /// The facility went out of range.
+    OutOfRange(T),
+}
+
+/// A stream of the facilities which come into or go out of range, created by
+/// [`SimConnect::subscribe_to_facilities`](super::SimConnect::subscribe_to_facilities).
+/// Dropping it unsubscribes from the facilities. SimConnect only keeps one
+/// subscription per kind of facility, so there can only be one of these for
+/// each kind at a time.
+pub struct FacilitySubscription<T> {
+    /// The messages for both the facilities coming into range, and those
+    /// going out of range.
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    in_range_request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    /// Events received in pages, but not yet taken from the stream.
+    pending: VecDeque<FacilityEvent<T>>,
+}
+
+impl<T: Facility> FacilitySubscription<T> {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        in_range_request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    ) -> Self {
+        FacilitySubscription {
+            rx,
+            in_range_request_id,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// The request ID allocated for the facilities which come into range.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.in_range_request_id)
+    }
+}
+
+impl<T: Facility> Stream for FacilitySubscription<T> {
+    type Item = FacilityEvent<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Poll::Ready(Some(event));
+            }
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(recv)) => {
+                    if let Some((list, facilities)) = T::page(&recv) {
+                        let event = if list.dwRequestID == self.in_range_request_id {
+                            FacilityEvent::InRange
+                        } else {
+                            FacilityEvent::OutOfRange
+                        };
+                        let events = facilities.into_iter().map(event);
+                        self.pending.extend(events);
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T: Facility> FusedStream for FacilitySubscription<T> {
+    fn is_terminated(&self) -> bool {
+        self.pending.is_empty() && self.rx.is_terminated()
+    }
+}
+
+impl<T> std::fmt::Debug for FacilitySubscription<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("FacilitySubscription")
+            .field("type", &std::any::type_name::<T>())
+            .field("request_id", &self.in_range_request_id)
+            .finish()
+    }
+}
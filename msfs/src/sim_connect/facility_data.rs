@@ -0,0 +1,175 @@
+use super::{
+    DecodeError, Exception, FacilityDefinition, OwnedSimConnectRecv, RequestError, RequestId,
+    SentCall,
+};
+use crate::sys;
+use futures::channel::mpsc;
+use futures::stream::Stream;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A block of facility data, with the blocks nested in it, which a
+/// `facility_definition` is assembled from.
+#[derive(Debug)]
+pub struct FacilityBlock {
+    unique_id: sys::DWORD,
+    parent_id: sys::DWORD,
+    data_type: sys::DWORD,
+    is_list_item: bool,
+    list_size: sys::DWORD,
+    data: Vec<u8>,
+    /// The nested blocks not yet taken, in the order they were received.
+    children: VecDeque<FacilityBlock>,
+}
+
+impl FacilityBlock {
+    fn new(recv: &OwnedSimConnectRecv) -> Option<Self> {
+        let OwnedSimConnectRecv::FacilityData(data) = recv else {
+            return None;
+        };
+        Some(FacilityBlock {
+            unique_id: data.UniqueRequestId,
+            parent_id: data.ParentUniqueRequestId,
+            data_type: data.Type,
+            is_list_item: data.IsListItem != 0,
+            list_size: data.ListSize,
+            data: data.data().to_vec(),
+            children: VecDeque::new(),
+        })
+    }
+
+    /// Take the fields of this block.
+    pub fn take_data(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.data)
+    }
+
+    /// Take the items of the next list of `T` nested in this block.
+    pub fn take_list<T: FacilityDefinition>(&mut self) -> Result<Vec<T>, DecodeError> {
+        let mut items = Vec::new();
+        let mut list_size = None;
+        let mut rest = VecDeque::new();
+        for child in self.children.drain(..) {
+            if child.is_list_item
+                && child.data_type == T::DATA_TYPE as sys::DWORD
+                && list_size.is_none_or(|list_size| items.len() < list_size)
+            {
+                list_size.get_or_insert(child.list_size as usize);
+                items.push(child);
+            } else {
+                rest.push_back(child);
+            }
+        }
+        self.children = rest;
+        items.iter_mut().map(T::assemble).collect()
+    }
+
+    /// Take the next block of `T` nested in this block, if there is one.
+    pub fn take_one<T: FacilityDefinition>(&mut self) -> Result<Option<T>, DecodeError> {
+        let Some(index) = self
+            .children
+            .iter()
+            .position(|child| child.data_type == T::DATA_TYPE as sys::DWORD)
+        else {
+            return Ok(None);
+        };
+        let mut child = self.children.remove(index).unwrap();
+        T::assemble(&mut child).map(Some)
+    }
+}
+
+/// Nest each block in its parent, returning the outermost blocks.
+fn assemble_tree(blocks: Vec<FacilityBlock>) -> Vec<FacilityBlock> {
+    let indices = blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| (block.unique_id, index))
+        .collect::<HashMap<_, _>>();
+    let mut blocks = blocks.into_iter().map(Some).collect::<Vec<_>>();
+    // Blocks are sent after their parents, so nesting them last to first
+    // keeps every block whole by the time it is nested in turn.
+    for index in (0..blocks.len()).rev() {
+        let parent_id = blocks[index].as_ref().unwrap().parent_id;
+        if let Some(&parent) = indices.get(&parent_id)
+            && parent < index
+        {
+            let block = blocks[index].take().unwrap();
+            blocks[parent].as_mut().unwrap().children.push_front(block);
+        }
+    }
+    blocks.into_iter().flatten().collect()
+}
+
+/// A request for the data of a facility, created by
+/// [`SimConnect::request_facility_data`](super::SimConnect::request_facility_data).
+/// It resolves once every block of the facility has been received.
+pub struct FacilityDataRequest<T> {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    /// The call which made the request, to describe its exceptions.
+    call: Option<SentCall>,
+    blocks: Vec<FacilityBlock>,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: FacilityDefinition> FacilityDataRequest<T> {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        call: Option<SentCall>,
+    ) -> Self {
+        FacilityDataRequest {
+            rx,
+            request_id,
+            call,
+            blocks: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The request ID allocated for this request.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+}
+
+impl<T: FacilityDefinition> Future for FacilityDataRequest<T> {
+    type Output = Result<T, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(OwnedSimConnectRecv::FacilityDataEnd(_))) => {
+                    let blocks = std::mem::take(&mut self.blocks);
+                    let mut root = assemble_tree(blocks)
+                        .into_iter()
+                        .find(|block| block.data_type == T::DATA_TYPE as sys::DWORD)
+                        .ok_or(DecodeError::MissingBlock { block: T::BLOCK })?;
+                    return Poll::Ready(Ok(T::assemble(&mut root)?));
+                }
+                Poll::Ready(Some(OwnedSimConnectRecv::Exception(exception))) => {
+                    let exception = Exception::new(&exception, self.call.take());
+                    return Poll::Ready(Err(exception.into()));
+                }
+                Poll::Ready(Some(recv)) => {
+                    if let Some(block) = FacilityBlock::new(&recv) {
+                        self.blocks.push(block);
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(Err(RequestError::Closed)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for FacilityDataRequest<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("FacilityDataRequest")
+            .field("type", &std::any::type_name::<T>())
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
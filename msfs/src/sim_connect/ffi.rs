@@ -0,0 +1,461 @@
+use super::{Backend, Call, HResult, Result};
+use crate::sys;
+
+#[inline(always)]
+fn map_err(result: sys::HRESULT) -> Result<()> {
+    if result >= 0 {
+        Ok(())
+    } else {
+        Err(HResult(result))
+    }
+}
+
+/// A backend which uses the SimConnect library shipped with the MSFS SDK.
+/// This is the backend used by `SimConnect::open`. The library is only
+/// available in WASM modules and on Windows.
+#[derive(Debug)]
+pub struct FfiBackend {
+    handle: sys::HANDLE,
+}
+
+impl FfiBackend {
+    /// Send a request to the Microsoft Flight Simulator server to open up communications with a new client.
+    pub fn open(name: &str) -> Result<Self> {
+        unsafe {
+            let mut handle = 0;
+            let name = std::ffi::CString::new(name).unwrap();
+            map_err(sys::SimConnect_Open(
+                &mut handle,
+                name.as_ptr(),
+                std::ptr::null_mut(),
+                0,
+                0,
+                0,
+            ))?;
+            debug_assert!(handle != 0);
+            Ok(FfiBackend { handle })
+        }
+    }
+}
+
+impl Backend for FfiBackend {
+    fn call(&self, call: Call<'_>) -> Result<()> {
+        let handle = self.handle;
+        map_err(unsafe {
+            match call {
+                Call::MapClientEventToSimEvent {
+                    event_id,
+                    event_name,
+                } => {
+                    sys::SimConnect_MapClientEventToSimEvent(handle, event_id, event_name.as_ptr())
+                }
+                Call::TransmitClientEvent {
+                    object_id,
+                    event_id,
+                    data,
+                    group_id,
+                    flags,
+                } => sys::SimConnect_TransmitClientEvent(
+                    handle, object_id, event_id, data, group_id, flags,
+                ),
+                Call::TransmitClientEventEx1 {
+                    object_id,
+                    event_id,
+                    group_id,
+                    flags,
+                    data,
+                } => sys::SimConnect_TransmitClientEvent_EX1(
+                    handle, object_id, event_id, group_id, flags, data[0], data[1], data[2],
+                    data[3], data[4],
+                ),
+                Call::SetSystemEventState { event_id, state } => {
+                    sys::SimConnect_SetSystemEventState(handle, event_id, state)
+                }
+                Call::AddClientEventToNotificationGroup {
+                    group_id,
+                    event_id,
+                    maskable,
+                } => sys::SimConnect_AddClientEventToNotificationGroup(
+                    handle,
+                    group_id,
+                    event_id,
+                    maskable.into(),
+                ),
+                Call::RemoveClientEvent { group_id, event_id } => {
+                    sys::SimConnect_RemoveClientEvent(handle, group_id, event_id)
+                }
+                Call::SetNotificationGroupPriority { group_id, priority } => {
+                    sys::SimConnect_SetNotificationGroupPriority(handle, group_id, priority)
+                }
+                Call::ClearNotificationGroup { group_id } => {
+                    sys::SimConnect_ClearNotificationGroup(handle, group_id)
+                }
+                Call::RequestNotificationGroup {
+                    group_id,
+                    reserved,
+                    flags,
+                } => sys::SimConnect_RequestNotificationGroup(handle, group_id, reserved, flags),
+                Call::MapInputEventToClientEvent {
+                    group_id,
+                    input_definition,
+                    down_event_id,
+                    down_value,
+                    up_event_id,
+                    up_value,
+                    maskable,
+                } => sys::SimConnect_MapInputEventToClientEvent(
+                    handle,
+                    group_id,
+                    input_definition.as_ptr(),
+                    down_event_id,
+                    down_value,
+                    up_event_id,
+                    up_value,
+                    maskable.into(),
+                ),
+                Call::SetInputGroupPriority { group_id, priority } => {
+                    sys::SimConnect_SetInputGroupPriority(handle, group_id, priority)
+                }
+                Call::RemoveInputEvent {
+                    group_id,
+                    input_definition,
+                } => sys::SimConnect_RemoveInputEvent(handle, group_id, input_definition.as_ptr()),
+                Call::ClearInputGroup { group_id } => {
+                    sys::SimConnect_ClearInputGroup(handle, group_id)
+                }
+                Call::SetInputGroupState { group_id, state } => {
+                    sys::SimConnect_SetInputGroupState(handle, group_id, state)
+                }
+                Call::RequestReservedKey {
+                    event_id,
+                    key_choice_1,
+                    key_choice_2,
+                    key_choice_3,
+                } => sys::SimConnect_RequestReservedKey(
+                    handle,
+                    event_id,
+                    key_choice_1.as_ptr(),
+                    key_choice_2.unwrap_or_default().as_ptr(),
+                    key_choice_3.unwrap_or_default().as_ptr(),
+                ),
+                Call::AddToDataDefinition {
+                    define_id,
+                    datum_name,
+                    units_name,
+                    datum_type,
+                    epsilon,
+                    datum_id,
+                } => sys::SimConnect_AddToDataDefinition(
+                    handle,
+                    define_id,
+                    datum_name.as_ptr(),
+                    units_name.as_ptr(),
+                    datum_type,
+                    epsilon,
+                    datum_id,
+                ),
+                Call::ClearDataDefinition { define_id } => {
+                    sys::SimConnect_ClearDataDefinition(handle, define_id)
+                }
+                Call::RequestDataOnSimObject {
+                    request_id,
+                    define_id,
+                    object_id,
+                    period,
+                    flags,
+                    origin,
+                    interval,
+                    limit,
+                } => sys::SimConnect_RequestDataOnSimObject(
+                    handle, request_id, define_id, object_id, period, flags, origin, interval,
+                    limit,
+                ),
+                Call::RequestDataOnSimObjectType {
+                    request_id,
+                    define_id,
+                    radius,
+                    r#type,
+                } => sys::SimConnect_RequestDataOnSimObjectType(
+                    handle, request_id, define_id, radius, r#type,
+                ),
+                Call::SetDataOnSimObject {
+                    define_id,
+                    object_id,
+                    flags,
+                    array_count,
+                    unit_size,
+                    data,
+                } => sys::SimConnect_SetDataOnSimObject(
+                    handle,
+                    define_id,
+                    object_id,
+                    flags,
+                    array_count,
+                    unit_size,
+                    data.as_ptr() as *mut std::ffi::c_void,
+                ),
+                Call::SubscribeToSystemEvent {
+                    event_id,
+                    system_event_name,
+                } => sys::SimConnect_SubscribeToSystemEvent(
+                    handle,
+                    event_id,
+                    system_event_name.as_ptr(),
+                ),
+                Call::UnsubscribeFromSystemEvent { event_id } => {
+                    sys::SimConnect_UnsubscribeFromSystemEvent(handle, event_id)
+                }
+                Call::AiCreateParkedAtcAircraft {
+                    container_title,
+                    tail_number,
+                    airport_id,
+                    request_id,
+                } => sys::SimConnect_AICreateParkedATCAircraft(
+                    handle,
+                    container_title.as_ptr(),
+                    tail_number.as_ptr(),
+                    airport_id.as_ptr(),
+                    request_id,
+                ),
+                Call::AiCreateEnrouteAtcAircraft {
+                    container_title,
+                    tail_number,
+                    flight_number,
+                    flight_plan_path,
+                    flight_plan_position,
+                    touch_and_go,
+                    request_id,
+                } => sys::SimConnect_AICreateEnrouteATCAircraft(
+                    handle,
+                    container_title.as_ptr(),
+                    tail_number.as_ptr(),
+                    flight_number,
+                    flight_plan_path.as_ptr(),
+                    flight_plan_position,
+                    touch_and_go.into(),
+                    request_id,
+                ),
+                Call::AiCreateNonAtcAircraft {
+                    container_title,
+                    tail_number,
+                    init_position,
+                    request_id,
+                } => sys::SimConnect_AICreateNonATCAircraft(
+                    handle,
+                    container_title.as_ptr(),
+                    tail_number.as_ptr(),
+                    init_position,
+                    request_id,
+                ),
+                Call::AiCreateSimulatedObject {
+                    container_title,
+                    init_position,
+                    request_id,
+                } => sys::SimConnect_AICreateSimulatedObject(
+                    handle,
+                    container_title.as_ptr(),
+                    init_position,
+                    request_id,
+                ),
+                Call::AiReleaseControl {
+                    object_id,
+                    request_id,
+                } => sys::SimConnect_AIReleaseControl(handle, object_id, request_id),
+                Call::AiRemoveObject {
+                    object_id,
+                    request_id,
+                } => sys::SimConnect_AIRemoveObject(handle, object_id, request_id),
+                Call::AiSetAircraftFlightPlan {
+                    object_id,
+                    flight_plan_path,
+                    request_id,
+                } => sys::SimConnect_AISetAircraftFlightPlan(
+                    handle,
+                    object_id,
+                    flight_plan_path.as_ptr(),
+                    request_id,
+                ),
+                Call::RequestSystemState { request_id, state } => {
+                    sys::SimConnect_RequestSystemState(handle, request_id, state.as_ptr())
+                }
+                Call::MapClientDataNameToId {
+                    client_data_name,
+                    client_data_id,
+                } => sys::SimConnect_MapClientDataNameToID(
+                    handle,
+                    client_data_name.as_ptr(),
+                    client_data_id,
+                ),
+                Call::CreateClientData {
+                    client_data_id,
+                    size,
+                    flags,
+                } => sys::SimConnect_CreateClientData(handle, client_data_id, size, flags),
+                Call::AddToClientDataDefinition {
+                    define_id,
+                    offset,
+                    size_or_type,
+                    epsilon,
+                    datum_id,
+                } => sys::SimConnect_AddToClientDataDefinition(
+                    handle,
+                    define_id,
+                    offset,
+                    size_or_type,
+                    epsilon,
+                    datum_id,
+                ),
+                Call::RequestClientData {
+                    client_data_id,
+                    request_id,
+                    define_id,
+                    period,
+                    flags,
+                    origin,
+                    interval,
+                    limit,
+                } => sys::SimConnect_RequestClientData(
+                    handle,
+                    client_data_id,
+                    request_id,
+                    define_id,
+                    period,
+                    flags,
+                    origin,
+                    interval,
+                    limit,
+                ),
+                Call::SetClientData {
+                    client_data_id,
+                    define_id,
+                    flags,
+                    data,
+                } => sys::SimConnect_SetClientData(
+                    handle,
+                    client_data_id,
+                    define_id,
+                    flags,
+                    0,
+                    data.len() as sys::DWORD,
+                    data.as_ptr() as *mut std::ffi::c_void,
+                ),
+                Call::FlightLoad { file_name } => {
+                    sys::SimConnect_FlightLoad(handle, file_name.as_ptr())
+                }
+                Call::FlightSave {
+                    file_name,
+                    title,
+                    description,
+                    flags,
+                } => sys::SimConnect_FlightSave(
+                    handle,
+                    file_name.as_ptr(),
+                    title.map(|x| x.as_ptr()).unwrap_or(std::ptr::null()),
+                    description.map(|x| x.as_ptr()).unwrap_or(std::ptr::null()),
+                    flags,
+                ),
+                Call::FlightPlanLoad { file_name } => {
+                    sys::SimConnect_FlightPlanLoad(handle, file_name.as_ptr())
+                }
+                Call::SubscribeToFacilities { r#type, request_id } => {
+                    sys::SimConnect_SubscribeToFacilities(handle, r#type, request_id)
+                }
+                Call::UnsubscribeToFacilities { r#type } => {
+                    sys::SimConnect_UnsubscribeToFacilities(handle, r#type)
+                }
+                Call::RequestFacilitiesList { r#type, request_id } => {
+                    sys::SimConnect_RequestFacilitiesList(handle, r#type, request_id)
+                }
+                Call::SubscribeToFacilitiesEx1 {
+                    r#type,
+                    in_range_request_id,
+                    out_of_range_request_id,
+                } => sys::SimConnect_SubscribeToFacilities_EX1(
+                    handle,
+                    r#type,
+                    in_range_request_id,
+                    out_of_range_request_id,
+                ),
+                Call::UnsubscribeToFacilitiesEx1 {
+                    r#type,
+                    in_range,
+                    out_of_range,
+                } => sys::SimConnect_UnsubscribeToFacilities_EX1(
+                    handle,
+                    r#type,
+                    in_range,
+                    out_of_range,
+                ),
+                Call::RequestFacilitiesListEx1 { r#type, request_id } => {
+                    sys::SimConnect_RequestFacilitiesList_EX1(handle, r#type, request_id)
+                }
+                Call::AddToFacilityDefinition {
+                    define_id,
+                    field_name,
+                } => {
+                    sys::SimConnect_AddToFacilityDefinition(handle, define_id, field_name.as_ptr())
+                }
+                Call::RequestFacilityData {
+                    define_id,
+                    request_id,
+                    icao,
+                    region,
+                } => sys::SimConnect_RequestFacilityData(
+                    handle,
+                    define_id,
+                    request_id,
+                    icao.as_ptr(),
+                    region.unwrap_or_default().as_ptr(),
+                ),
+                Call::EnumerateInputEvents { request_id } => {
+                    sys::SimConnect_EnumerateInputEvents(handle, request_id)
+                }
+                Call::GetInputEvent { request_id, hash } => {
+                    sys::SimConnect_GetInputEvent(handle, request_id, hash)
+                }
+                Call::SetInputEvent { hash, value } => sys::SimConnect_SetInputEvent(
+                    handle,
+                    hash,
+                    value.len() as sys::DWORD,
+                    value.as_ptr() as *mut std::ffi::c_void,
+                ),
+                Call::SubscribeInputEvent { hash } => {
+                    sys::SimConnect_SubscribeInputEvent(handle, hash)
+                }
+                Call::UnsubscribeInputEvent { hash } => {
+                    sys::SimConnect_UnsubscribeInputEvent(handle, hash)
+                }
+                Call::EnumerateInputEventParams { hash } => {
+                    sys::SimConnect_EnumerateInputEventParams(handle, hash)
+                }
+            }
+        })
+    }
+
+    fn last_sent_packet_id(&self) -> Result<sys::DWORD> {
+        let mut packet_id = 0;
+        unsafe {
+            map_err(sys::SimConnect_GetLastSentPacketID(
+                self.handle,
+                &mut packet_id,
+            ))?;
+        }
+        Ok(packet_id)
+    }
+
+    unsafe fn call_dispatch(
+        &self,
+        dispatch: sys::DispatchProc,
+        context: *mut std::ffi::c_void,
+    ) -> Result<()> {
+        unsafe { map_err(sys::SimConnect_CallDispatch(self.handle, dispatch, context)) }
+    }
+}
+
+impl Drop for FfiBackend {
+    fn drop(&mut self) {
+        unsafe {
+            map_err(sys::SimConnect_Close(self.handle)).expect("SimConnect_Close");
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use super::{Call, RequestId, Result, SimConnect};
+use futures::channel::mpsc;
+
+/// A request made through [`SimConnect`], such as a periodic data request or
+/// a system event subscription, which is stopped when the handle is dropped.
+/// The request is stopped the next time messages are dispatched, or at once
+/// with [`cancel`](RequestHandle::cancel). `I` is the type of its ID, which
+/// is a [`SystemEventId`](super::SystemEventId) for a system event
+/// subscription, and an [`ObjectId`](super::ObjectId) for an
+/// [`AiObject`](super::AiObject).
+///
+/// Only the request is stopped. The data definition it was made with is
+/// added once per type and shared by every request for that type, so it
+/// stays defined for the rest of the session. A [`DynamicDataDefinition`]
+/// can be cleared with
+/// [`clear_dynamic_data_definition`](SimConnect::clear_dynamic_data_definition).
+///
+/// [`DynamicDataDefinition`]: super::DynamicDataDefinition
+/// ```rs
+/// let altitude = sim.request_data_on_sim_object::<Altitude>(RequestId(0), ObjectId::USER, Period::SimFrame)?;
+/// // ...
+/// altitude.cancel(&mut sim)?;
+/// ```
+#[must_use = "the request is stopped as soon as the handle is dropped"]
+pub struct RequestHandle<I = RequestId> {
+    id: I,
+    stop: Option<Call<'static>>,
+    dropped: mpsc::UnboundedSender<Call<'static>>,
+}
+
+impl<I: Copy + std::fmt::Display> RequestHandle<I> {
+    pub(super) fn new(
+        id: I,
+        stop: Call<'static>,
+        dropped: mpsc::UnboundedSender<Call<'static>>,
+    ) -> Self {
+        RequestHandle {
+            id,
+            stop: Some(stop),
+            dropped,
+        }
+    }
+
+    /// The ID which the messages for this request carry.
+    pub fn id(&self) -> I {
+        self.id
+    }
+
+    /// Stop the request now, rather than the next time messages are
+    /// dispatched.
+    pub fn cancel(mut self, sim: &mut SimConnect) -> Result<()> {
+        let stop = self.stop.take().unwrap();
+        sim.sender.method("RequestHandle::cancel");
+        sim.sender.call(stop)
+    }
+
+    /// Let the request run for the rest of the session, returning its ID.
+    pub fn forget(mut self) -> I {
+        self.stop = None;
+        self.id
+    }
+}
+
+impl<I> Drop for RequestHandle<I> {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            // The session may have been closed already, which stopped the
+            // request anyway.
+            let _ = self.dropped.unbounded_send(stop);
+        }
+    }
+}
+
+impl<I: std::fmt::Debug> std::fmt::Debug for RequestHandle<I> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("RequestHandle")
+            .field("id", &self.id)
+            .finish()
+    }
+}
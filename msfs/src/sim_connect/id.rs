@@ -0,0 +1,64 @@
+use crate::sys;
+
+macro_rules! id {
+    ($( $(#[$meta:meta])* $Id:ident($T:ty); )*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #[repr(transparent)]
+            pub struct $Id(pub $T);
+
+            impl From<$Id> for $T {
+                fn from(id: $Id) -> Self {
+                    id.0
+                }
+            }
+
+            impl std::fmt::Display for $Id {
+                fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    std::fmt::Display::fmt(&self.0, fmt)
+                }
+            }
+        )*
+    };
+}
+
+id! {
+    /// A SimObject in the sim, such as the user's aircraft or an AI object.
+    ObjectId(sys::SIMCONNECT_OBJECT_ID);
+    /// A request for data, chosen by the client and carried by the messages
+    /// which answer it.
+    RequestId(sys::SIMCONNECT_DATA_REQUEST_ID);
+    /// A client event mapped to a sim event with
+    /// [`map_client_event_to_sim_event`](super::SimConnect::map_client_event_to_sim_event).
+    ClientEventId(sys::SIMCONNECT_CLIENT_EVENT_ID);
+    /// A subscription to a system event, made with
+    /// [`subscribe_to_system_event`](super::SimConnect::subscribe_to_system_event).
+    /// Client events share the same IDs in SimConnect, but one can't be used
+    /// in place of the other:
+    /// ```compile_fail
+    /// # use msfs::sim_connect::{ClientEventId, SimConnect};
+    /// fn unsubscribe(sim: &mut SimConnect, event_id: ClientEventId) {
+    ///     sim.unsubscribe_from_system_event(event_id).unwrap();
+    /// }
+    /// ```
+    SystemEventId(sys::SIMCONNECT_CLIENT_EVENT_ID);
+    /// A data or client data definition added to the session.
+    DefineId(sys::SIMCONNECT_DATA_DEFINITION_ID);
+    /// A client data area, mapped from its name.
+    ClientDataId(sys::SIMCONNECT_CLIENT_DATA_ID);
+    /// A group of client events which are notified together, at the group's
+    /// priority.
+    NotificationGroupId(sys::SIMCONNECT_NOTIFICATION_GROUP_ID);
+    /// A group of key and joystick inputs mapped to client events, which is
+    /// turned on and off as a whole.
+    InputGroupId(sys::SIMCONNECT_INPUT_GROUP_ID);
+    /// An input event, such as a cockpit knob or switch, identified by the
+    /// hash the sim gives its name.
+    InputEventHash(sys::UINT64);
+}
+
+impl ObjectId {
+    /// The user's aircraft.
+    pub const USER: ObjectId = ObjectId(sys::SIMCONNECT_OBJECT_ID_USER);
+}
@@ -0,0 +1,456 @@
+use super::{
+    DecodeError, Exception, InputEventHash, OwnedSimConnectRecv, Recv, RequestError, RequestId,
+    SentCall, fixed_str,
+};
+use crate::sys;
+use futures::channel::mpsc;
+use futures::stream::{FusedStream, Stream};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// An input event, given either by its name, as cached by
+/// [`SimConnect::enumerate_input_events`](super::SimConnect::enumerate_input_events),
+/// or by its hash.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InputEvent<'a> {
+    Name(&'a str),
+    Hash(InputEventHash),
+}
+
+impl<'a> From<&'a str> for InputEvent<'a> {
+    fn from(name: &'a str) -> Self {
+        InputEvent::Name(name)
+    }
+}
+
+impl From<InputEventHash> for InputEvent<'_> {
+    fn from(hash: InputEventHash) -> Self {
+        InputEvent::Hash(hash)
+    }
+}
+
+impl std::fmt::Display for InputEvent<'_> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InputEvent::Name(name) => write!(fmt, "{name:?}"),
+            InputEvent::Hash(hash) => write!(fmt, "{hash}"),
+        }
+    }
+}
+
+/// The type of the value of an input event.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InputEventType {
+    Double,
+    String,
+}
+
+impl InputEventType {
+    fn decode(r#type: sys::SIMCONNECT_INPUT_EVENT_TYPE) -> Result<Self, DecodeError> {
+        match r#type {
+            sys::SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_DOUBLE => {
+                Ok(InputEventType::Double)
+            }
+            sys::SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_STRING => {
+                Ok(InputEventType::String)
+            }
+            _ => Err(DecodeError::InvalidValue {
+                type_name: "SIMCONNECT_INPUT_EVENT_TYPE",
+                value: r#type.to_string(),
+            }),
+        }
+    }
+}
+
+/// The value of an input event.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEventValue {
+    Double(f64),
+    String(String),
+}
+
+impl InputEventValue {
+    /// The type of this value.
+    pub fn r#type(&self) -> InputEventType {
+        match self {
+            InputEventValue::Double(_) => InputEventType::Double,
+            InputEventValue::String(_) => InputEventType::String,
+        }
+    }
+
+    /// The bytes the value is sent to the sim as. Strings are nul-terminated.
+    pub(super) fn encode(&self) -> Vec<u8> {
+        match self {
+            InputEventValue::Double(value) => value.to_le_bytes().to_vec(),
+            InputEventValue::String(value) => {
+                let mut bytes = value.as_bytes().to_vec();
+                bytes.push(0);
+                bytes
+            }
+        }
+    }
+
+    fn decode(r#type: sys::SIMCONNECT_INPUT_EVENT_TYPE, bytes: &[u8]) -> Result<Self, DecodeError> {
+        match InputEventType::decode(r#type)? {
+            InputEventType::Double => {
+                let bytes = bytes.get(..8).ok_or(DecodeError::Truncated)?;
+                Ok(InputEventValue::Double(f64::from_le_bytes(
+                    bytes.try_into().unwrap(),
+                )))
+            }
+            InputEventType::String => {
+                let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                Ok(InputEventValue::String(
+                    String::from_utf8_lossy(&bytes[..len]).into_owned(),
+                ))
+            }
+        }
+    }
+}
+
+impl From<f64> for InputEventValue {
+    fn from(value: f64) -> Self {
+        InputEventValue::Double(value)
+    }
+}
+
+impl From<&str> for InputEventValue {
+    fn from(value: &str) -> Self {
+        InputEventValue::String(value.to_string())
+    }
+}
+
+impl From<String> for InputEventValue {
+    fn from(value: String) -> Self {
+        InputEventValue::String(value)
+    }
+}
+
+/// An input event of the user's aircraft, as enumerated by the sim.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputEventDescriptor {
+    pub name: String,
+    pub hash: InputEventHash,
+    pub r#type: InputEventType,
+}
+
+impl sys::SIMCONNECT_RECV_LIST_TEMPLATE {
+    /// The ID of the request.
+    pub fn id(&self) -> RequestId {
+        RequestId(self.dwRequestID)
+    }
+
+    /// The index of this page of the list, starting at 0.
+    pub fn entry_number(&self) -> sys::DWORD {
+        self.dwEntryNumber
+    }
+
+    /// The number of pages the list is sent in.
+    pub fn out_of(&self) -> sys::DWORD {
+        self.dwOutOf
+    }
+}
+
+impl Recv<sys::SIMCONNECT_RECV_ENUMERATE_INPUT_EVENTS> {
+    /// The input events in this page of the list. Those of an unknown type
+    /// are left out.
+    pub fn descriptors(&self) -> Vec<InputEventDescriptor> {
+        let offset = std::mem::offset_of!(sys::SIMCONNECT_RECV_ENUMERATE_INPUT_EVENTS, rgData);
+        self.bytes()
+            .get(offset..)
+            .unwrap_or_default()
+            .chunks_exact(std::mem::size_of::<sys::SIMCONNECT_INPUT_EVENT_DESCRIPTOR>())
+            .take(self._base.dwArraySize as usize)
+            .filter_map(|chunk| {
+                let descriptor: sys::SIMCONNECT_INPUT_EVENT_DESCRIPTOR =
+                    unsafe { std::ptr::read_unaligned(chunk.as_ptr().cast()) };
+                Some(InputEventDescriptor {
+                    name: fixed_str(&descriptor.Name).into_owned(),
+                    hash: InputEventHash(descriptor.Hash),
+                    r#type: InputEventType::decode(descriptor.eType).ok()?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl sys::SIMCONNECT_RECV_GET_INPUT_EVENT {
+    /// The ID of the request.
+    pub fn id(&self) -> RequestId {
+        RequestId(self.dwRequestID)
+    }
+}
+
+impl Recv<sys::SIMCONNECT_RECV_GET_INPUT_EVENT> {
+    /// The value of the input event.
+    pub fn value(&self) -> Result<InputEventValue, DecodeError> {
+        let offset = std::mem::offset_of!(sys::SIMCONNECT_RECV_GET_INPUT_EVENT, Value);
+        InputEventValue::decode(self.eType, &self.bytes()[offset..])
+    }
+}
+
+impl sys::SIMCONNECT_RECV_SUBSCRIBE_INPUT_EVENT {
+    /// The hash of the input event which changed.
+    pub fn hash(&self) -> InputEventHash {
+        InputEventHash(self.Hash)
+    }
+}
+
+impl Recv<sys::SIMCONNECT_RECV_SUBSCRIBE_INPUT_EVENT> {
+    /// The new value of the input event.
+    pub fn value(&self) -> Result<InputEventValue, DecodeError> {
+        let offset = std::mem::offset_of!(sys::SIMCONNECT_RECV_SUBSCRIBE_INPUT_EVENT, Value);
+        InputEventValue::decode(self.eType, &self.bytes()[offset..])
+    }
+}
+
+impl sys::SIMCONNECT_RECV_ENUMERATE_INPUT_EVENT_PARAMS {
+    /// The hash of the input event.
+    pub fn hash(&self) -> InputEventHash {
+        InputEventHash(self.Hash)
+    }
+
+    /// The types of the parameters of the input event, separated by `;`.
+    pub fn params(&self) -> std::borrow::Cow<'_, str> {
+        fixed_str(&self.Value)
+    }
+}
+
+/// A request for the input events of the user's aircraft, created by
+/// [`SimConnect::enumerate_input_events`](super::SimConnect::enumerate_input_events).
+/// It resolves once every page of the list has been received.
+pub struct InputEventList {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    /// The call which made the request, to describe its exceptions.
+    call: Option<SentCall>,
+    /// The pages received so far, by entry number.
+    pages: BTreeMap<sys::DWORD, Vec<InputEventDescriptor>>,
+}
+
+impl InputEventList {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        call: Option<SentCall>,
+    ) -> Self {
+        InputEventList {
+            rx,
+            request_id,
+            call,
+            pages: BTreeMap::new(),
+        }
+    }
+
+    /// The request ID allocated for this request.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+}
+
+impl Future for InputEventList {
+    type Output = Result<Vec<InputEventDescriptor>, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(OwnedSimConnectRecv::EnumerateInputEvents(list))) => {
+                    // An empty list is still sent as a single page.
+                    let out_of = list._base.out_of().max(1) as usize;
+                    self.pages
+                        .insert(list._base.entry_number(), list.descriptors());
+                    if self.pages.len() >= out_of {
+                        let pages = std::mem::take(&mut self.pages);
+                        return Poll::Ready(Ok(pages.into_values().flatten().collect()));
+                    }
+                }
+                Poll::Ready(Some(OwnedSimConnectRecv::Exception(exception))) => {
+                    let exception = Exception::new(&exception, self.call.take());
+                    return Poll::Ready(Err(exception.into()));
+                }
+                Poll::Ready(Some(_)) => {}
+                Poll::Ready(None) => return Poll::Ready(Err(RequestError::Closed)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for InputEventList {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("InputEventList")
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
+
+/// A request for the value of an input event, created by
+/// [`SimConnect::get_input_event`](super::SimConnect::get_input_event). It
+/// resolves once the sim answers.
+pub struct InputEventRequest {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    /// The call which made the request, to describe its exceptions.
+    call: Option<SentCall>,
+}
+
+impl InputEventRequest {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        call: Option<SentCall>,
+    ) -> Self {
+        InputEventRequest {
+            rx,
+            request_id,
+            call,
+        }
+    }
+
+    /// The request ID allocated for this request.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+}
+
+impl Future for InputEventRequest {
+    type Output = Result<InputEventValue, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(OwnedSimConnectRecv::GetInputEvent(event))) => {
+                    return Poll::Ready(Ok(event.value()?));
+                }
+                Poll::Ready(Some(OwnedSimConnectRecv::Exception(exception))) => {
+                    let exception = Exception::new(&exception, self.call.take());
+                    return Poll::Ready(Err(exception.into()));
+                }
+                Poll::Ready(Some(_)) => {}
+                Poll::Ready(None) => return Poll::Ready(Err(RequestError::Closed)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for InputEventRequest {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("InputEventRequest")
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
+
+/// A request for the parameters of an input event, created by
+/// [`SimConnect::enumerate_input_event_params`](super::SimConnect::enumerate_input_event_params).
+/// It resolves to the types of the parameters, separated by `;`, once the sim
+/// answers.
+pub struct InputEventParamsRequest {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    hash: InputEventHash,
+    /// The call which made the request, to describe its exceptions.
+    call: Option<SentCall>,
+}
+
+impl InputEventParamsRequest {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        hash: InputEventHash,
+        call: Option<SentCall>,
+    ) -> Self {
+        InputEventParamsRequest { rx, hash, call }
+    }
+
+    /// The hash of the input event.
+    pub fn hash(&self) -> InputEventHash {
+        self.hash
+    }
+}
+
+impl Future for InputEventParamsRequest {
+    type Output = Result<String, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(OwnedSimConnectRecv::EnumerateInputEventParams(params))) => {
+                    return Poll::Ready(Ok(params.params().into_owned()));
+                }
+                Poll::Ready(Some(OwnedSimConnectRecv::Exception(exception))) => {
+                    let exception = Exception::new(&exception, self.call.take());
+                    return Poll::Ready(Err(exception.into()));
+                }
+                Poll::Ready(Some(_)) => {}
+                Poll::Ready(None) => return Poll::Ready(Err(RequestError::Closed)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for InputEventParamsRequest {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("InputEventParamsRequest")
+            .field("hash", &self.hash)
+            .finish()
+    }
+}
+
+/// A stream of the values of an input event, created by
+/// [`SimConnect::subscribe_input_event`](super::SimConnect::subscribe_input_event).
+/// Values which can't be decoded are skipped. Dropping it unsubscribes from
+/// the input event, once no other stream is subscribed to it.
+pub struct InputEventSubscription {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    hash: InputEventHash,
+}
+
+impl InputEventSubscription {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        hash: InputEventHash,
+    ) -> Self {
+        InputEventSubscription { rx, hash }
+    }
+
+    /// The hash of the input event.
+    pub fn hash(&self) -> InputEventHash {
+        self.hash
+    }
+}
+
+impl Stream for InputEventSubscription {
+    type Item = InputEventValue;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(OwnedSimConnectRecv::SubscribeInputEvent(event))) => {
+                    if let Ok(value) = event.value() {
+                        return Poll::Ready(Some(value));
+                    }
+                }
+                Poll::Ready(Some(_)) => {}
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl FusedStream for InputEventSubscription {
+    fn is_terminated(&self) -> bool {
+        self.rx.is_terminated()
+    }
+}
+
+impl std::fmt::Debug for InputEventSubscription {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("InputEventSubscription")
+            .field("hash", &self.hash)
+            .finish()
+    }
+}
@@ -0,0 +1,2057 @@
+use super::{
+    Backend, Call, ClientEventId, DataField, Facility, InputEventValue, ObjectId, RaceResult,
+    Result, as_bytes,
+};
+use crate::sys;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+
+const VERSION: sys::DWORD = 5;
+const UNKNOWN_GROUP: sys::DWORD = sys::DWORD::MAX;
+/// How many facilities are sent in each page of a facility list.
+const FACILITIES_PAGE_SIZE: usize = 4;
+/// How many input events are sent in each page of their enumeration.
+const INPUT_EVENTS_PAGE_SIZE: usize = 4;
+
+/// A message under construction, sent to the client by `MockServer`.
+struct Message(Vec<u8>);
+
+impl Message {
+    fn new(id: sys::SIMCONNECT_RECV_ID) -> Self {
+        let mut message = Message(Vec::new());
+        message.u32(0).u32(VERSION).u32(id as sys::DWORD);
+        message
+    }
+
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn u64(&mut self, value: u64) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    fn string(&mut self, value: &str, size: usize) -> &mut Self {
+        let len = value.len().min(size - 1);
+        self.0.extend_from_slice(&value.as_bytes()[..len]);
+        self.0.resize(self.0.len() + size - len, 0);
+        self
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let size = self.0.len() as u32;
+        self.0[0..4].copy_from_slice(&size.to_le_bytes());
+        self.0
+    }
+}
+
+/// The size of a single datum of the given type.
+fn datum_size(datum_type: sys::SIMCONNECT_DATATYPE) -> usize {
+    match datum_type {
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT32 => 4,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT64 => 8,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT32 => 4,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64 => 8,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING8 => 8,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING32 => 32,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING64 => 64,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING128 => 128,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING256 => 256,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING260 => 260,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INITPOSITION => {
+            std::mem::size_of::<sys::SIMCONNECT_DATA_INITPOSITION>()
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_MARKERSTATE => {
+            std::mem::size_of::<sys::SIMCONNECT_DATA_MARKERSTATE>()
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_WAYPOINT => {
+            std::mem::size_of::<sys::SIMCONNECT_DATA_WAYPOINT>()
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_LATLONALT => {
+            std::mem::size_of::<sys::SIMCONNECT_DATA_LATLONALT>()
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_XYZ => {
+            std::mem::size_of::<sys::SIMCONNECT_DATA_XYZ>()
+        }
+        _ => 0,
+    }
+}
+
+fn is_string(datum_type: sys::SIMCONNECT_DATATYPE) -> bool {
+    matches!(
+        datum_type,
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING8
+            | sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING32
+            | sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING64
+            | sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING128
+            | sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING256
+            | sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING260
+            | sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRINGV
+    )
+}
+
+/// Strings are nul-padded to the size of their type, or just nul-terminated
+/// for `STRINGV`.
+fn encode_string(buffer: &mut Vec<u8>, datum_type: sys::SIMCONNECT_DATATYPE, value: &str) {
+    let size = match datum_type {
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRINGV => value.len() + 1,
+        _ => datum_size(datum_type),
+    };
+    let len = value.len().min(size - 1);
+    buffer.extend_from_slice(&value.as_bytes()[..len]);
+    buffer.resize(buffer.len() + size - len, 0);
+}
+
+fn decode_string(bytes: &[u8]) -> String {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+fn encode_datum(buffer: &mut Vec<u8>, datum_type: sys::SIMCONNECT_DATATYPE, value: f64) {
+    match datum_type {
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT32 => {
+            buffer.extend_from_slice(&(value as i32).to_le_bytes())
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT64 => {
+            buffer.extend_from_slice(&(value as i64).to_le_bytes())
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT32 => {
+            buffer.extend_from_slice(&(value as f32).to_le_bytes())
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64 => {
+            buffer.extend_from_slice(&value.to_le_bytes())
+        }
+        _ => buffer.resize(buffer.len() + datum_size(datum_type), 0),
+    }
+}
+
+fn decode_datum(bytes: &[u8], datum_type: sys::SIMCONNECT_DATATYPE) -> Option<f64> {
+    match datum_type {
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT32 => {
+            Some(i32::from_le_bytes(bytes.try_into().ok()?) as f64)
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INT64 => {
+            Some(i64::from_le_bytes(bytes.try_into().ok()?) as f64)
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT32 => {
+            Some(f32::from_le_bytes(bytes.try_into().ok()?) as f64)
+        }
+        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64 => {
+            Some(f64::from_le_bytes(bytes.try_into().ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// A client event sent to the sim with `transmit_client_event` or
+/// `transmit_client_event_ex1`, as recorded by a [`MockServer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransmittedEvent {
+    pub object_id: ObjectId,
+    pub event_id: ClientEventId,
+    /// The sim event name the event was mapped to, if any.
+    pub event_name: Option<String>,
+    pub data: [sys::DWORD; 5],
+}
+
+struct Datum {
+    name: String,
+    datum_type: sys::SIMCONNECT_DATATYPE,
+    datum_id: sys::DWORD,
+}
+
+/// When a request is due, with every period counted as one frame.
+struct Schedule {
+    origin: sys::DWORD,
+    interval: sys::DWORD,
+    limit: sys::DWORD,
+    periods: sys::DWORD,
+    sent: sys::DWORD,
+}
+
+impl Schedule {
+    fn new(origin: sys::DWORD, interval: sys::DWORD, limit: sys::DWORD) -> Self {
+        Schedule {
+            origin,
+            interval,
+            limit,
+            periods: 0,
+            sent: 0,
+        }
+    }
+
+    /// Count a period, returning whether the request is due in it.
+    fn tick(&mut self) -> bool {
+        let period = self.periods;
+        self.periods += 1;
+        period >= self.origin && (period - self.origin).is_multiple_of(self.interval + 1)
+    }
+
+    /// Whether the request has sent as much as it should.
+    fn finished(&self, once: bool) -> bool {
+        (once && self.sent > 0) || (self.limit != 0 && self.sent >= self.limit)
+    }
+}
+
+struct DataRequest {
+    /// The packet which made the request, referenced by its exceptions.
+    packet_id: sys::DWORD,
+    define_id: sys::SIMCONNECT_DATA_DEFINITION_ID,
+    object_id: sys::SIMCONNECT_OBJECT_ID,
+    period: sys::SIMCONNECT_PERIOD,
+    flags: sys::SIMCONNECT_DATA_REQUEST_FLAG,
+    schedule: Schedule,
+    /// The last value sent of each datum.
+    last: Option<Vec<Vec<u8>>>,
+}
+
+struct ClientDataRequest {
+    /// The packet which made the request, referenced by its exceptions.
+    packet_id: sys::DWORD,
+    client_data_id: sys::SIMCONNECT_CLIENT_DATA_ID,
+    define_id: sys::SIMCONNECT_CLIENT_DATA_DEFINITION_ID,
+    period: sys::SIMCONNECT_CLIENT_DATA_PERIOD,
+    flags: sys::SIMCONNECT_CLIENT_DATA_REQUEST_FLAG,
+    schedule: Schedule,
+    version: Option<u64>,
+    last: Option<Vec<u8>>,
+}
+
+struct ClientDataArea {
+    data: Vec<u8>,
+    version: u64,
+}
+
+/// The client events an input is mapped to.
+struct MappedInput {
+    down_event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+    down_value: sys::DWORD,
+    up_event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+    up_value: sys::DWORD,
+}
+
+/// A facility added with `MockServer::add_facility`.
+struct MockFacility {
+    ident: String,
+    region: String,
+    /// The `SIMCONNECT_DATA_FACILITY_*` struct of the facility.
+    data: Vec<u8>,
+}
+
+/// The message a list of facilities of `list_type` is sent in.
+fn facility_list_message(
+    list_type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+) -> Option<sys::SIMCONNECT_RECV_ID> {
+    Some(match list_type {
+        sys::SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_AIRPORT => {
+            sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_AIRPORT_LIST
+        }
+        sys::SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_WAYPOINT => {
+            sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_WAYPOINT_LIST
+        }
+        sys::SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_NDB => {
+            sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_NDB_LIST
+        }
+        sys::SIMCONNECT_FACILITY_LIST_TYPE_SIMCONNECT_FACILITY_LIST_TYPE_VOR => {
+            sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_VOR_LIST
+        }
+        _ => return None,
+    })
+}
+
+/// An input event added with `MockServer::add_input_event`.
+struct MockInputEvent {
+    name: String,
+    hash: u64,
+    value: InputEventValue,
+    params: String,
+}
+
+/// The hash the server gives the input event `name`, which is FNV-1a of its
+/// uppercase name.
+fn input_event_hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte.to_ascii_uppercase())).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// The type and value of an input event, as sent in messages. Strings are
+/// padded to fill the message's value field.
+fn input_event_value(value: &InputEventValue) -> (sys::SIMCONNECT_INPUT_EVENT_TYPE, Vec<u8>) {
+    let mut bytes = value.encode();
+    bytes.resize(bytes.len().next_multiple_of(4), 0);
+    let r#type = match value {
+        InputEventValue::Double(_) => {
+            sys::SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_DOUBLE
+        }
+        InputEventValue::String(_) => {
+            sys::SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_STRING
+        }
+    };
+    (r#type, bytes)
+}
+
+/// A block of facility data served by [`MockServer`], such as an airport or
+/// one of its runways.
+#[derive(Debug, Clone)]
+pub struct MockFacilityBlock {
+    name: String,
+    data_type: sys::SIMCONNECT_FACILITY_DATA_TYPE,
+    /// The values of the fields, as sent by the sim.
+    fields: HashMap<String, Vec<u8>>,
+    /// The nested blocks, with whether they are items of a list.
+    children: Vec<(MockFacilityBlock, bool)>,
+}
+
+impl MockFacilityBlock {
+    /// A block which is opened as `name`, such as `RUNWAY` or
+    /// `PRIMARY_THRESHOLD`.
+    pub fn new(name: &str, data_type: sys::SIMCONNECT_FACILITY_DATA_TYPE) -> Self {
+        MockFacilityBlock {
+            name: name.to_string(),
+            data_type,
+            fields: HashMap::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Set the field `name`, which is sent as the sim would send `value`.
+    pub fn with_field(mut self, name: &str, value: impl DataField) -> Self {
+        let mut data = Vec::new();
+        value.write(&mut data);
+        self.fields.insert(name.to_string(), data);
+        self
+    }
+
+    /// Nest a single block, such as the threshold of a runway.
+    pub fn with_child(mut self, child: MockFacilityBlock) -> Self {
+        self.children.push((child, false));
+        self
+    }
+
+    /// Nest an item of a list, such as a runway of an airport.
+    pub fn with_item(mut self, item: MockFacilityBlock) -> Self {
+        self.children.push((item, true));
+        self
+    }
+}
+
+/// A block of a facility definition, with the names of its fields.
+#[derive(Default)]
+struct FacilityDefinitionBlock {
+    name: String,
+    fields: Vec<String>,
+    children: Vec<FacilityDefinitionBlock>,
+}
+
+impl FacilityDefinitionBlock {
+    /// Parse the fields added to a facility definition, returning the
+    /// outermost block.
+    fn parse(definition: &[String]) -> Option<Self> {
+        let mut open = vec![FacilityDefinitionBlock::default()];
+        for field in definition {
+            if let Some(name) = field.strip_prefix("OPEN ") {
+                open.push(FacilityDefinitionBlock {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+            } else if field.starts_with("CLOSE ") && open.len() > 1 {
+                let block = open.pop().unwrap();
+                open.last_mut().unwrap().children.push(block);
+            } else {
+                open.last_mut().unwrap().fields.push(field.clone());
+            }
+        }
+        open.pop()?.children.pop()
+    }
+}
+
+/// Where a facility data block sits in its parent.
+struct FacilityBlockPosition {
+    parent_id: sys::DWORD,
+    is_list_item: bool,
+    item_index: usize,
+    list_size: usize,
+}
+
+/// Encode the messages sending `block` as described by `definition`, along
+/// with the blocks nested in it. Fails if the block has no value for a field
+/// of the definition.
+fn facility_data_messages(
+    definition: &FacilityDefinitionBlock,
+    block: &MockFacilityBlock,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    position: FacilityBlockPosition,
+    messages: &mut Vec<Vec<u8>>,
+) -> Option<()> {
+    let unique_id = messages.len() as sys::DWORD + 1;
+    let mut data = Vec::new();
+    for field in &definition.fields {
+        data.extend_from_slice(block.fields.get(field)?);
+    }
+    // The message always has room for the first field.
+    data.resize(data.len().max(4), 0);
+    let mut message = Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_FACILITY_DATA);
+    message
+        .u32(request_id)
+        .u32(unique_id)
+        .u32(position.parent_id)
+        .u32(block.data_type)
+        .u32(position.is_list_item.into())
+        .u32(position.item_index as u32)
+        .u32(position.list_size as u32)
+        .bytes(&data);
+    messages.push(message.finish());
+
+    for child_definition in &definition.children {
+        let children = block
+            .children
+            .iter()
+            .filter(|(child, _)| child.name == child_definition.name)
+            .collect::<Vec<_>>();
+        let items = children.iter().filter(|(_, is_item)| *is_item).count();
+        let mut item_index = 0;
+        for (child, is_list_item) in children {
+            let position = FacilityBlockPosition {
+                parent_id: unique_id,
+                is_list_item: *is_list_item,
+                item_index: if *is_list_item { item_index } else { 0 },
+                list_size: if *is_list_item { items } else { 0 },
+            };
+            item_index += usize::from(*is_list_item);
+            facility_data_messages(child_definition, child, request_id, position, messages)?;
+        }
+    }
+    Some(())
+}
+
+#[derive(Default)]
+struct State {
+    packet_id: sys::DWORD,
+    outbox: Vec<Vec<u8>>,
+    failures: Vec<(sys::SIMCONNECT_EXCEPTION, sys::DWORD)>,
+    simvars: HashMap<(sys::SIMCONNECT_OBJECT_ID, String), f64>,
+    string_simvars: HashMap<(sys::SIMCONNECT_OBJECT_ID, String), String>,
+    /// SimObjects other than the user's aircraft, with their type.
+    objects: BTreeMap<sys::SIMCONNECT_OBJECT_ID, sys::SIMCONNECT_SIMOBJECT_TYPE>,
+    /// The flight plans of AI aircraft, and the AI objects whose control was
+    /// released.
+    ai_flight_plans: HashMap<sys::SIMCONNECT_OBJECT_ID, String>,
+    ai_released: HashSet<sys::SIMCONNECT_OBJECT_ID>,
+    data_definitions: HashMap<sys::SIMCONNECT_DATA_DEFINITION_ID, Vec<Datum>>,
+    data_requests: BTreeMap<sys::SIMCONNECT_DATA_REQUEST_ID, DataRequest>,
+    client_events: HashMap<sys::SIMCONNECT_CLIENT_EVENT_ID, String>,
+    notification_groups:
+        HashMap<sys::SIMCONNECT_CLIENT_EVENT_ID, sys::SIMCONNECT_NOTIFICATION_GROUP_ID>,
+    group_priorities: HashMap<sys::SIMCONNECT_NOTIFICATION_GROUP_ID, sys::DWORD>,
+    system_events: HashMap<sys::SIMCONNECT_CLIENT_EVENT_ID, (String, bool)>,
+    /// Input mappings by group and lowercase input definition.
+    input_events: BTreeMap<(sys::SIMCONNECT_INPUT_GROUP_ID, String), MappedInput>,
+    input_group_priorities: HashMap<sys::SIMCONNECT_INPUT_GROUP_ID, sys::DWORD>,
+    /// Input groups which are turned on. Groups start out off.
+    input_groups_on: HashSet<sys::SIMCONNECT_INPUT_GROUP_ID>,
+    /// The integer and string answers to system state requests, by lowercase
+    /// state name.
+    system_states: HashMap<String, (sys::DWORD, String)>,
+    /// Keys reserved with `RequestReservedKey`, by lowercase key.
+    reserved_keys: HashMap<String, sys::SIMCONNECT_CLIENT_EVENT_ID>,
+    /// Facilities by list type, in the order they were added.
+    facilities: HashMap<sys::SIMCONNECT_FACILITY_LIST_TYPE, Vec<MockFacility>>,
+    facility_definitions: HashMap<sys::SIMCONNECT_DATA_DEFINITION_ID, Vec<String>>,
+    /// Facility data by ICAO identifier and region.
+    facility_data: Vec<(String, String, MockFacilityBlock)>,
+    /// The request IDs of the facility subscriptions by list type, for
+    /// facilities coming into and going out of range.
+    facility_subscriptions: HashMap<
+        sys::SIMCONNECT_FACILITY_LIST_TYPE,
+        (
+            Option<sys::SIMCONNECT_DATA_REQUEST_ID>,
+            Option<sys::SIMCONNECT_DATA_REQUEST_ID>,
+        ),
+    >,
+    /// The input events of the user's aircraft, in the order they were added.
+    aircraft_input_events: Vec<MockInputEvent>,
+    /// The hashes of the input events the client subscribed to.
+    input_event_subscriptions: HashSet<u64>,
+    transmitted_events: Vec<TransmittedEvent>,
+    client_data_names: HashMap<sys::SIMCONNECT_CLIENT_DATA_ID, String>,
+    client_data_areas: HashMap<String, ClientDataArea>,
+    client_data_definitions:
+        HashMap<sys::SIMCONNECT_CLIENT_DATA_DEFINITION_ID, Vec<(usize, usize)>>,
+    client_data_requests: BTreeMap<sys::SIMCONNECT_DATA_REQUEST_ID, ClientDataRequest>,
+}
+
+impl State {
+    fn exception(&mut self, exception: sys::SIMCONNECT_EXCEPTION, index: sys::DWORD) {
+        self.exception_for(self.packet_id, exception, index);
+    }
+
+    /// Report an exception caused by an earlier packet.
+    fn exception_for(
+        &mut self,
+        packet_id: sys::DWORD,
+        exception: sys::SIMCONNECT_EXCEPTION,
+        index: sys::DWORD,
+    ) {
+        let mut message = Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION);
+        message
+            .u32(exception as sys::DWORD)
+            .u32(packet_id)
+            .u32(index);
+        self.outbox.push(message.finish());
+    }
+
+    fn event(
+        &mut self,
+        group_id: sys::SIMCONNECT_NOTIFICATION_GROUP_ID,
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        data: sys::DWORD,
+    ) {
+        self.event_as(
+            sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT,
+            group_id,
+            event_id,
+            data,
+        );
+    }
+
+    /// Send an event in a message of its own kind, such as the multiplayer
+    /// system events, which have nothing after the event.
+    fn event_as(
+        &mut self,
+        id: sys::SIMCONNECT_RECV_ID,
+        group_id: sys::SIMCONNECT_NOTIFICATION_GROUP_ID,
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        data: sys::DWORD,
+    ) {
+        let mut message = Message::new(id);
+        message.u32(group_id).u32(event_id).u32(data);
+        self.outbox.push(message.finish());
+    }
+
+    fn input(&mut self, input_definition: &str, down: bool) {
+        let input_definition = input_definition.to_lowercase();
+        if let Some(&event_id) = self.reserved_keys.get(&input_definition) {
+            if down {
+                self.event(UNKNOWN_GROUP, event_id, 0);
+            }
+            return;
+        }
+        let mut events = Vec::new();
+        for ((group_id, definition), mapping) in &self.input_events {
+            if *definition != input_definition || !self.input_groups_on.contains(group_id) {
+                continue;
+            }
+            let (event_id, value) = if down {
+                (mapping.down_event_id, mapping.down_value)
+            } else {
+                (mapping.up_event_id, mapping.up_value)
+            };
+            if event_id == sys::SIMCONNECT_UNUSED {
+                continue;
+            }
+            let priority = self
+                .input_group_priorities
+                .get(group_id)
+                .copied()
+                .unwrap_or(sys::SIMCONNECT_GROUP_PRIORITY_DEFAULT);
+            events.push((priority, *group_id, event_id, value));
+        }
+        events.sort();
+        for (_, group_id, event_id, value) in events {
+            self.event(group_id, event_id, value);
+        }
+    }
+
+    /// The IDs of the subscriptions to the system event `name` which are on.
+    fn system_event_ids(&self, name: &str) -> Vec<sys::SIMCONNECT_CLIENT_EVENT_ID> {
+        let mut event_ids = self
+            .system_events
+            .iter()
+            .filter(|(_, (event_name, on))| *on && event_name.eq_ignore_ascii_case(name))
+            .map(|(&event_id, _)| event_id)
+            .collect::<Vec<_>>();
+        event_ids.sort();
+        event_ids
+    }
+
+    /// Send the `ObjectAdded` or `ObjectRemoved` system event to its
+    /// subscribers.
+    fn object_event(
+        &mut self,
+        name: &str,
+        object_id: sys::SIMCONNECT_OBJECT_ID,
+        r#type: sys::SIMCONNECT_SIMOBJECT_TYPE,
+    ) {
+        for event_id in self.system_event_ids(name) {
+            let mut message =
+                Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_OBJECT_ADDREMOVE);
+            message
+                .u32(UNKNOWN_GROUP)
+                .u32(event_id)
+                .u32(object_id)
+                .u32(r#type);
+            self.outbox.push(message.finish());
+        }
+    }
+
+    /// Create an AI object, assigning it the next free object ID. Objects
+    /// without a container title can't be created.
+    fn create_ai_object(
+        &mut self,
+        container_title: &std::ffi::CStr,
+        r#type: sys::SIMCONNECT_SIMOBJECT_TYPE,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    ) -> Option<sys::SIMCONNECT_OBJECT_ID> {
+        if container_title.is_empty() {
+            self.exception(
+                sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_CREATE_OBJECT_FAILED,
+                1,
+            );
+            return None;
+        }
+        let object_id = self
+            .objects
+            .keys()
+            .next_back()
+            .map_or(sys::SIMCONNECT_OBJECT_ID_USER, |&object_id| object_id)
+            + 1;
+        self.objects.insert(object_id, r#type);
+        let mut message =
+            Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ASSIGNED_OBJECT_ID);
+        message.u32(request_id).u32(object_id);
+        self.outbox.push(message.finish());
+        self.object_event("ObjectAdded", object_id, r#type);
+        Some(object_id)
+    }
+
+    /// Send `facilities` of `list_type` for `request_id`, split into pages.
+    /// An empty list is sent as a single empty page.
+    fn facility_list<'a>(
+        &mut self,
+        list_type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        facilities: impl IntoIterator<Item = &'a [u8]>,
+    ) {
+        let Some(recv_id) = facility_list_message(list_type) else {
+            self.exception(
+                sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_INVALID_ENUM,
+                1,
+            );
+            return;
+        };
+        let facilities = facilities.into_iter().collect::<Vec<_>>();
+        let pages = facilities.chunks(FACILITIES_PAGE_SIZE).collect::<Vec<_>>();
+        let pages = if pages.is_empty() {
+            vec![&[][..]]
+        } else {
+            pages
+        };
+        for (entry_number, page) in pages.iter().enumerate() {
+            let mut message = Message::new(recv_id);
+            message
+                .u32(request_id)
+                .u32(page.len() as u32)
+                .u32(entry_number as u32)
+                .u32(pages.len() as u32);
+            for data in page.iter() {
+                message.bytes(data);
+            }
+            self.outbox.push(message.finish());
+        }
+    }
+
+    /// Send all the facilities of `list_type` for `request_id`.
+    fn all_facilities(
+        &mut self,
+        list_type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    ) {
+        let facilities = self.facilities.remove(&list_type).unwrap_or_default();
+        self.facility_list(
+            list_type,
+            request_id,
+            facilities.iter().map(|facility| &facility.data[..]),
+        );
+        self.facilities.insert(list_type, facilities);
+    }
+
+    fn transmit(&mut self, object_id: sys::DWORD, event_id: sys::DWORD, data: [sys::DWORD; 5]) {
+        let Some(event_name) = self.client_events.get(&event_id).cloned() else {
+            self.exception(
+                sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                2,
+            );
+            return;
+        };
+        if let Some(&group_id) = self.notification_groups.get(&event_id) {
+            self.event(group_id, event_id, data[0]);
+        }
+        self.transmitted_events.push(TransmittedEvent {
+            object_id: ObjectId(object_id),
+            event_id: ClientEventId(event_id),
+            event_name: Some(event_name),
+            data,
+        });
+    }
+
+    /// The objects a by-type request for `r#type` is answered with. The user's
+    /// aircraft is an aircraft, and the radius is ignored.
+    fn objects_of_type(
+        &self,
+        r#type: sys::SIMCONNECT_SIMOBJECT_TYPE,
+    ) -> Vec<sys::SIMCONNECT_OBJECT_ID> {
+        let mut object_ids = Vec::new();
+        if matches!(
+            r#type,
+            sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_USER
+                | sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_ALL
+                | sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT
+        ) {
+            object_ids.push(sys::SIMCONNECT_OBJECT_ID_USER);
+        }
+        if r#type != sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_USER {
+            object_ids.extend(
+                self.objects
+                    .iter()
+                    .filter(|&(_, &object_type)| {
+                        r#type == sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_ALL
+                            || object_type == r#type
+                    })
+                    .map(|(&object_id, _)| object_id),
+            );
+        }
+        object_ids
+    }
+
+    /// The current value of every datum of a data definition, or `None` if
+    /// there is no such definition.
+    fn sim_object_data(
+        &self,
+        define_id: sys::DWORD,
+        object_id: sys::DWORD,
+    ) -> Option<Vec<Vec<u8>>> {
+        let definition = self.data_definitions.get(&define_id)?;
+        let data = definition
+            .iter()
+            .map(|datum| {
+                let mut data = Vec::new();
+                let key = (object_id, datum.name.clone());
+                if is_string(datum.datum_type) {
+                    let value = self.string_simvars.get(&key).map_or("", String::as_str);
+                    encode_string(&mut data, datum.datum_type, value);
+                } else {
+                    let value = self.simvars.get(&key).copied().unwrap_or(0.0);
+                    encode_datum(&mut data, datum.datum_type, value);
+                }
+                data
+            })
+            .collect();
+        Some(data)
+    }
+
+    /// The contents of a client data area as laid out by a client data
+    /// definition, or the exception to report if the area or definition
+    /// doesn't exist, or the definition reaches past the end of the area.
+    fn client_data(
+        &self,
+        client_data_id: sys::DWORD,
+        define_id: sys::DWORD,
+    ) -> std::result::Result<Vec<u8>, sys::SIMCONNECT_EXCEPTION> {
+        let area = self
+            .client_data_names
+            .get(&client_data_id)
+            .and_then(|name| self.client_data_areas.get(name));
+        let (Some(area), Some(definition)) = (area, self.client_data_definitions.get(&define_id))
+        else {
+            return Err(sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID);
+        };
+        let mut data = Vec::new();
+        for &(offset, size) in definition {
+            let Some(source) = area.data.get(offset..offset + size) else {
+                return Err(sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_OUT_OF_BOUNDS);
+            };
+            if data.len() < offset + size {
+                data.resize(offset + size, 0);
+            }
+            data[offset..offset + size].copy_from_slice(source);
+        }
+        Ok(data)
+    }
+
+    fn handle(&mut self, call: Call<'_>) {
+        match call {
+            Call::MapClientEventToSimEvent {
+                event_id,
+                event_name,
+            } => {
+                self.client_events
+                    .insert(event_id, event_name.to_string_lossy().into_owned());
+            }
+            Call::TransmitClientEvent {
+                object_id,
+                event_id,
+                data,
+                ..
+            } => self.transmit(object_id, event_id, [data, 0, 0, 0, 0]),
+            Call::TransmitClientEventEx1 {
+                object_id,
+                event_id,
+                data,
+                ..
+            } => self.transmit(object_id, event_id, data),
+            Call::SetSystemEventState { event_id, state } => {
+                if let Some((_, on)) = self.system_events.get_mut(&event_id) {
+                    *on = state != 0;
+                }
+            }
+            Call::AddClientEventToNotificationGroup {
+                group_id, event_id, ..
+            } => {
+                self.notification_groups.insert(event_id, group_id);
+            }
+            Call::RemoveClientEvent { group_id, event_id } => {
+                if self.notification_groups.get(&event_id) == Some(&group_id) {
+                    self.notification_groups.remove(&event_id);
+                }
+            }
+            Call::SetNotificationGroupPriority { group_id, priority } => {
+                self.group_priorities.insert(group_id, priority);
+            }
+            Call::ClearNotificationGroup { group_id } => {
+                self.notification_groups
+                    .retain(|_, event_group_id| *event_group_id != group_id);
+            }
+            Call::RequestNotificationGroup { .. } => {}
+            Call::MapInputEventToClientEvent {
+                group_id,
+                input_definition,
+                down_event_id,
+                down_value,
+                up_event_id,
+                up_value,
+                ..
+            } => {
+                let input_definition = input_definition.to_string_lossy().to_lowercase();
+                self.input_events.insert(
+                    (group_id, input_definition),
+                    MappedInput {
+                        down_event_id,
+                        down_value,
+                        up_event_id,
+                        up_value,
+                    },
+                );
+            }
+            Call::SetInputGroupPriority { group_id, priority } => {
+                self.input_group_priorities.insert(group_id, priority);
+            }
+            Call::RemoveInputEvent {
+                group_id,
+                input_definition,
+            } => {
+                let input_definition = input_definition.to_string_lossy().to_lowercase();
+                if self
+                    .input_events
+                    .remove(&(group_id, input_definition))
+                    .is_none()
+                {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        2,
+                    );
+                }
+            }
+            Call::ClearInputGroup { group_id } => {
+                self.input_events
+                    .retain(|(input_group_id, _), _| *input_group_id != group_id);
+            }
+            Call::SetInputGroupState { group_id, state } => {
+                if state != 0 {
+                    self.input_groups_on.insert(group_id);
+                } else {
+                    self.input_groups_on.remove(&group_id);
+                }
+            }
+            Call::RequestReservedKey {
+                event_id,
+                key_choice_1,
+                key_choice_2,
+                key_choice_3,
+            } => {
+                // The first choice which isn't reserved yet is given out.
+                let choice = [Some(key_choice_1), key_choice_2, key_choice_3]
+                    .into_iter()
+                    .flatten()
+                    .map(|choice| choice.to_string_lossy())
+                    .find(|choice| {
+                        !choice.is_empty()
+                            && !self.reserved_keys.contains_key(&choice.to_lowercase())
+                    });
+                match choice {
+                    Some(choice) => {
+                        self.reserved_keys.insert(choice.to_lowercase(), event_id);
+                        let mut message =
+                            Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_RESERVED_KEY);
+                        message.string(&choice, 30).string(&choice, 50);
+                        self.outbox.push(message.finish());
+                    }
+                    None => {
+                        self.exception(sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_ERROR, 2)
+                    }
+                }
+            }
+            Call::AddToDataDefinition {
+                define_id,
+                datum_name,
+                datum_type,
+                datum_id,
+                ..
+            } => {
+                self.data_definitions
+                    .entry(define_id)
+                    .or_default()
+                    .push(Datum {
+                        name: datum_name.to_string_lossy().into_owned(),
+                        datum_type,
+                        datum_id,
+                    });
+            }
+            Call::ClearDataDefinition { define_id } => {
+                self.data_definitions.remove(&define_id);
+            }
+            Call::RequestDataOnSimObject {
+                request_id,
+                define_id,
+                object_id,
+                period,
+                flags,
+                origin,
+                interval,
+                limit,
+            } => {
+                if !self.data_definitions.contains_key(&define_id) {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        2,
+                    );
+                } else if period == sys::SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_NEVER {
+                    self.data_requests.remove(&request_id);
+                } else {
+                    self.data_requests.insert(
+                        request_id,
+                        DataRequest {
+                            packet_id: self.packet_id,
+                            define_id,
+                            object_id,
+                            period,
+                            flags,
+                            schedule: Schedule::new(origin, interval, limit),
+                            last: None,
+                        },
+                    );
+                }
+            }
+            Call::RequestDataOnSimObjectType {
+                request_id,
+                define_id,
+                r#type,
+                ..
+            } => {
+                let Some(definition) = self.data_definitions.get(&define_id) else {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        2,
+                    );
+                    return;
+                };
+                let define_count = definition.len() as sys::DWORD;
+                let object_ids = self.objects_of_type(r#type);
+                // With no objects, a single message is sent with a `dwoutof` of
+                // 0, and the placeholder `dwData` of the struct.
+                if object_ids.is_empty() {
+                    let mut message = Message::new(
+                        sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE,
+                    );
+                    message
+                        .u32(request_id)
+                        .u32(0)
+                        .u32(define_id)
+                        .u32(0)
+                        .u32(0)
+                        .u32(0)
+                        .u32(0)
+                        .u32(0);
+                    self.outbox.push(message.finish());
+                }
+                for (entry, &object_id) in object_ids.iter().enumerate() {
+                    let data = self
+                        .sim_object_data(define_id, object_id)
+                        .unwrap_or_default()
+                        .concat();
+                    let mut message = Message::new(
+                        sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE,
+                    );
+                    message
+                        .u32(request_id)
+                        .u32(object_id)
+                        .u32(define_id)
+                        .u32(0)
+                        .u32(entry as sys::DWORD + 1)
+                        .u32(object_ids.len() as sys::DWORD)
+                        .u32(define_count)
+                        .bytes(&data);
+                    self.outbox.push(message.finish());
+                }
+            }
+            Call::SetDataOnSimObject {
+                define_id,
+                object_id,
+                data,
+                ..
+            } => {
+                let Some(datums) = self.data_definitions.get(&define_id) else {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        1,
+                    );
+                    return;
+                };
+                let mut offset = 0;
+                for datum in datums {
+                    let size = match datum.datum_type {
+                        sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRINGV => data
+                            .get(offset..)
+                            .and_then(|rest| rest.iter().position(|&b| b == 0))
+                            .map_or(0, |len| len + 1),
+                        datum_type => datum_size(datum_type),
+                    };
+                    let key = (object_id, datum.name.clone());
+                    match data.get(offset..offset + size) {
+                        Some(bytes) if is_string(datum.datum_type) => {
+                            self.string_simvars.insert(key, decode_string(bytes));
+                        }
+                        Some(bytes) => {
+                            if let Some(value) = decode_datum(bytes, datum.datum_type) {
+                                self.simvars.insert(key, value);
+                            }
+                        }
+                        None => {}
+                    }
+                    offset += size;
+                }
+            }
+            Call::SubscribeToSystemEvent {
+                event_id,
+                system_event_name,
+            } => {
+                self.system_events.insert(
+                    event_id,
+                    (system_event_name.to_string_lossy().into_owned(), true),
+                );
+            }
+            Call::UnsubscribeFromSystemEvent { event_id } => {
+                self.system_events.remove(&event_id);
+            }
+            Call::AiCreateParkedAtcAircraft {
+                container_title,
+                request_id,
+                ..
+            }
+            | Call::AiCreateNonAtcAircraft {
+                container_title,
+                request_id,
+                ..
+            } => {
+                self.create_ai_object(
+                    container_title,
+                    sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT,
+                    request_id,
+                );
+            }
+            Call::AiCreateEnrouteAtcAircraft {
+                container_title,
+                flight_plan_path,
+                request_id,
+                ..
+            } => {
+                if let Some(object_id) = self.create_ai_object(
+                    container_title,
+                    sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT,
+                    request_id,
+                ) {
+                    self.ai_flight_plans
+                        .insert(object_id, flight_plan_path.to_string_lossy().into_owned());
+                }
+            }
+            Call::AiCreateSimulatedObject {
+                container_title,
+                request_id,
+                ..
+            } => {
+                self.create_ai_object(
+                    container_title,
+                    sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_GROUND,
+                    request_id,
+                );
+            }
+            Call::AiReleaseControl { object_id, .. } => {
+                if self.objects.contains_key(&object_id) {
+                    self.ai_released.insert(object_id);
+                } else {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        1,
+                    );
+                }
+            }
+            Call::AiRemoveObject { object_id, .. } => match self.objects.remove(&object_id) {
+                Some(r#type) => {
+                    self.ai_flight_plans.remove(&object_id);
+                    self.ai_released.remove(&object_id);
+                    self.object_event("ObjectRemoved", object_id, r#type);
+                }
+                None => self.exception(
+                    sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                    1,
+                ),
+            },
+            Call::AiSetAircraftFlightPlan {
+                object_id,
+                flight_plan_path,
+                ..
+            } => {
+                if self.objects.contains_key(&object_id) {
+                    self.ai_flight_plans
+                        .insert(object_id, flight_plan_path.to_string_lossy().into_owned());
+                } else {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        1,
+                    );
+                }
+            }
+            Call::RequestSystemState { request_id, state } => {
+                let state = state.to_string_lossy().to_lowercase();
+                let (integer, string) = self.system_states.get(&state).cloned().unwrap_or_default();
+                let mut message =
+                    Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SYSTEM_STATE);
+                message
+                    .u32(request_id)
+                    .u32(integer)
+                    .u32(0)
+                    .string(&string, 260);
+                self.outbox.push(message.finish());
+            }
+            Call::MapClientDataNameToId {
+                client_data_name,
+                client_data_id,
+            } => {
+                self.client_data_names.insert(
+                    client_data_id,
+                    client_data_name.to_string_lossy().into_owned(),
+                );
+            }
+            Call::CreateClientData {
+                client_data_id,
+                size,
+                ..
+            } => {
+                let Some(name) = self.client_data_names.get(&client_data_id).cloned() else {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        1,
+                    );
+                    return;
+                };
+                if self.client_data_areas.contains_key(&name) {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_ALREADY_CREATED,
+                        1,
+                    );
+                    return;
+                }
+                self.client_data_areas.insert(
+                    name,
+                    ClientDataArea {
+                        data: vec![0; size as usize],
+                        version: 0,
+                    },
+                );
+            }
+            Call::AddToClientDataDefinition {
+                define_id,
+                offset,
+                size_or_type,
+                ..
+            } => {
+                self.client_data_definitions
+                    .entry(define_id)
+                    .or_default()
+                    .push((offset as usize, size_or_type as usize));
+            }
+            Call::RequestClientData {
+                client_data_id,
+                request_id,
+                define_id,
+                period,
+                flags,
+                origin,
+                interval,
+                limit,
+            } => {
+                if !self.client_data_names.contains_key(&client_data_id)
+                    || !self.client_data_definitions.contains_key(&define_id)
+                {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        1,
+                    );
+                } else if period
+                    == sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_NEVER
+                {
+                    self.client_data_requests.remove(&request_id);
+                } else {
+                    self.client_data_requests.insert(
+                        request_id,
+                        ClientDataRequest {
+                            packet_id: self.packet_id,
+                            client_data_id,
+                            define_id,
+                            period,
+                            flags,
+                            schedule: Schedule::new(origin, interval, limit),
+                            version: None,
+                            last: None,
+                        },
+                    );
+                }
+            }
+            Call::SetClientData {
+                client_data_id,
+                define_id,
+                data,
+                ..
+            } => {
+                let area = self
+                    .client_data_names
+                    .get(&client_data_id)
+                    .and_then(|name| self.client_data_areas.get_mut(name));
+                let (Some(area), Some(definition)) =
+                    (area, self.client_data_definitions.get(&define_id))
+                else {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        1,
+                    );
+                    return;
+                };
+                for &(offset, size) in definition {
+                    if let (Some(target), Some(source)) = (
+                        area.data.get_mut(offset..offset + size),
+                        data.get(offset..offset + size),
+                    ) {
+                        target.copy_from_slice(source);
+                    }
+                }
+                area.version += 1;
+            }
+            Call::FlightLoad { .. } | Call::FlightSave { .. } | Call::FlightPlanLoad { .. } => {}
+            Call::RequestFacilitiesList { r#type, request_id }
+            | Call::RequestFacilitiesListEx1 { r#type, request_id } => {
+                self.all_facilities(r#type, request_id);
+            }
+            Call::SubscribeToFacilities { r#type, request_id } => {
+                self.facility_subscriptions
+                    .insert(r#type, (Some(request_id), None));
+                self.all_facilities(r#type, request_id);
+            }
+            Call::SubscribeToFacilitiesEx1 {
+                r#type,
+                in_range_request_id,
+                out_of_range_request_id,
+            } => {
+                self.facility_subscriptions.insert(
+                    r#type,
+                    (Some(in_range_request_id), Some(out_of_range_request_id)),
+                );
+                self.all_facilities(r#type, in_range_request_id);
+            }
+            Call::AddToFacilityDefinition {
+                define_id,
+                field_name,
+            } => {
+                self.facility_definitions
+                    .entry(define_id)
+                    .or_default()
+                    .push(field_name.to_string_lossy().into_owned());
+            }
+            Call::RequestFacilityData {
+                define_id,
+                request_id,
+                icao,
+                region,
+            } => {
+                let Some(definition) = self
+                    .facility_definitions
+                    .get(&define_id)
+                    .and_then(|definition| FacilityDefinitionBlock::parse(definition))
+                else {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        1,
+                    );
+                    return;
+                };
+                let icao = icao.to_string_lossy();
+                let region = region.map(|region| region.to_string_lossy());
+                let facility = self
+                    .facility_data
+                    .iter()
+                    .find(|(ident, facility_region, block)| {
+                        ident.eq_ignore_ascii_case(&icao)
+                            && region
+                                .as_ref()
+                                .is_none_or(|region| facility_region.eq_ignore_ascii_case(region))
+                            && block.name == definition.name
+                    });
+                let mut messages = Vec::new();
+                if let Some((_, _, block)) = facility {
+                    let position = FacilityBlockPosition {
+                        parent_id: 0,
+                        is_list_item: false,
+                        item_index: 0,
+                        list_size: 0,
+                    };
+                    if facility_data_messages(
+                        &definition,
+                        block,
+                        request_id,
+                        position,
+                        &mut messages,
+                    )
+                    .is_none()
+                    {
+                        self.exception(
+                            sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_DATA_ERROR,
+                            1,
+                        );
+                        return;
+                    }
+                }
+                self.outbox.extend(messages);
+                let mut message =
+                    Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_FACILITY_DATA_END);
+                message.u32(request_id);
+                self.outbox.push(message.finish());
+            }
+            Call::UnsubscribeToFacilities { r#type } => {
+                self.facility_subscriptions.remove(&r#type);
+            }
+            Call::UnsubscribeToFacilitiesEx1 {
+                r#type,
+                in_range,
+                out_of_range,
+            } => {
+                if let Some(subscription) = self.facility_subscriptions.get_mut(&r#type) {
+                    if in_range {
+                        subscription.0 = None;
+                    }
+                    if out_of_range {
+                        subscription.1 = None;
+                    }
+                    if *subscription == (None, None) {
+                        self.facility_subscriptions.remove(&r#type);
+                    }
+                }
+            }
+            Call::EnumerateInputEvents { request_id } => {
+                let pages = self
+                    .aircraft_input_events
+                    .chunks(INPUT_EVENTS_PAGE_SIZE)
+                    .collect::<Vec<_>>();
+                let pages = if pages.is_empty() {
+                    vec![&[][..]]
+                } else {
+                    pages
+                };
+                let mut messages = Vec::new();
+                for (entry_number, page) in pages.iter().enumerate() {
+                    let mut message = Message::new(
+                        sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ENUMERATE_INPUT_EVENTS,
+                    );
+                    message
+                        .u32(request_id)
+                        .u32(page.len() as u32)
+                        .u32(entry_number as u32)
+                        .u32(pages.len() as u32);
+                    for event in page.iter() {
+                        let (r#type, _) = input_event_value(&event.value);
+                        message.string(&event.name, 64).u64(event.hash).u32(r#type);
+                    }
+                    messages.push(message.finish());
+                }
+                self.outbox.extend(messages);
+            }
+            Call::GetInputEvent { request_id, hash } => {
+                let Some(event) = self.input_event(hash) else {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_GET_INPUT_EVENT_FAILED,
+                        2,
+                    );
+                    return;
+                };
+                let (r#type, value) = input_event_value(&event.value);
+                let mut message =
+                    Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_GET_INPUT_EVENT);
+                message.u32(request_id).u32(r#type).bytes(&value);
+                self.outbox.push(message.finish());
+            }
+            Call::SetInputEvent { hash, value } => {
+                let value = match self.input_event(hash).map(|event| &event.value) {
+                    Some(InputEventValue::Double(_)) if value.len() == 8 => {
+                        InputEventValue::Double(f64::from_le_bytes(value.try_into().unwrap()))
+                    }
+                    Some(InputEventValue::String(_)) => {
+                        let len = value.iter().position(|&b| b == 0).unwrap_or(value.len());
+                        InputEventValue::String(String::from_utf8_lossy(&value[..len]).into_owned())
+                    }
+                    _ => {
+                        self.exception(
+                            sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_SET_INPUT_EVENT_FAILED,
+                            1,
+                        );
+                        return;
+                    }
+                };
+                self.change_input_event(hash, value);
+            }
+            Call::SubscribeInputEvent { hash } => {
+                if self.input_event(hash).is_none() {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        1,
+                    );
+                    return;
+                }
+                self.input_event_subscriptions.insert(hash);
+            }
+            Call::UnsubscribeInputEvent { hash } => {
+                self.input_event_subscriptions.remove(&hash);
+            }
+            Call::EnumerateInputEventParams { hash } => {
+                let Some(event) = self.input_event(hash) else {
+                    self.exception(
+                        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                        1,
+                    );
+                    return;
+                };
+                let mut message = Message::new(
+                    sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ENUMERATE_INPUT_EVENT_PARAMS,
+                );
+                message.u64(hash).string(&event.params, 260);
+                self.outbox.push(message.finish());
+            }
+        }
+    }
+
+    fn input_event(&self, hash: u64) -> Option<&MockInputEvent> {
+        self.aircraft_input_events
+            .iter()
+            .find(|event| event.hash == hash)
+    }
+
+    /// Set the value of the input event `hash`, notifying the client if it
+    /// subscribed to it and the value changed.
+    fn change_input_event(&mut self, hash: u64, value: InputEventValue) {
+        let Some(event) = self
+            .aircraft_input_events
+            .iter_mut()
+            .find(|event| event.hash == hash)
+        else {
+            return;
+        };
+        if event.value == value {
+            return;
+        }
+        event.value = value;
+        if self.input_event_subscriptions.contains(&hash) {
+            let (r#type, value) = input_event_value(&event.value);
+            let mut message =
+                Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SUBSCRIBE_INPUT_EVENT);
+            message.u64(hash).u32(r#type).bytes(&value);
+            self.outbox.push(message.finish());
+        }
+    }
+
+    /// Advance the sim by one frame, answering every active request which is
+    /// due.
+    fn frame(&mut self) {
+        let request_ids = self.data_requests.keys().copied().collect::<Vec<_>>();
+        for request_id in request_ids {
+            let request = &self.data_requests[&request_id];
+            // The definition may have been cleared since the request was made.
+            let Some(data) = self.sim_object_data(request.define_id, request.object_id) else {
+                self.exception_for(
+                    request.packet_id,
+                    sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID,
+                    2,
+                );
+                self.data_requests.remove(&request_id);
+                continue;
+            };
+            let definition = &self.data_definitions[&request.define_id];
+            let request = self.data_requests.get_mut(&request_id).unwrap();
+            if !request.schedule.tick() {
+                continue;
+            }
+            let only_changed = request.flags & sys::SIMCONNECT_DATA_REQUEST_FLAG_CHANGED != 0;
+            let changed = |index: usize| {
+                request
+                    .last
+                    .as_ref()
+                    .is_none_or(|last| last.get(index) != Some(&data[index]))
+            };
+            // Tagged data is pairs of datum ID and value, and with `CHANGED`
+            // only has the datums which changed.
+            let message = if request.flags & sys::SIMCONNECT_DATA_REQUEST_FLAG_TAGGED != 0 {
+                let mut bytes = Vec::new();
+                let mut define_count = 0;
+                for (index, datum) in definition.iter().enumerate() {
+                    if !only_changed || changed(index) {
+                        bytes.extend_from_slice(&datum.datum_id.to_ne_bytes());
+                        bytes.extend_from_slice(&data[index]);
+                        define_count += 1;
+                    }
+                }
+                (define_count > 0).then_some((define_count, bytes))
+            } else {
+                (!only_changed || (0..data.len()).any(changed)).then(|| (data.len(), data.concat()))
+            };
+            if let Some((define_count, bytes)) = message {
+                let mut message =
+                    Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA);
+                message
+                    .u32(request_id)
+                    .u32(request.object_id)
+                    .u32(request.define_id)
+                    .u32(request.flags)
+                    .u32(1)
+                    .u32(1)
+                    .u32(define_count as sys::DWORD)
+                    .bytes(&bytes);
+                self.outbox.push(message.finish());
+                request.schedule.sent += 1;
+            }
+            request.last = Some(data);
+            if request
+                .schedule
+                .finished(request.period == sys::SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_ONCE)
+            {
+                self.data_requests.remove(&request_id);
+            }
+        }
+
+        let request_ids = self
+            .client_data_requests
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        for request_id in request_ids {
+            let request = &self.client_data_requests[&request_id];
+            let name = &self.client_data_names[&request.client_data_id];
+            let Some(area) = self.client_data_areas.get(name) else {
+                continue;
+            };
+            // Every time the area is set is a period of `ON_SET`.
+            let version = area.version;
+            if request.period
+                == sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_ON_SET
+                && request.version == Some(version)
+            {
+                continue;
+            }
+            let data = match self.client_data(request.client_data_id, request.define_id) {
+                Ok(data) => data,
+                Err(exception) => {
+                    self.exception_for(request.packet_id, exception, 3);
+                    self.client_data_requests.remove(&request_id);
+                    continue;
+                }
+            };
+            let define_count = self.client_data_definitions[&request.define_id].len();
+            let request = self.client_data_requests.get_mut(&request_id).unwrap();
+            request.version = Some(version);
+            if !request.schedule.tick() {
+                continue;
+            }
+            let changed = request.last.as_ref() != Some(&data);
+            if request.flags & sys::SIMCONNECT_CLIENT_DATA_REQUEST_FLAG_CHANGED == 0 || changed {
+                let mut message =
+                    Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CLIENT_DATA);
+                message
+                    .u32(request_id)
+                    .u32(sys::SIMCONNECT_OBJECT_ID_USER)
+                    .u32(request.define_id)
+                    .u32(request.flags)
+                    .u32(1)
+                    .u32(1)
+                    .u32(define_count as sys::DWORD)
+                    .bytes(&data);
+                self.outbox.push(message.finish());
+                request.schedule.sent += 1;
+            }
+            request.last = Some(data);
+            if request.schedule.finished(
+                request.period
+                    == sys::SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_ONCE,
+            ) {
+                self.client_data_requests.remove(&request_id);
+            }
+        }
+    }
+}
+
+/// A scriptable, in-process stand-in for the sim, for testing code built on
+/// [`SimConnect`](super::SimConnect) without MSFS running. Requires the
+/// `mock` feature.
+///
+/// Every `call_dispatch` on the session behaves like one sim frame: queued
+/// messages are delivered, and every active data request which is due is
+/// answered from the server's simvars and client data areas. Every period is
+/// one frame, so `origin`, `interval` and `limit` count frames.
+/// ```rs
+/// let server = MockServer::new();
+/// let mut sim = SimConnect::open_with_backend(server.backend(), |sim, recv| {
+///     // ...
+/// })?;
+///
+/// server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 5000.0);
+/// let _altitude = sim.request_data_on_sim_object::<Altitude>(RequestId(0), ObjectId::USER, Period::SimFrame)?;
+/// sim.call_dispatch()?;
+/// ```
+#[derive(Clone, Default)]
+pub struct MockServer {
+    state: Rc<RefCell<State>>,
+}
+
+impl std::fmt::Debug for MockServer {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("MockServer").finish()
+    }
+}
+
+impl MockServer {
+    /// Create a new server.
+    pub fn new() -> Self {
+        let server = MockServer::default();
+        let mut message = Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_OPEN);
+        message.string("MockServer", 256);
+        for _ in 0..10 {
+            message.u32(0);
+        }
+        server.state.borrow_mut().outbox.push(message.finish());
+        server
+    }
+
+    /// Create a backend connected to this server, to be passed to
+    /// `SimConnect::open_with_backend`.
+    pub fn backend(&self) -> MockBackend {
+        MockBackend {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Set the value of a simvar on an object. Values are converted to the
+    /// datum type requested by the data definition.
+    pub fn set_simvar(&self, object_id: ObjectId, name: &str, value: f64) {
+        self.state
+            .borrow_mut()
+            .simvars
+            .insert((object_id.0, name.to_string()), value);
+    }
+
+    /// Get the value of a simvar on an object, as last set by the test or by
+    /// `set_data_on_sim_object`.
+    pub fn simvar(&self, object_id: ObjectId, name: &str) -> Option<f64> {
+        self.state
+            .borrow()
+            .simvars
+            .get(&(object_id.0, name.to_string()))
+            .copied()
+    }
+
+    /// Set the value of a string simvar on an object, sent for datums of any
+    /// of the string types.
+    pub fn set_simvar_string(&self, object_id: ObjectId, name: &str, value: &str) {
+        self.state
+            .borrow_mut()
+            .string_simvars
+            .insert((object_id.0, name.to_string()), value.to_string());
+    }
+
+    /// Get the value of a string simvar on an object, as last set by the test
+    /// or by `set_data_on_sim_object`.
+    pub fn simvar_string(&self, object_id: ObjectId, name: &str) -> Option<String> {
+        self.state
+            .borrow()
+            .string_simvars
+            .get(&(object_id.0, name.to_string()))
+            .cloned()
+    }
+
+    /// Get the contents of the client data area with the given `name`.
+    pub fn client_data(&self, name: &str) -> Option<Vec<u8>> {
+        self.state
+            .borrow()
+            .client_data_areas
+            .get(name)
+            .map(|area| area.data.clone())
+    }
+
+    /// Replace the contents of the client data area with the given `name`,
+    /// as if another client had called `set_client_data`.
+    pub fn set_client_data(&self, name: &str, data: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        let area = state
+            .client_data_areas
+            .entry(name.to_string())
+            .or_insert_with(|| ClientDataArea {
+                data: Vec::new(),
+                version: 0,
+            });
+        area.data = data.to_vec();
+        area.version += 1;
+    }
+
+    /// Set the answer to requests for the system state `name`. States which
+    /// are flags, such as `Sim`, are answered with `integer`, and those which
+    /// are paths with `string`.
+    pub fn set_system_state(&self, name: &str, integer: sys::DWORD, string: &str) {
+        self.state
+            .borrow_mut()
+            .system_states
+            .insert(name.to_lowercase(), (integer, string.to_string()));
+    }
+
+    /// Make the next call sent by the client fail with `exception`, reporting
+    /// the parameter at `index` as the cause.
+    pub fn fail_next(&self, exception: sys::SIMCONNECT_EXCEPTION, index: sys::DWORD) {
+        self.state.borrow_mut().failures.push((exception, index));
+    }
+
+    /// Fire the sim event `name`, notifying the client if it subscribed to it
+    /// as a system event, or mapped it and added it to a notification group.
+    /// Notification groups are notified in order of priority, followed by
+    /// system events.
+    pub fn send_event(&self, name: &str, data: sys::DWORD) {
+        let mut state = self.state.borrow_mut();
+        // The multiplayer system events are sent as messages of their own.
+        let system_id = match name.to_ascii_lowercase().as_str() {
+            "multiplayerserverstarted" => {
+                sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_SERVER_STARTED
+            }
+            "multiplayerclientstarted" => {
+                sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_CLIENT_STARTED
+            }
+            "multiplayersessionended" => {
+                sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_MULTIPLAYER_SESSION_ENDED
+            }
+            _ => sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT,
+        };
+        let mut events = Vec::new();
+        for (&event_id, (event_name, on)) in &state.system_events {
+            if *on && event_name.eq_ignore_ascii_case(name) {
+                events.push((sys::DWORD::MAX, UNKNOWN_GROUP, event_id, system_id));
+            }
+        }
+        for (&event_id, event_name) in &state.client_events {
+            if let Some(&group_id) = state.notification_groups.get(&event_id)
+                && event_name.eq_ignore_ascii_case(name)
+            {
+                let priority = state
+                    .group_priorities
+                    .get(&group_id)
+                    .copied()
+                    .unwrap_or(sys::SIMCONNECT_GROUP_PRIORITY_DEFAULT);
+                events.push((
+                    priority,
+                    group_id,
+                    event_id,
+                    sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT,
+                ));
+            }
+        }
+        events.sort();
+        for (_, group_id, event_id, id) in events {
+            state.event_as(id, group_id, event_id, data);
+        }
+    }
+
+    /// Fire the system event `name` with the path of a file, as sent for
+    /// `FlightLoaded` and the like.
+    pub fn send_filename_event(&self, name: &str, file_name: &str) {
+        let mut state = self.state.borrow_mut();
+        for event_id in state.system_event_ids(name) {
+            let mut message =
+                Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FILENAME);
+            message
+                .u32(UNKNOWN_GROUP)
+                .u32(event_id)
+                .u32(0)
+                .string(file_name, 260)
+                .u32(0);
+            state.outbox.push(message.finish());
+        }
+    }
+
+    /// Fire the system event `RaceEnd` or `RaceLap`, with the racer's number
+    /// or the index of the lap, and the racer's result.
+    pub fn send_race_event(&self, name: &str, number: sys::DWORD, result: &RaceResult) {
+        let id = if name.eq_ignore_ascii_case("RaceLap") {
+            sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_RACE_LAP
+        } else {
+            sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_RACE_END
+        };
+        let mut state = self.state.borrow_mut();
+        for event_id in state.system_event_ids(name) {
+            let mut message = Message::new(id);
+            message
+                .u32(UNKNOWN_GROUP)
+                .u32(event_id)
+                .u32(0)
+                .u32(number)
+                .u32(result.racers)
+                .bytes(&result.mission_guid)
+                .string(&result.player_name, 260)
+                .string(&result.session_type, 260)
+                .string(&result.aircraft, 260)
+                .string(&result.player_role, 260)
+                .u64(result.total_time.to_bits())
+                .u64(result.penalty_time.to_bits())
+                .u32(result.disqualified as sys::DWORD);
+            state.outbox.push(message.finish());
+        }
+    }
+
+    /// Fire the system event `name` with a frame rate and simulation speed,
+    /// as sent for `Frame` and `PauseFrame`.
+    pub fn send_frame_event(&self, name: &str, frame_rate: f32, sim_speed: f32) {
+        let mut state = self.state.borrow_mut();
+        for event_id in state.system_event_ids(name) {
+            let mut message = Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FRAME);
+            message
+                .u32(UNKNOWN_GROUP)
+                .u32(event_id)
+                .u32(0)
+                .u32(frame_rate.to_bits())
+                .u32(sim_speed.to_bits());
+            state.outbox.push(message.finish());
+        }
+    }
+
+    /// Press the key or joystick button `input_definition`, sending the down
+    /// events it is mapped to in input groups which are turned on, in order
+    /// of the groups' priority, or the event of the key if it is reserved.
+    pub fn press_input(&self, input_definition: &str) {
+        self.state.borrow_mut().input(input_definition, true);
+    }
+
+    /// Release the key or joystick button `input_definition`, sending the up
+    /// events it is mapped to in input groups which are turned on.
+    pub fn release_input(&self, input_definition: &str) {
+        self.state.borrow_mut().input(input_definition, false);
+    }
+
+    /// Add a SimObject of `r#type`, which by-type requests are answered with
+    /// along with the user's aircraft, sending the `ObjectAdded` system event.
+    pub fn add_object(&self, object_id: ObjectId, r#type: sys::SIMCONNECT_SIMOBJECT_TYPE) {
+        let mut state = self.state.borrow_mut();
+        state.objects.insert(object_id.0, r#type);
+        state.object_event("ObjectAdded", object_id.0, r#type);
+    }
+
+    /// Remove a SimObject added with `add_object`, sending the `ObjectRemoved`
+    /// system event.
+    pub fn remove_object(&self, object_id: ObjectId) {
+        let mut state = self.state.borrow_mut();
+        if let Some(r#type) = state.objects.remove(&object_id.0) {
+            state.object_event("ObjectRemoved", object_id.0, r#type);
+        }
+    }
+
+    /// Add a facility, which facility lists are answered with, sending it to
+    /// the client's subscription to its kind of facility.
+    pub fn add_facility<T: Facility>(&self, facility: &T) {
+        let mut state = self.state.borrow_mut();
+        let data = as_bytes(&facility.to_data()).to_vec();
+        if let Some(&(Some(request_id), _)) = state.facility_subscriptions.get(&T::LIST_TYPE) {
+            state.facility_list(T::LIST_TYPE, request_id, [&data[..]]);
+        }
+        state
+            .facilities
+            .entry(T::LIST_TYPE)
+            .or_default()
+            .push(MockFacility {
+                ident: facility.ident().to_string(),
+                region: facility.region().to_string(),
+                data,
+            });
+    }
+
+    /// Remove a facility added with `add_facility`, sending it to the
+    /// client's subscription to facilities going out of range.
+    pub fn remove_facility<T: Facility>(&self, ident: &str, region: &str) {
+        let mut state = self.state.borrow_mut();
+        let facilities = state.facilities.entry(T::LIST_TYPE).or_default();
+        let Some(index) = facilities
+            .iter()
+            .position(|facility| facility.ident == ident && facility.region == region)
+        else {
+            return;
+        };
+        let facility = facilities.remove(index);
+        if let Some(&(_, Some(request_id))) = state.facility_subscriptions.get(&T::LIST_TYPE) {
+            state.facility_list(T::LIST_TYPE, request_id, [&facility.data[..]]);
+        }
+    }
+
+    /// Serve the data of a facility, such as an airport, which is sent for
+    /// facility data requests of its ICAO identifier. Requests fail with
+    /// `DATA_ERROR` if a block has no value for a field of the definition.
+    pub fn add_facility_data(&self, icao: &str, region: &str, block: MockFacilityBlock) {
+        self.state
+            .borrow_mut()
+            .facility_data
+            .push((icao.to_string(), region.to_string(), block));
+    }
+
+    /// Add an input event to the user's aircraft, which is enumerated with the
+    /// types of its parameters in `params`, such as `"FLOAT64"`. Its hash is
+    /// made up by the server, and its type is that of `value`.
+    pub fn add_input_event(&self, name: &str, value: impl Into<InputEventValue>, params: &str) {
+        self.state
+            .borrow_mut()
+            .aircraft_input_events
+            .push(MockInputEvent {
+                name: name.to_string(),
+                hash: input_event_hash(name),
+                value: value.into(),
+                params: params.to_string(),
+            });
+    }
+
+    /// Change the value of an input event, as if the pilot had moved the
+    /// control, notifying the client if it subscribed to it.
+    pub fn set_input_event(&self, name: &str, value: impl Into<InputEventValue>) {
+        self.state
+            .borrow_mut()
+            .change_input_event(input_event_hash(name), value.into());
+    }
+
+    /// Get the value of an input event, as last set by the test or by the
+    /// client.
+    pub fn input_event(&self, name: &str) -> Option<InputEventValue> {
+        self.state
+            .borrow()
+            .input_event(input_event_hash(name))
+            .map(|event| event.value.clone())
+    }
+
+    /// Whether the client is subscribed to the input event `name`.
+    pub fn input_event_subscribed(&self, name: &str) -> bool {
+        self.state
+            .borrow()
+            .input_event_subscriptions
+            .contains(&input_event_hash(name))
+    }
+
+    /// The flight plan an AI aircraft was created with or given, if it is
+    /// still in the sim.
+    pub fn ai_flight_plan(&self, object_id: ObjectId) -> Option<String> {
+        self.state
+            .borrow()
+            .ai_flight_plans
+            .get(&object_id.0)
+            .cloned()
+    }
+
+    /// Whether the sim's control of an AI object was released with
+    /// `ai_release_control`.
+    pub fn ai_released(&self, object_id: ObjectId) -> bool {
+        self.state.borrow().ai_released.contains(&object_id.0)
+    }
+
+    /// Tell the client that the sim is shutting down.
+    pub fn quit(&self) {
+        let message = Message::new(sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_QUIT);
+        self.state.borrow_mut().outbox.push(message.finish());
+    }
+
+    /// All client events transmitted by the client so far.
+    pub fn transmitted_events(&self) -> Vec<TransmittedEvent> {
+        self.state.borrow().transmitted_events.clone()
+    }
+}
+
+/// The client side of a [`MockServer`].
+pub struct MockBackend {
+    state: Rc<RefCell<State>>,
+}
+
+impl std::fmt::Debug for MockBackend {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("MockBackend").finish()
+    }
+}
+
+impl Backend for MockBackend {
+    fn call(&self, call: Call<'_>) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        state.packet_id += 1;
+        if state.failures.is_empty() {
+            state.handle(call);
+        } else {
+            let (exception, index) = state.failures.remove(0);
+            state.exception(exception, index);
+        }
+        Ok(())
+    }
+
+    fn last_sent_packet_id(&self) -> Result<sys::DWORD> {
+        Ok(self.state.borrow().packet_id)
+    }
+
+    unsafe fn call_dispatch(
+        &self,
+        dispatch: sys::DispatchProc,
+        context: *mut std::ffi::c_void,
+    ) -> Result<()> {
+        let messages = {
+            let mut state = self.state.borrow_mut();
+            state.frame();
+            std::mem::take(&mut state.outbox)
+        };
+        if let Some(dispatch) = dispatch {
+            for mut message in messages {
+                unsafe {
+                    dispatch(
+                        message.as_mut_ptr() as *mut sys::SIMCONNECT_RECV,
+                        message.len() as sys::DWORD,
+                        context,
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use std::borrow::{Borrow, ToOwned};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// A message received from SimConnect. It dereferences to the underlying
+/// `SIMCONNECT_RECV_*` struct, and keeps the bytes of the whole packet,
+/// including any trailing payload.
+#[repr(transparent)]
+pub struct Recv<T> {
+    phantom: PhantomData<T>,
+    bytes: [u8],
+}
+
+impl<T> Recv<T> {
+    /// View `bytes` as a message of type `T`, if there are enough of them.
+    pub(crate) fn new(bytes: &[u8]) -> Option<&Self> {
+        // The SimConnect structs are packed, so any address is suitably aligned.
+        const { assert!(std::mem::align_of::<T>() == 1) };
+        if bytes.len() < std::mem::size_of::<T>() {
+            return None;
+        }
+        Some(unsafe { &*(bytes as *const [u8] as *const Recv<T>) })
+    }
+
+    /// View `bytes` as a message of type `T` which ends with an array declared
+    /// with one element of `element` bytes, such as a facilities list. An
+    /// empty page leaves the message shorter than `T`, so it is copied into
+    /// `padded` and zeroed out to the size of `T` instead.
+    pub(crate) fn new_list<'a>(
+        bytes: &'a [u8],
+        element: usize,
+        padded: &'a mut Vec<u8>,
+    ) -> Option<&'a Self> {
+        if bytes.len() >= std::mem::size_of::<T>() {
+            return Self::new(bytes);
+        }
+        if bytes.len() + element < std::mem::size_of::<T>() {
+            return None;
+        }
+        padded.clear();
+        padded.extend_from_slice(bytes);
+        padded.resize(std::mem::size_of::<T>(), 0);
+        let padded: &'a Vec<u8> = padded;
+        Self::new(padded)
+    }
+
+    /// The bytes of the whole packet.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<T> Deref for Recv<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*(self.bytes.as_ptr() as *const T) }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Recv<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&**self, fmt)
+    }
+}
+
+impl<T> ToOwned for Recv<T> {
+    type Owned = OwnedRecv<T>;
+
+    fn to_owned(&self) -> OwnedRecv<T> {
+        OwnedRecv {
+            phantom: PhantomData,
+            bytes: self.bytes.into(),
+        }
+    }
+}
+
+/// A message copied out of SimConnect's dispatch buffer, which can be stored,
+/// queued, or held across awaits.
+pub struct OwnedRecv<T> {
+    phantom: PhantomData<T>,
+    bytes: Box<[u8]>,
+}
+
+impl<T> Deref for OwnedRecv<T> {
+    type Target = Recv<T>;
+
+    fn deref(&self) -> &Recv<T> {
+        // The length was checked when the borrowed message was created.
+        unsafe { &*(&*self.bytes as *const [u8] as *const Recv<T>) }
+    }
+}
+
+impl<T> Borrow<Recv<T>> for OwnedRecv<T> {
+    fn borrow(&self) -> &Recv<T> {
+        self
+    }
+}
+
+impl<T> Clone for OwnedRecv<T> {
+    fn clone(&self) -> Self {
+        (**self).to_owned()
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for OwnedRecv<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&**self, fmt)
+    }
+}
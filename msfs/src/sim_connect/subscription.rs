@@ -0,0 +1,833 @@
+use super::data::{DecodeError, take};
+use super::dynamic::DataType;
+use super::{
+    Call, DataDefinition, DefineId, Exception, ObjectId, OwnedSimConnectRecv, RequestHandle,
+    RequestId, SimConnectRecv,
+};
+use crate::sys;
+use futures::channel::mpsc;
+use futures::stream::{FusedStream, Stream};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Request IDs allocated by `SimConnect` start here, to stay clear of those
+/// chosen by hand.
+const FIRST_REQUEST_ID: sys::DWORD = 0x8000_0000;
+
+struct Route {
+    tx: mpsc::UnboundedSender<OwnedSimConnectRecv>,
+    stop: Option<Call<'static>>,
+    deadline: Option<Instant>,
+    repeat: Option<Repeat>,
+    /// The packet whose exceptions are routed here.
+    packet_id: Option<sys::DWORD>,
+}
+
+/// A request which is sent again every `interval`, as made by `method`.
+struct Repeat {
+    method: &'static str,
+    call: Call<'static>,
+    interval: Duration,
+    due: Instant,
+}
+
+/// Messages for requests made on behalf of a handle, such as a
+/// [`Subscription`], are sent to that handle instead of the callback.
+pub(super) struct Routes {
+    next_request_id: sys::DWORD,
+    routes: HashMap<sys::DWORD, Route>,
+    /// System events sent to the handle of a request, with the call which
+    /// unsubscribes from them.
+    events: HashMap<sys::SIMCONNECT_CLIENT_EVENT_ID, (sys::DWORD, Call<'static>)>,
+    /// Input events sent to the handles of every request subscribed to them,
+    /// by hash.
+    input_events: HashMap<sys::UINT64, Vec<sys::DWORD>>,
+    /// Requests for the parameters of input events, which carry no request
+    /// ID, by hash and oldest first.
+    input_event_params: HashMap<sys::UINT64, VecDeque<sys::DWORD>>,
+    /// The request ID of the subscription to each type of facility, of which
+    /// SimConnect only keeps one.
+    facility_subscriptions: HashMap<sys::SIMCONNECT_FACILITY_LIST_TYPE, sys::DWORD>,
+    /// The calls which stop the requests of dropped [`RequestHandle`]s.
+    dropped: (
+        mpsc::UnboundedSender<Call<'static>>,
+        mpsc::UnboundedReceiver<Call<'static>>,
+    ),
+}
+
+impl Default for Routes {
+    fn default() -> Self {
+        Routes {
+            next_request_id: 0,
+            routes: HashMap::new(),
+            events: HashMap::new(),
+            input_events: HashMap::new(),
+            input_event_params: HashMap::new(),
+            facility_subscriptions: HashMap::new(),
+            dropped: mpsc::unbounded(),
+        }
+    }
+}
+
+impl Routes {
+    pub(super) fn allocate(&mut self) -> sys::DWORD {
+        let request_id = FIRST_REQUEST_ID + self.next_request_id;
+        self.next_request_id += 1;
+        request_id
+    }
+
+    /// Route the messages for `request_id` to the returned receiver. `stop`
+    /// is sent once the receiver is dropped. After `deadline`, the route is
+    /// removed, which ends the receiver.
+    pub(super) fn insert(
+        &mut self,
+        request_id: sys::DWORD,
+        stop: Option<Call<'static>>,
+        deadline: Option<Instant>,
+    ) -> mpsc::UnboundedReceiver<OwnedSimConnectRecv> {
+        let (tx, rx) = mpsc::unbounded();
+        self.routes.insert(
+            request_id,
+            Route {
+                tx,
+                stop,
+                deadline,
+                repeat: None,
+                packet_id: None,
+            },
+        );
+        rx
+    }
+
+    /// Send `call` again every `interval` while the route for `request_id` is
+    /// open.
+    pub(super) fn repeat(
+        &mut self,
+        request_id: sys::DWORD,
+        method: &'static str,
+        call: Call<'static>,
+        interval: Duration,
+    ) {
+        if let Some(route) = self.routes.get_mut(&request_id) {
+            route.repeat = Some(Repeat {
+                method,
+                call,
+                interval,
+                due: Instant::now() + interval,
+            });
+        }
+    }
+
+    /// Send the messages for `request_id` to the handle of `target` as well,
+    /// in the order they are received.
+    pub(super) fn alias(&mut self, request_id: sys::DWORD, target: sys::DWORD) {
+        if let Some(tx) = self.routes.get(&target).map(|route| route.tx.clone()) {
+            self.routes.insert(
+                request_id,
+                Route {
+                    tx,
+                    stop: None,
+                    deadline: None,
+                    repeat: None,
+                    packet_id: None,
+                },
+            );
+        }
+    }
+
+    /// Send the exceptions raised by the packet `packet_id` to the handle of
+    /// `request_id`.
+    pub(super) fn route_exceptions(&mut self, request_id: sys::DWORD, packet_id: sys::DWORD) {
+        if let Some(route) = self.routes.get_mut(&request_id) {
+            route.packet_id = Some(packet_id);
+        }
+    }
+
+    /// The sender of the calls which stop the requests of dropped
+    /// [`RequestHandle`]s.
+    pub(super) fn dropped(&self) -> mpsc::UnboundedSender<Call<'static>> {
+        self.dropped.0.clone()
+    }
+
+    /// A handle for a request whose messages go to the callback, which sends
+    /// `stop` once it is dropped.
+    pub(super) fn handle<I: Copy + std::fmt::Display>(
+        &self,
+        id: I,
+        stop: Call<'static>,
+    ) -> RequestHandle<I> {
+        RequestHandle::new(id, stop, self.dropped.0.clone())
+    }
+
+    /// Send the system event `event_id` to the handle of `request_id`.
+    /// `unsubscribe` is sent once the handle is dropped.
+    pub(super) fn insert_event(
+        &mut self,
+        event_id: sys::SIMCONNECT_CLIENT_EVENT_ID,
+        request_id: sys::DWORD,
+        unsubscribe: Call<'static>,
+    ) {
+        self.events.insert(event_id, (request_id, unsubscribe));
+    }
+
+    /// Send the input event `hash` to the handle of `request_id`, returning
+    /// whether it is the first handle for it, so that it has to be
+    /// subscribed to.
+    pub(super) fn insert_input_event(&mut self, hash: sys::UINT64, request_id: sys::DWORD) -> bool {
+        let request_ids = self.input_events.entry(hash).or_default();
+        request_ids.push(request_id);
+        request_ids.len() == 1
+    }
+
+    /// Send the next parameters received for the input event `hash` to the
+    /// handle of `request_id`.
+    pub(super) fn insert_input_event_params(&mut self, hash: sys::UINT64, request_id: sys::DWORD) {
+        self.input_event_params
+            .entry(hash)
+            .or_default()
+            .push_back(request_id);
+    }
+
+    /// Make `request_id` the subscription to facilities of `list_type`,
+    /// returning false if another one is still open. One whose handle was
+    /// dropped is removed without being stopped, as unsubscribing from the
+    /// type would end the new subscription too.
+    pub(super) fn insert_facility_subscription(
+        &mut self,
+        list_type: sys::SIMCONNECT_FACILITY_LIST_TYPE,
+        request_id: sys::DWORD,
+    ) -> bool {
+        if let Some(previous) = self.facility_subscriptions.get(&list_type)
+            && let Some(route) = self.routes.get(previous)
+        {
+            if !route.tx.is_closed() {
+                return false;
+            }
+            self.routes.remove(previous);
+        }
+        self.facility_subscriptions.insert(list_type, request_id);
+        true
+    }
+
+    /// Remove the routes whose deadline has passed, returning whether there
+    /// were any.
+    pub(super) fn expire(&mut self, now: Instant) -> bool {
+        let before = self.routes.len();
+        self.routes
+            .retain(|_, route| route.deadline.is_none_or(|deadline| deadline > now));
+        self.routes.len() != before
+    }
+
+    /// Route `recv` to its handle, if it has one.
+    pub(super) fn route<'a>(&mut self, recv: SimConnectRecv<'a>) -> Option<SimConnectRecv<'a>> {
+        if let SimConnectRecv::SubscribeInputEvent(event) = &recv
+            && let Some(request_ids) = self.input_events.get(&{ event.Hash })
+        {
+            let recv = recv.into_owned();
+            for request_id in request_ids {
+                if let Some(route) = self.routes.get(request_id) {
+                    let _ = route.tx.unbounded_send(recv.clone());
+                }
+            }
+            return None;
+        }
+        let event_id = match &recv {
+            SimConnectRecv::Event(event) => Some(event.uEventID),
+            SimConnectRecv::EventFilename(event) => Some(event._base.uEventID),
+            SimConnectRecv::EventFrame(event) => Some(event._base.uEventID),
+            SimConnectRecv::EventObjectAddRemove(event) => Some(event._base.uEventID),
+            SimConnectRecv::EventMultiplayerServerStarted(event) => Some(event._base.uEventID),
+            SimConnectRecv::EventMultiplayerClientStarted(event) => Some(event._base.uEventID),
+            SimConnectRecv::EventMultiplayerSessionEnded(event) => Some(event._base.uEventID),
+            SimConnectRecv::EventRaceEnd(event) => Some(event._base.uEventID),
+            SimConnectRecv::EventRaceLap(event) => Some(event._base.uEventID),
+            _ => None,
+        };
+        let request_id = match (&recv, event_id) {
+            (_, Some(event_id)) => match self.events.get(&event_id) {
+                Some(&(request_id, _)) => {
+                    // An object appearing or disappearing makes a repeated
+                    // request due at once.
+                    if let Some(repeat) = self
+                        .routes
+                        .get_mut(&request_id)
+                        .and_then(|route| route.repeat.as_mut())
+                    {
+                        repeat.due = Instant::now();
+                    }
+                    request_id
+                }
+                None => return Some(recv),
+            },
+            (SimConnectRecv::SimObjectData(data), None) => data.dwRequestID,
+            (SimConnectRecv::ClientData(data), None) => data._base.dwRequestID,
+            (SimConnectRecv::SystemState(state), None) => state.dwRequestID,
+            (SimConnectRecv::AssignedObjectId(assigned), None) => assigned.dwRequestID,
+            (SimConnectRecv::AirportList(list), None) => list._base.dwRequestID,
+            (SimConnectRecv::VorList(list), None) => list._base.dwRequestID,
+            (SimConnectRecv::NdbList(list), None) => list._base.dwRequestID,
+            (SimConnectRecv::WaypointList(list), None) => list._base.dwRequestID,
+            (SimConnectRecv::FacilityData(data), None) => data.UserRequestId,
+            (SimConnectRecv::FacilityDataEnd(end), None) => end.RequestId,
+            (SimConnectRecv::EnumerateInputEvents(list), None) => list._base.dwRequestID,
+            (SimConnectRecv::GetInputEvent(event), None) => event.dwRequestID,
+            (SimConnectRecv::EnumerateInputEventParams(params), None) => {
+                // Requests whose handles were dropped are skipped.
+                let requests = self.input_event_params.get_mut(&{ params.Hash });
+                match requests.and_then(|requests| {
+                    std::iter::from_fn(|| requests.pop_front())
+                        .find(|request_id| self.routes.contains_key(request_id))
+                }) {
+                    Some(request_id) => request_id,
+                    None => return Some(recv),
+                }
+            }
+            (SimConnectRecv::Exception(exception), None) => {
+                let send_id = exception.dwSendID;
+                match self
+                    .routes
+                    .iter()
+                    .find(|(_, route)| route.packet_id == Some(send_id))
+                {
+                    Some((&request_id, _)) => request_id,
+                    None => return Some(recv),
+                }
+            }
+            _ => return Some(recv),
+        };
+        match self.routes.get(&request_id) {
+            Some(route) => {
+                // The handle may have been dropped since the last dispatch, in
+                // which case the message is discarded.
+                let _ = route.tx.unbounded_send(recv.into_owned());
+                None
+            }
+            None => Some(recv),
+        }
+    }
+
+    /// Remove the routes whose handles were dropped, returning the calls which
+    /// stop their requests along with those of dropped [`RequestHandle`]s.
+    pub(super) fn take_closed(&mut self) -> Vec<Call<'static>> {
+        let closed = self
+            .routes
+            .iter()
+            .filter(|(_, route)| route.tx.is_closed())
+            .map(|(request_id, _)| *request_id)
+            .collect::<Vec<_>>();
+        let mut stops = closed
+            .into_iter()
+            .filter_map(|request_id| self.routes.remove(&request_id))
+            .filter_map(|route| route.stop)
+            .collect::<Vec<_>>();
+
+        let unsubscribed = self
+            .events
+            .iter()
+            .filter(|(_, (request_id, _))| !self.routes.contains_key(request_id))
+            .map(|(event_id, _)| *event_id)
+            .collect::<Vec<_>>();
+        stops.extend(
+            unsubscribed
+                .into_iter()
+                .filter_map(|event_id| self.events.remove(&event_id))
+                .map(|(_, unsubscribe)| unsubscribe),
+        );
+
+        let routes = &self.routes;
+        self.input_event_params.retain(|_, requests| {
+            requests.retain(|request_id| routes.contains_key(request_id));
+            !requests.is_empty()
+        });
+        self.input_events.retain(|&hash, request_ids| {
+            request_ids.retain(|request_id| routes.contains_key(request_id));
+            if request_ids.is_empty() {
+                stops.push(Call::UnsubscribeInputEvent { hash });
+            }
+            !request_ids.is_empty()
+        });
+        while let Ok(stop) = self.dropped.1.try_recv() {
+            stops.push(stop);
+        }
+        stops
+    }
+
+    /// The repeated requests which are due, with the methods which made them.
+    pub(super) fn take_due(&mut self, now: Instant) -> Vec<(&'static str, Call<'static>)> {
+        self.routes
+            .values_mut()
+            .filter(|route| !route.tx.is_closed())
+            .filter_map(|route| route.repeat.as_mut())
+            .filter(|repeat| repeat.due <= now)
+            .map(|repeat| {
+                repeat.due = now + repeat.interval;
+                (repeat.method, repeat.call.clone())
+            })
+            .collect()
+    }
+}
+
+/// A stream of the values of a data definition on a SimObject, created by
+/// [`SimConnect::subscribe`](super::SimConnect::subscribe). Dropping it stops
+/// the request. Values which fail to decode are yielded as errors, and the
+/// stream carries on with the next value.
+pub struct Subscription<T: DataDefinition> {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    define_id: DefineId,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    phantom: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: DataDefinition> Subscription<T> {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        define_id: DefineId,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    ) -> Self {
+        Subscription {
+            rx,
+            define_id,
+            request_id,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// The request ID allocated for this subscription.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+
+    /// Take the most recent value received, discarding any older ones, without
+    /// waiting for a new one.
+    pub fn latest(&mut self) -> Option<Result<T, DecodeError>> {
+        let mut latest = None;
+        while let Ok(recv) = self.rx.try_recv() {
+            if let Some(value) = self.decode(recv) {
+                latest = Some(value);
+            }
+        }
+        latest
+    }
+
+    fn decode(&self, recv: OwnedSimConnectRecv) -> Option<Result<T, DecodeError>> {
+        match recv {
+            OwnedSimConnectRecv::SimObjectData(data) => Some(
+                super::check_header(data.bytes(), &data, self.define_id, T::DATUM_COUNT)
+                    .and_then(|()| T::decode(data.data())),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl<T: DataDefinition> Stream for Subscription<T> {
+    type Item = Result<T, DecodeError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(recv)) => {
+                    if let Some(value) = self.decode(recv) {
+                        return Poll::Ready(Some(value));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T: DataDefinition> FusedStream for Subscription<T> {
+    fn is_terminated(&self) -> bool {
+        self.rx.is_terminated()
+    }
+}
+
+impl<T: DataDefinition> std::fmt::Debug for Subscription<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Subscription")
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
+
+/// An error collecting a [`Snapshot`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    /// Not every object was received before the timeout, or before the session
+    /// was closed. `expected` is `None` if nothing was received at all.
+    Timeout {
+        received: usize,
+        expected: Option<usize>,
+    },
+    /// The data of an object could not be decoded.
+    Decode(DecodeError),
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SnapshotError::Timeout {
+                received,
+                expected: Some(expected),
+            } => write!(
+                fmt,
+                "timed out after receiving {received} of {expected} objects"
+            ),
+            SnapshotError::Timeout { expected: None, .. } => {
+                fmt.write_str("timed out before receiving any objects")
+            }
+            SnapshotError::Decode(e) => write!(fmt, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<DecodeError> for SnapshotError {
+    fn from(e: DecodeError) -> Self {
+        SnapshotError::Decode(e)
+    }
+}
+
+/// Why a request made on behalf of a future failed.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum RequestError {
+    /// The sim raised an exception for the request.
+    Exception(Exception),
+    /// The session was closed before the request was answered.
+    Closed,
+    /// The answer couldn't be decoded.
+    Decode(DecodeError),
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RequestError::Exception(e) => write!(fmt, "{e}"),
+            RequestError::Closed => fmt.write_str("the session was closed"),
+            RequestError::Decode(e) => write!(fmt, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+impl From<Exception> for RequestError {
+    fn from(e: Exception) -> Self {
+        RequestError::Exception(e)
+    }
+}
+
+impl From<DecodeError> for RequestError {
+    fn from(e: DecodeError) -> Self {
+        RequestError::Decode(e)
+    }
+}
+
+/// The values of a data definition on every SimObject of a type, created by
+/// [`SimConnect::snapshot_sim_objects`](super::SimConnect::snapshot_sim_objects).
+/// It resolves once every object has been received, or with an error once the
+/// timeout passes.
+pub struct Snapshot<T: DataDefinition> {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    define_id: DefineId,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    /// `dwoutof`, once the first object has been received.
+    expected: Option<usize>,
+    /// Each object received so far, with its `dwentrynumber`.
+    entries: Vec<(sys::DWORD, ObjectId, T)>,
+}
+
+impl<T: DataDefinition> Snapshot<T> {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        define_id: DefineId,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    ) -> Self {
+        Snapshot {
+            rx,
+            define_id,
+            request_id,
+            expected: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// The request ID allocated for this snapshot.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+
+    fn add(&mut self, recv: OwnedSimConnectRecv) -> Result<(), DecodeError> {
+        let OwnedSimConnectRecv::SimObjectData(data) = recv else {
+            return Ok(());
+        };
+        // With no objects, a single message is sent with a `dwoutof` of 0 and
+        // no data, so only its definition can be checked.
+        if data.dwoutof == 0 {
+            super::check_define_id(&data, self.define_id)?;
+            self.expected = Some(0);
+            return Ok(());
+        }
+        super::check_header(data.bytes(), &data, self.define_id, T::DATUM_COUNT)?;
+        let value = T::decode(data.data())?;
+        self.expected = Some(data.dwoutof as usize);
+        self.entries
+            .push((data.dwentrynumber, data.object_id(), value));
+        Ok(())
+    }
+
+    fn is_complete(&self) -> bool {
+        self.expected == Some(self.entries.len())
+    }
+}
+
+// The values received are never pinned.
+impl<T: DataDefinition> Unpin for Snapshot<T> {}
+
+impl<T: DataDefinition> Future for Snapshot<T> {
+    type Output = Result<Vec<(ObjectId, T)>, SnapshotError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        while !self.is_complete() {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(recv)) => {
+                    if let Err(e) = self.add(recv) {
+                        return Poll::Ready(Err(e.into()));
+                    }
+                }
+                Poll::Ready(None) => {
+                    return Poll::Ready(Err(SnapshotError::Timeout {
+                        received: self.entries.len(),
+                        expected: self.expected,
+                    }));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        let mut entries = std::mem::take(&mut self.entries);
+        entries.sort_by_key(|(entry_number, _, _)| *entry_number);
+        Poll::Ready(Ok(entries
+            .into_iter()
+            .map(|(_, object_id, value)| (object_id, value))
+            .collect()))
+    }
+}
+
+impl<T: DataDefinition> std::fmt::Debug for Snapshot<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Snapshot")
+            .field("request_id", &self.request_id)
+            .field("received", &self.entries.len())
+            .field("expected", &self.expected)
+            .finish()
+    }
+}
+
+/// The fields of a data definition which changed, as reported by a
+/// [`TaggedSubscription`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeSet {
+    fields: &'static [(&'static str, usize)],
+    datums: Vec<bool>,
+}
+
+impl ChangeSet {
+    /// Whether any simvar of the field named `field` changed. Fields of tuple
+    /// structs are named by their index, such as `"0"`.
+    pub fn contains(&self, field: &str) -> bool {
+        self.fields().any(|name| name == field)
+    }
+
+    /// The names of the fields which changed, in order.
+    pub fn fields(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.fields
+            .iter()
+            .scan(0, |start, &(name, count)| {
+                let datums = *start..*start + count;
+                *start += count;
+                Some((name, datums))
+            })
+            .filter(|(_, datums)| self.datums[datums.clone()].contains(&true))
+            .map(|(name, _)| name)
+    }
+
+    /// The indices of the simvars which changed, in the order they were added
+    /// to the definition. Fields which are arrays or nested definitions have
+    /// one simvar per element.
+    pub fn datums(&self) -> impl Iterator<Item = usize> + '_ {
+        self.datums
+            .iter()
+            .enumerate()
+            .filter(|(_, changed)| **changed)
+            .map(|(index, _)| index)
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        !self.datums.contains(&true)
+    }
+}
+
+/// A cached value of a data definition on a SimObject, kept up to date by a
+/// tagged request which only sends the simvars that changed. Created by
+/// [`SimConnect::subscribe_tagged`](super::SimConnect::subscribe_tagged), it is
+/// a stream of the fields which changed since the last item, or of the
+/// [`DecodeError`] for a message which couldn't be merged. Dropping it stops
+/// the request.
+pub struct TaggedSubscription<T: DataDefinition> {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    define_id: DefineId,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    data_types: Vec<DataType>,
+    /// The last bytes received for each simvar.
+    datums: Vec<Option<Vec<u8>>>,
+    /// The simvars which changed since the last change set was taken.
+    changed: Vec<bool>,
+    value: Option<T>,
+}
+
+impl<T: DataDefinition> TaggedSubscription<T> {
+    /// The type of each simvar of `T`, if they are all types which can be
+    /// received.
+    pub(super) fn data_types() -> Option<Vec<DataType>> {
+        T::definitions()
+            .into_iter()
+            .map(|(_, _, _, data_type)| DataType::from_sys(data_type))
+            .collect()
+    }
+
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        define_id: DefineId,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        data_types: Vec<DataType>,
+    ) -> Self {
+        TaggedSubscription {
+            rx,
+            define_id,
+            request_id,
+            datums: vec![None; data_types.len()],
+            changed: vec![false; data_types.len()],
+            data_types,
+            value: None,
+        }
+    }
+
+    /// The request ID allocated for this subscription.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+
+    /// The cached value, once every simvar has been received.
+    pub fn value(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    /// Merge every message received since the last call into the cached
+    /// value, without waiting for a new one, returning the fields which
+    /// changed. A message which can't be merged is returned as an error, and
+    /// any after it are left for the next call.
+    pub fn update(&mut self) -> Option<Result<ChangeSet, DecodeError>> {
+        while let Ok(recv) = self.rx.try_recv() {
+            if let Err(e) = self.merge(recv) {
+                return Some(Err(e));
+            }
+        }
+        self.take_changes().map(Ok)
+    }
+
+    /// Merge the (datum ID, value) pairs of a tagged message into the cached
+    /// value. A message which can't be parsed leaves it unchanged, and one
+    /// whose values can't be decoded into `T` leaves the last value in place,
+    /// with the changes reported once a later one can.
+    fn merge(&mut self, recv: OwnedSimConnectRecv) -> Result<(), DecodeError> {
+        let OwnedSimConnectRecv::SimObjectData(data) = recv else {
+            return Ok(());
+        };
+        super::check_define_id(&data, self.define_id)?;
+        let mut datums = self.datums.clone();
+        let mut changed = self.changed.clone();
+        let mut bytes = data.data();
+        for _ in 0..data.dwDefineCount {
+            let datum_id = sys::DWORD::from_ne_bytes(take(&mut bytes, 4)?.try_into().unwrap());
+            let index = datum_id as usize;
+            let data_type = *self
+                .data_types
+                .get(index)
+                .ok_or(DecodeError::UnknownDatum { datum_id })?;
+            let value = data_type.take(&mut bytes)?;
+            if datums[index].as_deref() != Some(value) {
+                datums[index] = Some(value.to_vec());
+                changed[index] = true;
+            }
+        }
+
+        self.datums = datums;
+        self.changed = changed;
+        if self.datums.iter().all(Option::is_some) {
+            let mut untagged = Vec::new();
+            for datum in self.datums.iter().flatten() {
+                untagged.extend_from_slice(datum);
+            }
+            self.value = Some(T::decode(&untagged)?);
+        }
+        Ok(())
+    }
+
+    fn take_changes(&mut self) -> Option<ChangeSet> {
+        if self.value.is_none() || !self.changed.contains(&true) {
+            return None;
+        }
+        let datums = std::mem::replace(&mut self.changed, vec![false; self.data_types.len()]);
+        Some(ChangeSet {
+            fields: T::FIELDS,
+            datums,
+        })
+    }
+}
+
+// The cached value is never pinned.
+impl<T: DataDefinition> Unpin for TaggedSubscription<T> {}
+
+impl<T: DataDefinition> Stream for TaggedSubscription<T> {
+    type Item = Result<ChangeSet, DecodeError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(recv)) => {
+                    if let Err(e) = self.merge(recv) {
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    if let Some(changes) = self.take_changes() {
+                        return Poll::Ready(Some(Ok(changes)));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T: DataDefinition> FusedStream for TaggedSubscription<T> {
+    fn is_terminated(&self) -> bool {
+        self.rx.is_terminated()
+    }
+}
+
+impl<T: DataDefinition> std::fmt::Debug for TaggedSubscription<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("TaggedSubscription")
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
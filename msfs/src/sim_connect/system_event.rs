@@ -0,0 +1,357 @@
+use super::{ObjectId, OwnedSimConnectRecv, SystemEventId};
+use crate::sys;
+use futures::channel::mpsc;
+use futures::stream::{FusedStream, Stream};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+macro_rules! system_event_kind {
+    ($( $(#[$meta:meta])* $Kind:ident = $name:literal, )*) => {
+        /// A system event which can be subscribed to with
+        /// [`SimConnect::system_events`](super::SimConnect::system_events).
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum SystemEventKind {
+            $( $(#[$meta])* $Kind, )*
+        }
+
+        impl SystemEventKind {
+            /// The name SimConnect knows the event by.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $( SystemEventKind::$Kind => $name, )*
+                }
+            }
+        }
+    };
+}
+
+system_event_kind! {
+    /// Every second, while the sim is running.
+    OneSec = "1sec",
+    /// Every four seconds, while the sim is running.
+    FourSec = "4sec",
+    /// Six times a second, while the sim is running.
+    SixHz = "6Hz",
+    /// When an aircraft is loaded, with the path of its `.AIR` file.
+    AircraftLoaded = "AircraftLoaded",
+    /// When the user's aircraft crashes.
+    Crashed = "Crashed",
+    /// When the crash cut-scene has ended.
+    CrashReset = "CrashReset",
+    /// When a mission action has been executed.
+    CustomMissionActionExecuted = "CustomMissionActionExecuted",
+    /// When a flight is loaded, with the path of its `.FLT` file.
+    FlightLoaded = "FlightLoaded",
+    /// When a flight is saved, with the path of its `.FLT` file.
+    FlightSaved = "FlightSaved",
+    /// When the user joins a multiplayer session as a client.
+    MultiplayerClientStarted = "MultiplayerClientStarted",
+    /// When the user starts hosting a multiplayer session.
+    MultiplayerServerStarted = "MultiplayerServerStarted",
+    /// When the multiplayer session the user is in ends.
+    MultiplayerSessionEnded = "MultiplayerSessionEnded",
+    /// When a flight plan is activated, with the path of its `.PLN` file.
+    FlightPlanActivated = "FlightPlanActivated",
+    /// When the active flight plan is deactivated.
+    FlightPlanDeactivated = "FlightPlanDeactivated",
+    /// Every visual frame, with the frame rate and simulation speed.
+    Frame = "Frame",
+    /// When an AI object is added to the sim.
+    ObjectAdded = "ObjectAdded",
+    /// When an AI object is removed from the sim.
+    ObjectRemoved = "ObjectRemoved",
+    /// When the sim is paused or unpaused, and once when subscribed with the
+    /// current state.
+    Pause = "Pause",
+    /// When the pause state changes, with the kind of pause.
+    PauseEx1 = "Pause_EX1",
+    /// When the sim is paused.
+    Paused = "Paused",
+    /// Every visual frame while the sim is paused, with the frame rate and
+    /// simulation speed.
+    PauseFrame = "PauseFrame",
+    /// When the user changes the position of their aircraft through a dialog.
+    PositionChanged = "PositionChanged",
+    /// When a racer finishes a race, with their result.
+    RaceEnd = "RaceEnd",
+    /// When a racer completes a lap, with their result so far.
+    RaceLap = "RaceLap",
+    /// When the sim starts or stops running, and once when subscribed with the
+    /// current state.
+    Sim = "Sim",
+    /// When the sim starts running.
+    SimStart = "SimStart",
+    /// When the sim stops running.
+    SimStop = "SimStop",
+    /// When the master sound switch is turned on or off, and once when
+    /// subscribed with the current state.
+    Sound = "Sound",
+    /// When the user has been unpaused.
+    Unpaused = "Unpaused",
+    /// When the user's aircraft view changes, with the kind of view.
+    View = "View",
+    /// When the weather mode is changed.
+    WeatherModeChanged = "WeatherModeChanged",
+}
+
+/// The kind of pause the sim is in, sent with [`SystemEvent::PauseEx1`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PauseFlags(pub sys::DWORD);
+
+impl PauseFlags {
+    /// The sim isn't paused.
+    pub const OFF: PauseFlags = PauseFlags(sys::PAUSE_STATE_FLAG_OFF);
+    /// The sim is fully paused.
+    pub const PAUSE: PauseFlags = PauseFlags(sys::PAUSE_STATE_FLAG_PAUSE);
+    /// The sim is fully paused, but sound keeps playing.
+    pub const PAUSE_WITH_SOUND: PauseFlags = PauseFlags(sys::PAUSE_STATE_FLAG_PAUSE_WITH_SOUND);
+    /// Active pause, in which the camera can still be moved.
+    pub const ACTIVE_PAUSE: PauseFlags = PauseFlags(sys::PAUSE_STATE_FLAG_ACTIVE_PAUSE);
+    /// The simulation is paused, but not the sim itself.
+    pub const SIM_PAUSE: PauseFlags = PauseFlags(sys::PAUSE_STATE_FLAG_SIM_PAUSE);
+
+    /// Whether any of `flags` are set.
+    pub fn contains(self, flags: PauseFlags) -> bool {
+        self.0 & flags.0 != 0
+    }
+
+    /// Whether the sim is paused in any way.
+    pub fn is_paused(self) -> bool {
+        self != PauseFlags::OFF
+    }
+}
+
+/// The result of a racer in a race, sent with [`SystemEvent::RaceEnd`] and
+/// [`SystemEvent::RaceLap`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaceResult {
+    /// The number of racers in the race.
+    pub racers: u32,
+    /// The GUID of the mission the race is part of, as laid out in memory.
+    pub mission_guid: [u8; 16],
+    pub player_name: String,
+    pub session_type: String,
+    pub aircraft: String,
+    pub player_role: String,
+    /// The racer's total time, in seconds.
+    pub total_time: f64,
+    /// The penalty time added to the total, in seconds.
+    pub penalty_time: f64,
+    pub disqualified: bool,
+}
+
+/// A system event, decoded from the message it was sent with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemEvent {
+    OneSec,
+    FourSec,
+    SixHz,
+    AircraftLoaded(PathBuf),
+    Crashed,
+    CrashReset,
+    CustomMissionActionExecuted,
+    FlightLoaded(PathBuf),
+    FlightSaved(PathBuf),
+    FlightPlanActivated(PathBuf),
+    FlightPlanDeactivated,
+    MultiplayerClientStarted,
+    MultiplayerServerStarted,
+    MultiplayerSessionEnded,
+    Frame {
+        fps: f32,
+        sim_speed: f32,
+    },
+    ObjectAdded {
+        id: ObjectId,
+        kind: sys::SIMCONNECT_SIMOBJECT_TYPE,
+    },
+    ObjectRemoved {
+        id: ObjectId,
+        kind: sys::SIMCONNECT_SIMOBJECT_TYPE,
+    },
+    /// Sent for `Pause`, `Paused` and `Unpaused`.
+    Paused(bool),
+    PauseEx1(PauseFlags),
+    PauseFrame {
+        fps: f32,
+        sim_speed: f32,
+    },
+    PositionChanged,
+    RaceEnd {
+        /// The index of the racer.
+        racer: u32,
+        result: RaceResult,
+    },
+    RaceLap {
+        /// The index of the lap, from 0.
+        lap: u32,
+        result: RaceResult,
+    },
+    /// Sent for `Sim`, `SimStart` and `SimStop`.
+    Running(bool),
+    /// Whether the master sound switch is on.
+    Sound(bool),
+    /// The `SIMCONNECT_VIEW_SYSTEM_EVENT_DATA_*` flags of the view.
+    View(sys::DWORD),
+    WeatherModeChanged,
+}
+
+impl SystemEvent {
+    /// Decode a message sent for an event of `kind`, if it is of the form
+    /// that kind of event is sent as.
+    fn decode(kind: SystemEventKind, recv: &OwnedSimConnectRecv) -> Option<SystemEvent> {
+        use SystemEventKind as K;
+        let event = match recv {
+            OwnedSimConnectRecv::Event(event) => {
+                let data = event.data();
+                match kind {
+                    K::OneSec => SystemEvent::OneSec,
+                    K::FourSec => SystemEvent::FourSec,
+                    K::SixHz => SystemEvent::SixHz,
+                    K::Crashed => SystemEvent::Crashed,
+                    K::CrashReset => SystemEvent::CrashReset,
+                    K::CustomMissionActionExecuted => SystemEvent::CustomMissionActionExecuted,
+                    K::FlightPlanDeactivated => SystemEvent::FlightPlanDeactivated,
+                    K::Pause => SystemEvent::Paused(data != 0),
+                    K::PauseEx1 => SystemEvent::PauseEx1(PauseFlags(data)),
+                    K::Paused => SystemEvent::Paused(true),
+                    K::Unpaused => SystemEvent::Paused(false),
+                    K::PositionChanged => SystemEvent::PositionChanged,
+                    K::Sim => SystemEvent::Running(data != 0),
+                    K::SimStart => SystemEvent::Running(true),
+                    K::SimStop => SystemEvent::Running(false),
+                    K::Sound => SystemEvent::Sound(
+                        data & sys::SIMCONNECT_SOUND_SYSTEM_EVENT_DATA_MASTER != 0,
+                    ),
+                    K::View => SystemEvent::View(data),
+                    K::WeatherModeChanged => SystemEvent::WeatherModeChanged,
+                    _ => return None,
+                }
+            }
+            OwnedSimConnectRecv::EventFilename(event) => {
+                let file_name = PathBuf::from(event.file_name().into_owned());
+                match kind {
+                    K::AircraftLoaded => SystemEvent::AircraftLoaded(file_name),
+                    K::FlightLoaded => SystemEvent::FlightLoaded(file_name),
+                    K::FlightSaved => SystemEvent::FlightSaved(file_name),
+                    K::FlightPlanActivated => SystemEvent::FlightPlanActivated(file_name),
+                    _ => return None,
+                }
+            }
+            OwnedSimConnectRecv::EventFrame(event) => {
+                let (fps, sim_speed) = (event.frame_rate(), event.sim_speed());
+                match kind {
+                    K::Frame => SystemEvent::Frame { fps, sim_speed },
+                    K::PauseFrame => SystemEvent::PauseFrame { fps, sim_speed },
+                    _ => return None,
+                }
+            }
+            OwnedSimConnectRecv::EventObjectAddRemove(event) => {
+                let (id, object_type) = (event.object_id(), event.object_type());
+                match kind {
+                    K::ObjectAdded => SystemEvent::ObjectAdded {
+                        id,
+                        kind: object_type,
+                    },
+                    K::ObjectRemoved => SystemEvent::ObjectRemoved {
+                        id,
+                        kind: object_type,
+                    },
+                    _ => return None,
+                }
+            }
+            OwnedSimConnectRecv::EventMultiplayerClientStarted(_)
+                if kind == K::MultiplayerClientStarted =>
+            {
+                SystemEvent::MultiplayerClientStarted
+            }
+            OwnedSimConnectRecv::EventMultiplayerServerStarted(_)
+                if kind == K::MultiplayerServerStarted =>
+            {
+                SystemEvent::MultiplayerServerStarted
+            }
+            OwnedSimConnectRecv::EventMultiplayerSessionEnded(_)
+                if kind == K::MultiplayerSessionEnded =>
+            {
+                SystemEvent::MultiplayerSessionEnded
+            }
+            OwnedSimConnectRecv::EventRaceEnd(event) if kind == K::RaceEnd => {
+                SystemEvent::RaceEnd {
+                    racer: event.racer_number(),
+                    result: event.result(),
+                }
+            }
+            OwnedSimConnectRecv::EventRaceLap(event) if kind == K::RaceLap => {
+                SystemEvent::RaceLap {
+                    lap: event.lap_index(),
+                    result: event.result(),
+                }
+            }
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+/// A stream of the events of a system event subscription, created by
+/// [`SimConnect::system_events`](super::SimConnect::system_events). Dropping
+/// it unsubscribes from the event.
+pub struct SystemEvents {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    kind: SystemEventKind,
+    event_id: SystemEventId,
+}
+
+impl SystemEvents {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        kind: SystemEventKind,
+        event_id: SystemEventId,
+    ) -> Self {
+        SystemEvents { rx, kind, event_id }
+    }
+
+    /// The kind of event subscribed to.
+    pub fn kind(&self) -> SystemEventKind {
+        self.kind
+    }
+
+    /// The event ID allocated for this subscription.
+    pub fn event_id(&self) -> SystemEventId {
+        self.event_id
+    }
+}
+
+impl Stream for SystemEvents {
+    type Item = SystemEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<SystemEvent>> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(recv)) => {
+                    if let Some(event) = SystemEvent::decode(self.kind, &recv) {
+                        return Poll::Ready(Some(event));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl FusedStream for SystemEvents {
+    fn is_terminated(&self) -> bool {
+        self.rx.is_terminated()
+    }
+}
+
+impl std::fmt::Debug for SystemEvents {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("SystemEvents")
+            .field("kind", &self.kind)
+            .field("event_id", &self.event_id)
+            .finish()
+    }
+}
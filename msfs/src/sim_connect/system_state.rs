@@ -0,0 +1,123 @@
+use super::{Exception, OwnedSimConnectRecv, RequestError, RequestId, SentCall};
+use crate::sys;
+use futures::channel::mpsc;
+use futures::stream::Stream;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A piece of the sim's state, which can be asked for with
+/// [`SimConnect::system_state`](super::SimConnect::system_state).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SystemStateQuery {
+    /// The path of the `.AIR` file of the user's aircraft.
+    AircraftLoaded,
+    /// Whether the sim is in dialog mode.
+    DialogMode,
+    /// The path of the `.FLT` file of the current flight.
+    FlightLoaded,
+    /// The path of the `.PLN` file of the active flight plan.
+    FlightPlan,
+    /// Whether the sim is running, rather than in a menu.
+    Sim,
+}
+
+impl SystemStateQuery {
+    /// The name SimConnect knows the state by.
+    pub fn name(self) -> &'static str {
+        match self {
+            SystemStateQuery::AircraftLoaded => "AircraftLoaded",
+            SystemStateQuery::DialogMode => "DialogMode",
+            SystemStateQuery::FlightLoaded => "FlightLoaded",
+            SystemStateQuery::FlightPlan => "FlightPlan",
+            SystemStateQuery::Sim => "Sim",
+        }
+    }
+}
+
+/// The answer to a [`SystemStateQuery`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SystemState {
+    AircraftLoaded(PathBuf),
+    DialogMode(bool),
+    FlightLoaded(PathBuf),
+    /// The path is empty if no flight plan is active.
+    FlightPlan(PathBuf),
+    Running(bool),
+}
+
+impl SystemState {
+    fn decode(query: SystemStateQuery, state: &sys::SIMCONNECT_RECV_SYSTEM_STATE) -> Self {
+        let path = || PathBuf::from(state.string().into_owned());
+        match query {
+            SystemStateQuery::AircraftLoaded => SystemState::AircraftLoaded(path()),
+            SystemStateQuery::DialogMode => SystemState::DialogMode(state.integer() != 0),
+            SystemStateQuery::FlightLoaded => SystemState::FlightLoaded(path()),
+            SystemStateQuery::FlightPlan => SystemState::FlightPlan(path()),
+            SystemStateQuery::Sim => SystemState::Running(state.integer() != 0),
+        }
+    }
+}
+
+/// A request for a piece of the sim's state, created by
+/// [`SimConnect::system_state`](super::SimConnect::system_state). It resolves
+/// once the sim answers.
+pub struct SystemStateRequest {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    query: SystemStateQuery,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    /// The call which made the request, to describe its exceptions.
+    call: Option<SentCall>,
+}
+
+impl SystemStateRequest {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        query: SystemStateQuery,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        call: Option<SentCall>,
+    ) -> Self {
+        SystemStateRequest {
+            rx,
+            query,
+            request_id,
+            call,
+        }
+    }
+
+    /// The request ID allocated for this request.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+}
+
+impl Future for SystemStateRequest {
+    type Output = Result<SystemState, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(OwnedSimConnectRecv::SystemState(state))) => {
+                    return Poll::Ready(Ok(SystemState::decode(self.query, &state)));
+                }
+                Poll::Ready(Some(OwnedSimConnectRecv::Exception(exception))) => {
+                    let exception = Exception::new(&exception, self.call.take());
+                    return Poll::Ready(Err(exception.into()));
+                }
+                Poll::Ready(Some(_)) => {}
+                Poll::Ready(None) => return Poll::Ready(Err(RequestError::Closed)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for SystemStateRequest {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("SystemStateRequest")
+            .field("query", &self.query)
+            .field("request_id", &self.request_id)
+            .finish()
+    }
+}
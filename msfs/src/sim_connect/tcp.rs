@@ -0,0 +1,720 @@
+use super::{Backend, Call, HResult, Result};
+use crate::sys;
+use std::cell::{Cell, RefCell};
+use std::ffi::CStr;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+// Messages are cast directly to the `SIMCONNECT_RECV_*` structs, which is only
+// valid when their layout matches the 32-bit little-endian wire format.
+const _: () = assert!(std::mem::size_of::<sys::DWORD>() == 4);
+const _: () = assert!(cfg!(target_endian = "little"));
+
+/// `E_FAIL`, reported for any I/O or protocol error on the connection.
+const E_FAIL: sys::HRESULT = 0x8000_4005_u32 as sys::HRESULT;
+
+/// Protocol revision announced in every packet header.
+const PROTOCOL_VERSION: u32 = 5;
+/// SimConnect version announced when opening the connection.
+const SIMCONNECT_VERSION: [u32; 4] = [11, 0, 62651, 3];
+
+const HEADER_SIZE: usize = 16;
+const RECV_HEADER_SIZE: usize = std::mem::size_of::<sys::SIMCONNECT_RECV>();
+const MAX_PATH: usize = 260;
+
+// Function indices, in the order the SimConnect client library numbers them.
+const OPEN: u32 = 0x01;
+const MAP_CLIENT_EVENT_TO_SIM_EVENT: u32 = 0x04;
+const TRANSMIT_CLIENT_EVENT: u32 = 0x05;
+const SET_SYSTEM_EVENT_STATE: u32 = 0x06;
+const ADD_CLIENT_EVENT_TO_NOTIFICATION_GROUP: u32 = 0x07;
+const REMOVE_CLIENT_EVENT: u32 = 0x08;
+const SET_NOTIFICATION_GROUP_PRIORITY: u32 = 0x09;
+const CLEAR_NOTIFICATION_GROUP: u32 = 0x0A;
+const REQUEST_NOTIFICATION_GROUP: u32 = 0x0B;
+const ADD_TO_DATA_DEFINITION: u32 = 0x0C;
+const CLEAR_DATA_DEFINITION: u32 = 0x0D;
+const REQUEST_DATA_ON_SIM_OBJECT: u32 = 0x0E;
+const REQUEST_DATA_ON_SIM_OBJECT_TYPE: u32 = 0x0F;
+const SET_DATA_ON_SIM_OBJECT: u32 = 0x10;
+const MAP_INPUT_EVENT_TO_CLIENT_EVENT: u32 = 0x11;
+const SET_INPUT_GROUP_PRIORITY: u32 = 0x12;
+const REMOVE_INPUT_EVENT: u32 = 0x13;
+const CLEAR_INPUT_GROUP: u32 = 0x14;
+const SET_INPUT_GROUP_STATE: u32 = 0x15;
+const REQUEST_RESERVED_KEY: u32 = 0x16;
+const SUBSCRIBE_TO_SYSTEM_EVENT: u32 = 0x17;
+const UNSUBSCRIBE_FROM_SYSTEM_EVENT: u32 = 0x18;
+const AI_CREATE_PARKED_ATC_AIRCRAFT: u32 = 0x27;
+const AI_CREATE_ENROUTE_ATC_AIRCRAFT: u32 = 0x28;
+const AI_CREATE_NON_ATC_AIRCRAFT: u32 = 0x29;
+const AI_CREATE_SIMULATED_OBJECT: u32 = 0x2A;
+const AI_RELEASE_CONTROL: u32 = 0x2B;
+const AI_REMOVE_OBJECT: u32 = 0x2C;
+const AI_SET_AIRCRAFT_FLIGHT_PLAN: u32 = 0x2D;
+const REQUEST_SYSTEM_STATE: u32 = 0x35;
+const MAP_CLIENT_DATA_NAME_TO_ID: u32 = 0x37;
+const CREATE_CLIENT_DATA: u32 = 0x38;
+const ADD_TO_CLIENT_DATA_DEFINITION: u32 = 0x39;
+const REQUEST_CLIENT_DATA: u32 = 0x3B;
+const SET_CLIENT_DATA: u32 = 0x3C;
+const FLIGHT_LOAD: u32 = 0x3D;
+const FLIGHT_SAVE: u32 = 0x3E;
+const FLIGHT_PLAN_LOAD: u32 = 0x3F;
+const SUBSCRIBE_TO_FACILITIES: u32 = 0x41;
+const UNSUBSCRIBE_TO_FACILITIES: u32 = 0x42;
+const REQUEST_FACILITIES_LIST: u32 = 0x43;
+const TRANSMIT_CLIENT_EVENT_EX1: u32 = 0x44;
+const ADD_TO_FACILITY_DEFINITION: u32 = 0x45;
+const REQUEST_FACILITY_DATA: u32 = 0x46;
+const SUBSCRIBE_TO_FACILITIES_EX1: u32 = 0x47;
+const UNSUBSCRIBE_TO_FACILITIES_EX1: u32 = 0x48;
+const REQUEST_FACILITIES_LIST_EX1: u32 = 0x49;
+const ENUMERATE_INPUT_EVENTS: u32 = 0x4F;
+const GET_INPUT_EVENT: u32 = 0x50;
+const SET_INPUT_EVENT: u32 = 0x51;
+const SUBSCRIBE_INPUT_EVENT: u32 = 0x52;
+const UNSUBSCRIBE_INPUT_EVENT: u32 = 0x53;
+const ENUMERATE_INPUT_EVENT_PARAMS: u32 = 0x54;
+
+fn io_error(_: std::io::Error) -> HResult {
+    HResult(E_FAIL)
+}
+
+/// A packet under construction. The header is filled in by `TcpBackend::send`.
+struct Packet(Vec<u8>);
+
+impl Packet {
+    fn new() -> Self {
+        Packet(vec![0; HEADER_SIZE])
+    }
+
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn u64(&mut self, value: u64) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn f32(&mut self, value: f32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn f64(&mut self, value: f64) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    /// Write a nul-terminated string into a fixed-size field, truncating it
+    /// if needed.
+    fn string(&mut self, value: &CStr, size: usize) -> &mut Self {
+        let value = value.to_bytes();
+        let len = value.len().min(size - 1);
+        self.0.extend_from_slice(&value[..len]);
+        self.0.resize(self.0.len() + size - len, 0);
+        self
+    }
+
+    fn init_position(&mut self, value: &sys::SIMCONNECT_DATA_INITPOSITION) -> &mut Self {
+        self.f64(value.Latitude)
+            .f64(value.Longitude)
+            .f64(value.Altitude)
+            .f64(value.Pitch)
+            .f64(value.Bank)
+            .f64(value.Heading)
+            .u32(value.OnGround)
+            .u32(value.Airspeed)
+    }
+}
+
+/// A backend which speaks the SimConnect wire protocol over TCP, without
+/// needing the SimConnect library from the MSFS SDK. The sim must be
+/// configured to accept remote clients in its `SimConnect.xml`.
+/// ```rs
+/// let backend = TcpBackend::connect("192.168.1.10:500", "LOG")?;
+/// let mut sim = SimConnect::open_with_backend(backend, |sim, recv| {
+///     // ...
+/// })?;
+/// ```
+#[derive(Debug)]
+pub struct TcpBackend {
+    stream: TcpStream,
+    buffer: RefCell<Vec<u8>>,
+    packet_id: Cell<u32>,
+}
+
+impl TcpBackend {
+    /// Connect to a SimConnect server at `addr`, and open up communications
+    /// as a new client called `name`.
+    pub fn connect<A: ToSocketAddrs>(addr: A, name: &str) -> std::io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        let backend = TcpBackend {
+            stream,
+            buffer: RefCell::new(Vec::new()),
+            packet_id: Cell::new(0),
+        };
+
+        let name = std::ffi::CString::new(name).unwrap();
+        let mut packet = Packet::new();
+        packet
+            .string(&name, 256)
+            .u32(0)
+            .bytes(&[0, b'X', b'S', b'F'])
+            .u32(SIMCONNECT_VERSION[0])
+            .u32(SIMCONNECT_VERSION[1])
+            .u32(SIMCONNECT_VERSION[2])
+            .u32(SIMCONNECT_VERSION[3]);
+        backend.send(OPEN, packet)?;
+
+        Ok(backend)
+    }
+
+    fn send(&self, function: u32, mut packet: Packet) -> std::io::Result<()> {
+        let packet_id = self.packet_id.get() + 1;
+        self.packet_id.set(packet_id);
+        let size = packet.0.len() as u32;
+        packet.0[0..4].copy_from_slice(&size.to_le_bytes());
+        packet.0[4..8].copy_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+        packet.0[8..12].copy_from_slice(&(0xF000_0000 | function).to_le_bytes());
+        packet.0[12..16].copy_from_slice(&packet_id.to_le_bytes());
+        (&self.stream).write_all(&packet.0)
+    }
+
+    /// Read everything the server has sent so far, without blocking.
+    fn receive(&self) -> std::io::Result<()> {
+        self.stream.set_nonblocking(true)?;
+        let mut chunk = [0; 4096];
+        let result = loop {
+            match (&self.stream).read(&mut chunk) {
+                Ok(0) => break Err(std::io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => self.buffer.borrow_mut().extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => break Err(e),
+            }
+        };
+        self.stream.set_nonblocking(false)?;
+        result
+    }
+
+    /// Split the complete messages off the front of the receive buffer.
+    fn take_messages(&self) -> std::io::Result<Vec<Vec<u8>>> {
+        let mut buffer = self.buffer.borrow_mut();
+        let mut messages = Vec::new();
+        let mut offset = 0;
+        while buffer.len() - offset >= RECV_HEADER_SIZE {
+            let size = u32::from_le_bytes(buffer[offset..offset + 4].try_into().unwrap());
+            let size = size as usize;
+            if size < RECV_HEADER_SIZE {
+                return Err(std::io::ErrorKind::InvalidData.into());
+            }
+            if buffer.len() - offset < size {
+                break;
+            }
+            messages.push(buffer[offset..offset + size].to_vec());
+            offset += size;
+        }
+        buffer.drain(..offset);
+        Ok(messages)
+    }
+}
+
+impl Backend for TcpBackend {
+    fn call(&self, call: Call<'_>) -> Result<()> {
+        let mut packet = Packet::new();
+        let function = match call {
+            Call::MapClientEventToSimEvent {
+                event_id,
+                event_name,
+            } => {
+                packet.u32(event_id).string(event_name, 256);
+                MAP_CLIENT_EVENT_TO_SIM_EVENT
+            }
+            Call::TransmitClientEvent {
+                object_id,
+                event_id,
+                data,
+                group_id,
+                flags,
+            } => {
+                packet
+                    .u32(object_id)
+                    .u32(event_id)
+                    .u32(data)
+                    .u32(group_id)
+                    .u32(flags);
+                TRANSMIT_CLIENT_EVENT
+            }
+            Call::TransmitClientEventEx1 {
+                object_id,
+                event_id,
+                group_id,
+                flags,
+                data,
+            } => {
+                packet.u32(object_id).u32(event_id).u32(group_id).u32(flags);
+                for data in data {
+                    packet.u32(data);
+                }
+                TRANSMIT_CLIENT_EVENT_EX1
+            }
+            Call::SetSystemEventState { event_id, state } => {
+                packet.u32(event_id).u32(state as sys::DWORD);
+                SET_SYSTEM_EVENT_STATE
+            }
+            Call::AddClientEventToNotificationGroup {
+                group_id,
+                event_id,
+                maskable,
+            } => {
+                packet.u32(group_id).u32(event_id).u32(maskable.into());
+                ADD_CLIENT_EVENT_TO_NOTIFICATION_GROUP
+            }
+            Call::RemoveClientEvent { group_id, event_id } => {
+                packet.u32(group_id).u32(event_id);
+                REMOVE_CLIENT_EVENT
+            }
+            Call::SetNotificationGroupPriority { group_id, priority } => {
+                packet.u32(group_id).u32(priority);
+                SET_NOTIFICATION_GROUP_PRIORITY
+            }
+            Call::ClearNotificationGroup { group_id } => {
+                packet.u32(group_id);
+                CLEAR_NOTIFICATION_GROUP
+            }
+            Call::RequestNotificationGroup {
+                group_id,
+                reserved,
+                flags,
+            } => {
+                packet.u32(group_id).u32(reserved).u32(flags);
+                REQUEST_NOTIFICATION_GROUP
+            }
+            Call::MapInputEventToClientEvent {
+                group_id,
+                input_definition,
+                down_event_id,
+                down_value,
+                up_event_id,
+                up_value,
+                maskable,
+            } => {
+                packet
+                    .u32(group_id)
+                    .string(input_definition, 256)
+                    .u32(down_event_id)
+                    .u32(down_value)
+                    .u32(up_event_id)
+                    .u32(up_value)
+                    .u32(maskable.into());
+                MAP_INPUT_EVENT_TO_CLIENT_EVENT
+            }
+            Call::SetInputGroupPriority { group_id, priority } => {
+                packet.u32(group_id).u32(priority);
+                SET_INPUT_GROUP_PRIORITY
+            }
+            Call::RemoveInputEvent {
+                group_id,
+                input_definition,
+            } => {
+                packet.u32(group_id).string(input_definition, 256);
+                REMOVE_INPUT_EVENT
+            }
+            Call::ClearInputGroup { group_id } => {
+                packet.u32(group_id);
+                CLEAR_INPUT_GROUP
+            }
+            Call::SetInputGroupState { group_id, state } => {
+                packet.u32(group_id).u32(state);
+                SET_INPUT_GROUP_STATE
+            }
+            Call::RequestReservedKey {
+                event_id,
+                key_choice_1,
+                key_choice_2,
+                key_choice_3,
+            } => {
+                packet
+                    .u32(event_id)
+                    .string(key_choice_1, 30)
+                    .string(key_choice_2.unwrap_or_default(), 30)
+                    .string(key_choice_3.unwrap_or_default(), 30);
+                REQUEST_RESERVED_KEY
+            }
+            Call::AddToDataDefinition {
+                define_id,
+                datum_name,
+                units_name,
+                datum_type,
+                epsilon,
+                datum_id,
+            } => {
+                packet
+                    .u32(define_id)
+                    .string(datum_name, 256)
+                    .string(units_name, 256)
+                    .u32(datum_type as sys::DWORD)
+                    .f32(epsilon)
+                    .u32(datum_id);
+                ADD_TO_DATA_DEFINITION
+            }
+            Call::ClearDataDefinition { define_id } => {
+                packet.u32(define_id);
+                CLEAR_DATA_DEFINITION
+            }
+            Call::RequestDataOnSimObject {
+                request_id,
+                define_id,
+                object_id,
+                period,
+                flags,
+                origin,
+                interval,
+                limit,
+            } => {
+                packet
+                    .u32(request_id)
+                    .u32(define_id)
+                    .u32(object_id)
+                    .u32(period as sys::DWORD)
+                    .u32(flags)
+                    .u32(origin)
+                    .u32(interval)
+                    .u32(limit);
+                REQUEST_DATA_ON_SIM_OBJECT
+            }
+            Call::RequestDataOnSimObjectType {
+                request_id,
+                define_id,
+                radius,
+                r#type,
+            } => {
+                packet
+                    .u32(request_id)
+                    .u32(define_id)
+                    .u32(radius)
+                    .u32(r#type as sys::DWORD);
+                REQUEST_DATA_ON_SIM_OBJECT_TYPE
+            }
+            Call::SetDataOnSimObject {
+                define_id,
+                object_id,
+                flags,
+                array_count,
+                unit_size,
+                data,
+            } => {
+                packet
+                    .u32(define_id)
+                    .u32(object_id)
+                    .u32(flags)
+                    .u32(array_count)
+                    .u32(unit_size)
+                    .bytes(data);
+                SET_DATA_ON_SIM_OBJECT
+            }
+            Call::SubscribeToSystemEvent {
+                event_id,
+                system_event_name,
+            } => {
+                packet.u32(event_id).string(system_event_name, 256);
+                SUBSCRIBE_TO_SYSTEM_EVENT
+            }
+            Call::UnsubscribeFromSystemEvent { event_id } => {
+                packet.u32(event_id);
+                UNSUBSCRIBE_FROM_SYSTEM_EVENT
+            }
+            Call::AiCreateParkedAtcAircraft {
+                container_title,
+                tail_number,
+                airport_id,
+                request_id,
+            } => {
+                packet
+                    .string(container_title, 256)
+                    .string(tail_number, 12)
+                    .string(airport_id, 5)
+                    .u32(request_id);
+                AI_CREATE_PARKED_ATC_AIRCRAFT
+            }
+            Call::AiCreateEnrouteAtcAircraft {
+                container_title,
+                tail_number,
+                flight_number,
+                flight_plan_path,
+                flight_plan_position,
+                touch_and_go,
+                request_id,
+            } => {
+                packet
+                    .string(container_title, 256)
+                    .string(tail_number, 12)
+                    .u32(flight_number as u32)
+                    .string(flight_plan_path, 260)
+                    .f64(flight_plan_position)
+                    .u32(touch_and_go.into())
+                    .u32(request_id);
+                AI_CREATE_ENROUTE_ATC_AIRCRAFT
+            }
+            Call::AiCreateNonAtcAircraft {
+                container_title,
+                tail_number,
+                init_position,
+                request_id,
+            } => {
+                packet
+                    .string(container_title, 256)
+                    .string(tail_number, 12)
+                    .init_position(&init_position)
+                    .u32(request_id);
+                AI_CREATE_NON_ATC_AIRCRAFT
+            }
+            Call::AiCreateSimulatedObject {
+                container_title,
+                init_position,
+                request_id,
+            } => {
+                packet
+                    .string(container_title, 256)
+                    .init_position(&init_position)
+                    .u32(request_id);
+                AI_CREATE_SIMULATED_OBJECT
+            }
+            Call::AiReleaseControl {
+                object_id,
+                request_id,
+            } => {
+                packet.u32(object_id).u32(request_id);
+                AI_RELEASE_CONTROL
+            }
+            Call::AiRemoveObject {
+                object_id,
+                request_id,
+            } => {
+                packet.u32(object_id).u32(request_id);
+                AI_REMOVE_OBJECT
+            }
+            Call::AiSetAircraftFlightPlan {
+                object_id,
+                flight_plan_path,
+                request_id,
+            } => {
+                packet
+                    .u32(object_id)
+                    .string(flight_plan_path, 260)
+                    .u32(request_id);
+                AI_SET_AIRCRAFT_FLIGHT_PLAN
+            }
+            Call::RequestSystemState { request_id, state } => {
+                packet.u32(request_id).string(state, 256);
+                REQUEST_SYSTEM_STATE
+            }
+            Call::MapClientDataNameToId {
+                client_data_name,
+                client_data_id,
+            } => {
+                packet.string(client_data_name, 256).u32(client_data_id);
+                MAP_CLIENT_DATA_NAME_TO_ID
+            }
+            Call::CreateClientData {
+                client_data_id,
+                size,
+                flags,
+            } => {
+                packet.u32(client_data_id).u32(size).u32(flags);
+                CREATE_CLIENT_DATA
+            }
+            Call::AddToClientDataDefinition {
+                define_id,
+                offset,
+                size_or_type,
+                epsilon,
+                datum_id,
+            } => {
+                packet
+                    .u32(define_id)
+                    .u32(offset)
+                    .u32(size_or_type)
+                    .f32(epsilon)
+                    .u32(datum_id);
+                ADD_TO_CLIENT_DATA_DEFINITION
+            }
+            Call::RequestClientData {
+                client_data_id,
+                request_id,
+                define_id,
+                period,
+                flags,
+                origin,
+                interval,
+                limit,
+            } => {
+                packet
+                    .u32(client_data_id)
+                    .u32(request_id)
+                    .u32(define_id)
+                    .u32(period as sys::DWORD)
+                    .u32(flags)
+                    .u32(origin)
+                    .u32(interval)
+                    .u32(limit);
+                REQUEST_CLIENT_DATA
+            }
+            Call::SetClientData {
+                client_data_id,
+                define_id,
+                flags,
+                data,
+            } => {
+                packet
+                    .u32(client_data_id)
+                    .u32(define_id)
+                    .u32(flags)
+                    .u32(0)
+                    .u32(data.len() as u32)
+                    .bytes(data);
+                SET_CLIENT_DATA
+            }
+            Call::FlightLoad { file_name } => {
+                packet.string(file_name, MAX_PATH);
+                FLIGHT_LOAD
+            }
+            Call::FlightSave {
+                file_name,
+                title,
+                description,
+                flags,
+            } => {
+                packet
+                    .string(file_name, MAX_PATH)
+                    .string(title.unwrap_or_default(), MAX_PATH)
+                    .string(description.unwrap_or_default(), 2048)
+                    .u32(flags);
+                FLIGHT_SAVE
+            }
+            Call::FlightPlanLoad { file_name } => {
+                packet.string(file_name, MAX_PATH);
+                FLIGHT_PLAN_LOAD
+            }
+            Call::SubscribeToFacilities { r#type, request_id } => {
+                packet.u32(r#type).u32(request_id);
+                SUBSCRIBE_TO_FACILITIES
+            }
+            Call::UnsubscribeToFacilities { r#type } => {
+                packet.u32(r#type);
+                UNSUBSCRIBE_TO_FACILITIES
+            }
+            Call::RequestFacilitiesList { r#type, request_id } => {
+                packet.u32(r#type).u32(request_id);
+                REQUEST_FACILITIES_LIST
+            }
+            Call::SubscribeToFacilitiesEx1 {
+                r#type,
+                in_range_request_id,
+                out_of_range_request_id,
+            } => {
+                packet
+                    .u32(r#type)
+                    .u32(in_range_request_id)
+                    .u32(out_of_range_request_id);
+                SUBSCRIBE_TO_FACILITIES_EX1
+            }
+            Call::UnsubscribeToFacilitiesEx1 {
+                r#type,
+                in_range,
+                out_of_range,
+            } => {
+                packet
+                    .u32(r#type)
+                    .u32(in_range.into())
+                    .u32(out_of_range.into());
+                UNSUBSCRIBE_TO_FACILITIES_EX1
+            }
+            Call::RequestFacilitiesListEx1 { r#type, request_id } => {
+                packet.u32(r#type).u32(request_id);
+                REQUEST_FACILITIES_LIST_EX1
+            }
+            Call::AddToFacilityDefinition {
+                define_id,
+                field_name,
+            } => {
+                packet.u32(define_id).string(field_name, 256);
+                ADD_TO_FACILITY_DEFINITION
+            }
+            Call::RequestFacilityData {
+                define_id,
+                request_id,
+                icao,
+                region,
+            } => {
+                packet
+                    .u32(define_id)
+                    .u32(request_id)
+                    .string(icao, 16)
+                    .string(region.unwrap_or_default(), 16);
+                REQUEST_FACILITY_DATA
+            }
+            Call::EnumerateInputEvents { request_id } => {
+                packet.u32(request_id);
+                ENUMERATE_INPUT_EVENTS
+            }
+            Call::GetInputEvent { request_id, hash } => {
+                packet.u32(request_id).u64(hash);
+                GET_INPUT_EVENT
+            }
+            Call::SetInputEvent { hash, value } => {
+                packet.u64(hash).u32(value.len() as u32).bytes(value);
+                SET_INPUT_EVENT
+            }
+            Call::SubscribeInputEvent { hash } => {
+                packet.u64(hash);
+                SUBSCRIBE_INPUT_EVENT
+            }
+            Call::UnsubscribeInputEvent { hash } => {
+                packet.u64(hash);
+                UNSUBSCRIBE_INPUT_EVENT
+            }
+            Call::EnumerateInputEventParams { hash } => {
+                packet.u64(hash);
+                ENUMERATE_INPUT_EVENT_PARAMS
+            }
+        };
+        self.send(function, packet).map_err(io_error)
+    }
+
+    fn last_sent_packet_id(&self) -> Result<sys::DWORD> {
+        Ok(self.packet_id.get())
+    }
+
+    unsafe fn call_dispatch(
+        &self,
+        dispatch: sys::DispatchProc,
+        context: *mut std::ffi::c_void,
+    ) -> Result<()> {
+        // Messages which arrived before a read error are still delivered.
+        let received = self.receive();
+        let messages = self.take_messages().map_err(io_error)?;
+        if let Some(dispatch) = dispatch {
+            for mut message in messages {
+                unsafe {
+                    dispatch(
+                        message.as_mut_ptr() as *mut sys::SIMCONNECT_RECV,
+                        message.len() as sys::DWORD,
+                        context,
+                    );
+                }
+            }
+        }
+        received.map_err(io_error)
+    }
+}
@@ -0,0 +1,195 @@
+use super::{DataDefinition, DefineId, ObjectId, OwnedSimConnectRecv, RequestId, SystemEventId};
+use crate::sys;
+use futures::channel::mpsc;
+use futures::stream::{FusedStream, Stream};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A change to the objects of a [`SimObjectTracker`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TrackerEvent {
+    /// An object came within range, and its value is now in the tracker.
+    Added(ObjectId),
+    /// The value of an object changed.
+    Updated(ObjectId),
+    /// An object was removed from the sim or went out of range, and is no
+    /// longer in the tracker.
+    Removed(ObjectId),
+}
+
+struct Tracked<T> {
+    /// The data last received, to tell whether the value changed.
+    data: Vec<u8>,
+    value: T,
+}
+
+/// The latest values of a data definition on every SimObject of a type within
+/// a radius of the user's aircraft, created by
+/// [`SimConnect::track_sim_objects`](super::SimConnect::track_sim_objects).
+/// It is a stream of the changes to the objects, and dropping it stops the
+/// tracking.
+pub struct SimObjectTracker<T: DataDefinition> {
+    rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+    define_id: DefineId,
+    request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+    removed_event_id: SystemEventId,
+    objects: BTreeMap<ObjectId, Tracked<T>>,
+    /// The objects received in the current response, and how many there are.
+    seen: BTreeSet<ObjectId>,
+    expected: usize,
+    events: VecDeque<TrackerEvent>,
+}
+
+impl<T: DataDefinition> SimObjectTracker<T> {
+    pub(super) fn new(
+        rx: mpsc::UnboundedReceiver<OwnedSimConnectRecv>,
+        define_id: DefineId,
+        request_id: sys::SIMCONNECT_DATA_REQUEST_ID,
+        removed_event_id: SystemEventId,
+    ) -> Self {
+        SimObjectTracker {
+            rx,
+            define_id,
+            request_id,
+            removed_event_id,
+            objects: BTreeMap::new(),
+            seen: BTreeSet::new(),
+            expected: 0,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// The request ID allocated for this tracker.
+    pub fn request_id(&self) -> RequestId {
+        RequestId(self.request_id)
+    }
+
+    /// The latest value of an object, if it is tracked.
+    pub fn get(&self, object_id: ObjectId) -> Option<&T> {
+        self.objects.get(&object_id).map(|tracked| &tracked.value)
+    }
+
+    /// The tracked objects and their latest values, by object ID.
+    pub fn iter(&self) -> impl Iterator<Item = (ObjectId, &T)> + '_ {
+        self.objects
+            .iter()
+            .map(|(&object_id, tracked)| (object_id, &tracked.value))
+    }
+
+    /// The number of tracked objects.
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Whether no objects are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Apply every message received since the last call, without waiting for
+    /// a new one, returning the changes to the objects.
+    pub fn update(&mut self) -> Vec<TrackerEvent> {
+        while let Ok(recv) = self.rx.try_recv() {
+            self.apply(recv);
+        }
+        self.events.drain(..).collect()
+    }
+
+    fn apply(&mut self, recv: OwnedSimConnectRecv) {
+        match recv {
+            OwnedSimConnectRecv::SimObjectData(data) if data.define_id() == self.define_id => {
+                // Each response starts again from the first entry, or is a
+                // single message with a `dwoutof` of 0 when there are no
+                // objects.
+                if data.dwentrynumber <= 1 {
+                    self.seen.clear();
+                    self.expected = data.dwoutof as usize;
+                }
+                if data.dwoutof > 0 {
+                    let object_id = data.object_id();
+                    self.seen.insert(object_id);
+                    // Objects which can't be decoded keep their last value.
+                    if let Ok(value) = T::decode(data.data()) {
+                        self.insert(object_id, data.data().to_vec(), value);
+                    }
+                }
+                if self.seen.len() == self.expected {
+                    self.evict();
+                }
+            }
+            OwnedSimConnectRecv::EventObjectAddRemove(event)
+                if event.id() == self.removed_event_id =>
+            {
+                let object_id = event.object_id();
+                if self.objects.remove(&object_id).is_some() {
+                    self.events.push_back(TrackerEvent::Removed(object_id));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn insert(&mut self, object_id: ObjectId, data: Vec<u8>, value: T) {
+        match self.objects.get_mut(&object_id) {
+            Some(tracked) if tracked.data == data => {}
+            Some(tracked) => {
+                *tracked = Tracked { data, value };
+                self.events.push_back(TrackerEvent::Updated(object_id));
+            }
+            None => {
+                self.objects.insert(object_id, Tracked { data, value });
+                self.events.push_back(TrackerEvent::Added(object_id));
+            }
+        }
+    }
+
+    /// Remove the objects which were missing from a complete response.
+    fn evict(&mut self) {
+        let gone = self
+            .objects
+            .keys()
+            .filter(|object_id| !self.seen.contains(object_id))
+            .copied()
+            .collect::<Vec<_>>();
+        for object_id in gone {
+            self.objects.remove(&object_id);
+            self.events.push_back(TrackerEvent::Removed(object_id));
+        }
+    }
+}
+
+// The tracked values are never pinned.
+impl<T: DataDefinition> Unpin for SimObjectTracker<T> {}
+
+impl<T: DataDefinition> Stream for SimObjectTracker<T> {
+    type Item = TrackerEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<TrackerEvent>> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Poll::Ready(Some(event));
+            }
+            match Pin::new(&mut self.rx).poll_next(cx) {
+                Poll::Ready(Some(recv)) => self.apply(recv),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T: DataDefinition> FusedStream for SimObjectTracker<T> {
+    fn is_terminated(&self) -> bool {
+        self.events.is_empty() && self.rx.is_terminated()
+    }
+}
+
+impl<T: DataDefinition> std::fmt::Debug for SimObjectTracker<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("SimObjectTracker")
+            .field("request_id", &self.request_id)
+            .field("objects", &self.objects.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
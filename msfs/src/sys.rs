@@ -5,9 +5,13 @@
 #![allow(dead_code)]
 #![allow(deref_nullptr)]
 #![allow(unsafe_op_in_unsafe_fn)]
+#[cfg(not(vendored_bindings))]
 include!(concat!(env!("OUT_DIR"), "/msfs-sys.rs"));
+#[cfg(vendored_bindings)]
+include!("bindgen_support/sim_connect.rs");
 
 // https://github.com/rustwasm/team/issues/291
+#[cfg(not(vendored_bindings))]
 unsafe extern "C" {
     pub fn nvgStrokeColor(ctx: *mut NVGcontext, color: *const NVGcolor);
     pub fn nvgStrokePaint(ctx: *mut NVGcontext, paint: *const NVGpaint);
@@ -0,0 +1,119 @@
+use futures::StreamExt;
+use msfs::sim_connect::{
+    ExceptionKind, MockServer, ObjectId, RequestError, RequestId, SimConnect, SimConnectRecv,
+    SystemEvent, SystemEventKind,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn position() -> msfs::sys::SIMCONNECT_DATA_INITPOSITION {
+    unsafe { std::mem::zeroed() }
+}
+
+#[test]
+fn lifecycle() {
+    let server = MockServer::new();
+    let unrouted: Rc<RefCell<Vec<String>>> = Rc::default();
+    let messages = unrouted.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        if !matches!(recv, SimConnectRecv::Open(_)) {
+            messages.borrow_mut().push(format!("{recv:?}"));
+        }
+    })
+    .unwrap();
+    let mut removed = sim.system_events(SystemEventKind::ObjectRemoved).unwrap();
+    let a = sim
+        .ai_create_non_atc_aircraft("A320", "N1", position())
+        .unwrap();
+    let b = sim
+        .ai_create_enroute_atc_aircraft("A320", "N2", 123, "plans/kjfk", 0.0, false)
+        .unwrap();
+    let c = sim.ai_create_simulated_object("Boat", position()).unwrap();
+    let d = sim
+        .ai_create_parked_atc_aircraft("A320", "N3", "KJFK")
+        .unwrap();
+    let failed = sim.ai_create_simulated_object("", position()).unwrap();
+    sim.call_dispatch().unwrap();
+    // Answers and exceptions all go to the requests.
+    assert!(unrouted.borrow().is_empty(), "{:?}", unrouted.borrow());
+
+    let (a, b, c, d) = futures::executor::block_on(async {
+        match failed.await {
+            Err(RequestError::Exception(exception)) => {
+                assert_eq!(exception.kind, ExceptionKind::CreateObjectFailed);
+            }
+            other => panic!("{other:?}"),
+        }
+        (
+            a.await.unwrap(),
+            b.await.unwrap(),
+            c.await.unwrap(),
+            d.await.unwrap(),
+        )
+    });
+    assert_eq!(
+        [a.id(), b.id(), c.id(), d.id()],
+        [ObjectId(1), ObjectId(2), ObjectId(3), ObjectId(4)]
+    );
+    assert_eq!(server.ai_flight_plan(b.id()).as_deref(), Some("plans/kjfk"));
+
+    sim.ai_set_aircraft_flight_plan(a.id(), "plans/egll", RequestId(1))
+        .unwrap();
+    sim.ai_release_control(c.id(), RequestId(2)).unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(server.ai_flight_plan(a.id()).as_deref(), Some("plans/egll"));
+    assert!(server.ai_released(c.id()));
+
+    // Dropped objects are removed with the next dispatch, cancelled ones at
+    // once, and forgotten ones stay.
+    drop(a);
+    b.cancel(&mut sim).unwrap();
+    let d = d.forget();
+    sim.call_dispatch().unwrap();
+    sim.call_dispatch().unwrap();
+    futures::executor::block_on(async {
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            match removed.next().await {
+                Some(SystemEvent::ObjectRemoved { id, .. }) => ids.push(id),
+                other => panic!("{other:?}"),
+            }
+        }
+        ids.sort();
+        assert_eq!(ids, [ObjectId(1), ObjectId(2)]);
+    });
+    // The forgotten object is still there.
+    sim.ai_set_aircraft_flight_plan(d, "plans/lfpg", RequestId(3))
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(server.ai_flight_plan(d).as_deref(), Some("plans/lfpg"));
+    assert!(unrouted.borrow().is_empty(), "{:?}", unrouted.borrow());
+    drop(c);
+}
+
+#[test]
+fn unanswered_calls_raise_exceptions_in_the_callback() {
+    let server = MockServer::new();
+    let exceptions: Rc<RefCell<Vec<(ExceptionKind, &str)>>> = Rc::default();
+    let received = exceptions.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |sim, recv| {
+        if let SimConnectRecv::Exception(exception) = recv {
+            let exception = exception.decode(sim);
+            received
+                .borrow_mut()
+                .push((exception.kind, exception.call.unwrap().method));
+        }
+    })
+    .unwrap();
+    sim.ai_set_aircraft_flight_plan(ObjectId(9), "plans/egll", RequestId(1))
+        .unwrap();
+    sim.ai_release_control(ObjectId(9), RequestId(2)).unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        *exceptions.borrow(),
+        [
+            (ExceptionKind::UnrecognizedId, "ai_set_aircraft_flight_plan"),
+            (ExceptionKind::UnrecognizedId, "ai_release_control"),
+        ]
+    );
+}
@@ -0,0 +1,64 @@
+use msfs::sim_connect::{
+    DataDefinition, MockServer, ObjectId, Period, RequestId, SimConnect, SimConnectRecv,
+    data_definition,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[data_definition]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Engines {
+    #[name = "ENG N1 RPM:{}"]
+    #[unit = "Percent"]
+    n1: [f64; 4],
+    #[name = "FUELSYSTEM TANK QUANTITY:{}"]
+    #[unit = "Gallons"]
+    #[start_index = 0]
+    tanks: [i32; 2],
+}
+
+#[test]
+fn arrays() {
+    let names: Vec<_> = Engines::definitions()
+        .into_iter()
+        .map(|definition| definition.0)
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "ENG N1 RPM:1",
+            "ENG N1 RPM:2",
+            "ENG N1 RPM:3",
+            "ENG N1 RPM:4",
+            "FUELSYSTEM TANK QUANTITY:0",
+            "FUELSYSTEM TANK QUANTITY:1"
+        ]
+    );
+
+    let server = MockServer::new();
+    let decoded: Rc<RefCell<Vec<Engines>>> = Rc::default();
+    let values = decoded.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |sim, recv| {
+        if let SimConnectRecv::SimObjectData(data) = recv {
+            values.borrow_mut().push(data.into(sim).unwrap());
+        }
+    })
+    .unwrap();
+    let engines = Engines {
+        n1: [1.0, 2.0, 3.0, 4.0],
+        tanks: [5, 6],
+    };
+    sim.set_data_on_sim_object(ObjectId::USER, &engines)
+        .unwrap();
+    assert_eq!(server.simvar(ObjectId::USER, "ENG N1 RPM:3"), Some(3.0));
+    assert_eq!(
+        server.simvar(ObjectId::USER, "FUELSYSTEM TANK QUANTITY:0"),
+        Some(5.0)
+    );
+
+    let _handle = sim
+        .request_data_on_sim_object::<Engines>(RequestId(0), ObjectId::USER, Period::Once)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(*decoded.borrow(), [engines]);
+}
@@ -0,0 +1,209 @@
+use futures::FutureExt;
+use msfs::sim_connect::{
+    Backend, Call, ClientEventId, DecodeError, DefineId, ObjectId, Period, RequestId, SimConnect,
+    SimConnectRecv, SnapshotError, data_definition,
+};
+use msfs::sys;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// A backend which delivers queued messages and records every call.
+#[derive(Clone, Default)]
+struct Loopback {
+    calls: Rc<RefCell<Vec<String>>>,
+    messages: Rc<RefCell<Vec<Vec<u8>>>>,
+}
+
+impl Backend for Loopback {
+    fn call(&self, call: Call<'_>) -> msfs::sim_connect::Result<()> {
+        self.calls.borrow_mut().push(format!("{call:?}"));
+        Ok(())
+    }
+
+    fn last_sent_packet_id(&self) -> msfs::sim_connect::Result<sys::DWORD> {
+        Ok(self.calls.borrow().len() as sys::DWORD)
+    }
+
+    unsafe fn call_dispatch(
+        &self,
+        dispatch: sys::DispatchProc,
+        context: *mut std::ffi::c_void,
+    ) -> msfs::sim_connect::Result<()> {
+        let messages = std::mem::take(&mut *self.messages.borrow_mut());
+        for mut message in messages {
+            let size = message.len() as sys::DWORD;
+            unsafe { dispatch.unwrap()(message.as_mut_ptr().cast(), size, context) };
+        }
+        Ok(())
+    }
+}
+
+fn event(event_id: u32, data: u32) -> Vec<u8> {
+    [24, 5, 4, u32::MAX, event_id, data]
+        .into_iter()
+        .flat_map(u32::to_le_bytes)
+        .collect()
+}
+
+/// SimObjectData for `request_id` and `define_id`, with `data` after the
+/// header, and `size` in the header.
+fn data(request_id: u32, define_id: u32, size: u32, data: &[u8]) -> Vec<u8> {
+    let mut message: Vec<u8> = [size, 5, 8, request_id, 0, define_id, 0, 0, 0, 1]
+        .into_iter()
+        .flat_map(u32::to_le_bytes)
+        .collect();
+    message.extend_from_slice(data);
+    message
+}
+
+/// Tagged SimObjectData for `request_id`, with `data` after the header.
+fn tagged_data(request_id: u32, define_count: u32, data: &[u8]) -> Vec<u8> {
+    let flags = sys::SIMCONNECT_DATA_REQUEST_FLAG_TAGGED;
+    let size = 40 + data.len() as u32;
+    let mut message: Vec<u8> = [size, 5, 8, request_id, 0, 0, flags, 0, 0, define_count]
+        .into_iter()
+        .flat_map(u32::to_le_bytes)
+        .collect();
+    message.extend_from_slice(data);
+    message
+}
+
+#[data_definition]
+#[derive(Debug, PartialEq)]
+struct Altitude {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+#[test]
+fn size_mismatch() {
+    let backend = Loopback::default();
+    let decoded: Rc<RefCell<Vec<Result<Altitude, DecodeError>>>> = Rc::default();
+    let values = decoded.clone();
+    let mut sim = SimConnect::open_with_backend(backend.clone(), move |sim, recv| {
+        if let SimConnectRecv::SimObjectData(data) = recv {
+            values.borrow_mut().push(data.into(sim));
+        }
+    })
+    .unwrap();
+    let _handle = sim
+        .request_data_on_sim_object::<Altitude>(RequestId(1), ObjectId::USER, Period::Once)
+        .unwrap();
+
+    // The second header claims fewer bytes than were received.
+    let altitude = 1.0f64.to_le_bytes();
+    backend
+        .messages
+        .borrow_mut()
+        .extend([data(1, 0, 48, &altitude), data(1, 0, 44, &altitude)]);
+    sim.call_dispatch().unwrap();
+    let decoded = decoded.borrow();
+    assert!(matches!(decoded[0], Ok(Altitude { altitude: 1.0 })));
+    assert_eq!(
+        decoded[1].as_ref().err(),
+        Some(&DecodeError::SizeMismatch {
+            expected: 44,
+            received: 48
+        })
+    );
+}
+
+#[test]
+fn malformed_tagged_data() {
+    let backend = Loopback::default();
+    let mut sim = SimConnect::open_with_backend(backend.clone(), |_, _| {}).unwrap();
+    let mut altitude = sim
+        .subscribe_tagged::<Altitude>(ObjectId::USER, Period::SimFrame)
+        .unwrap();
+    let request_id = altitude.request_id().0;
+
+    let mut unknown = 5u32.to_le_bytes().to_vec();
+    unknown.extend_from_slice(&1.0f64.to_le_bytes());
+    let mut truncated = 0u32.to_le_bytes().to_vec();
+    truncated.extend_from_slice(&[0; 4]);
+    let mut other = tagged_data(request_id, 1, &unknown);
+    other[20..24].copy_from_slice(&1u32.to_le_bytes());
+    backend.messages.borrow_mut().extend([
+        tagged_data(request_id, 1, &unknown),
+        tagged_data(request_id, 1, &truncated),
+        other,
+    ]);
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        altitude.update(),
+        Some(Err(DecodeError::UnknownDatum { datum_id: 5 }))
+    );
+    assert_eq!(altitude.update(), Some(Err(DecodeError::Truncated)));
+    assert_eq!(
+        altitude.update(),
+        Some(Err(DecodeError::DefinitionMismatch {
+            expected: DefineId(0),
+            received: DefineId(1)
+        }))
+    );
+    assert_eq!(altitude.update(), None);
+    assert!(altitude.value().is_none());
+}
+
+#[test]
+fn snapshot_header_mismatch() {
+    let backend = Loopback::default();
+    let mut sim = SimConnect::open_with_backend(backend.clone(), |_, _| {}).unwrap();
+    let mut snapshot = || {
+        sim.snapshot_sim_objects::<Altitude>(
+            1000,
+            sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT,
+            Duration::from_secs(60),
+        )
+        .unwrap()
+    };
+    let (empty, truncated) = (snapshot(), snapshot());
+
+    // No objects, for another definition.
+    let empty_id = empty.request_id().0;
+    // One object of one, with only half an altitude.
+    let mut one = data(truncated.request_id().0, 0, 44, &[0; 4]);
+    one[28..36].copy_from_slice(&[1, 0, 0, 0, 1, 0, 0, 0]);
+    backend
+        .messages
+        .borrow_mut()
+        .extend([data(empty_id, 1, 44, &[0; 4]), one]);
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        empty.now_or_never(),
+        Some(Err(SnapshotError::Decode(
+            DecodeError::DefinitionMismatch {
+                expected: DefineId(0),
+                received: DefineId(1)
+            }
+        )))
+    );
+    assert_eq!(
+        truncated.now_or_never(),
+        Some(Err(SnapshotError::Decode(DecodeError::Truncated)))
+    );
+}
+
+#[test]
+fn calls_from_dispatch() {
+    let backend = Loopback::default();
+    let mut sim = SimConnect::open_with_backend(backend.clone(), |sim, recv| {
+        // Calls made while the backend is dispatching.
+        if let SimConnectRecv::Event(event) = recv {
+            sim.transmit_client_event(ObjectId::USER, ClientEventId(7), event.data() + 1)
+                .unwrap();
+        }
+    })
+    .unwrap();
+    backend
+        .messages
+        .borrow_mut()
+        .extend([event(7, 1), event(7, 2)]);
+    sim.call_dispatch().unwrap();
+    let calls = backend.calls.borrow();
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].starts_with("TransmitClientEvent { object_id: 0, event_id: 7, data: 2"));
+    assert!(calls[1].starts_with("TransmitClientEvent { object_id: 0, event_id: 7, data: 3"));
+}
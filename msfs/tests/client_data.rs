@@ -0,0 +1,180 @@
+use msfs::sim_connect::{
+    ClientDataDefinition, DecodeError, DefineId, MockServer, RequestId, SimConnect, SimConnectRecv,
+    client_data_definition,
+};
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+enum Mode {
+    Off,
+    Standby,
+    On,
+}
+
+impl TryFrom<u8> for Mode {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, ()> {
+        match value {
+            0 => Ok(Mode::Off),
+            1 => Ok(Mode::Standby),
+            2 => Ok(Mode::On),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Mode> for u8 {
+    fn from(mode: Mode) -> u8 {
+        mode as u8
+    }
+}
+
+/// Twelve bytes of fields, padded to sixteen.
+#[client_data_definition]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Status {
+    altitude: f64,
+    #[convert = "u8"]
+    mode: Mode,
+    armed: bool,
+    count: u16,
+}
+
+#[client_data_definition]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Other {
+    value: f64,
+}
+
+type Decoded = Rc<RefCell<Vec<Result<Status, DecodeError>>>>;
+
+fn open(server: &MockServer) -> (Pin<Box<SimConnect<'static>>>, Decoded) {
+    let decoded = Decoded::default();
+    let statuses = decoded.clone();
+    let sim = SimConnect::open_with_backend(server.backend(), move |sim, recv| {
+        if let SimConnectRecv::ClientData(data) = recv {
+            statuses.borrow_mut().push(data.into(sim));
+        }
+    })
+    .unwrap();
+    (sim, decoded)
+}
+
+/// The bytes of a `Status` with `mode` and `armed` set to raw values.
+fn raw_status(mode: u8, armed: u8) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    Status {
+        altitude: 1.0,
+        mode: Mode::Off,
+        armed: false,
+        count: 0,
+    }
+    .encode(&mut bytes);
+    bytes[std::mem::offset_of!(Status, mode)] = mode;
+    bytes[std::mem::offset_of!(Status, armed)] = armed;
+    bytes
+}
+
+#[test]
+fn padded_round_trip() {
+    assert_eq!(std::mem::size_of::<Status>(), 16);
+    let server = MockServer::new();
+    let (mut sim, decoded) = open(&server);
+    let area = sim.create_client_data::<Status>("STATUS").unwrap();
+    let _handle = sim
+        .request_client_data::<Status>(RequestId(1), "STATUS")
+        .unwrap();
+
+    let status = Status {
+        altitude: 3500.0,
+        mode: Mode::Standby,
+        armed: true,
+        count: 7,
+    };
+    sim.set_client_data(&area, &status).unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        decoded.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [Ok(status)]
+    );
+
+    // The padding is sent zeroed.
+    let bytes = server.client_data("STATUS").unwrap();
+    assert_eq!(bytes.len(), 16);
+    assert!(bytes[12..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn invalid_values() {
+    let server = MockServer::new();
+    let (mut sim, decoded) = open(&server);
+    sim.create_client_data::<Status>("STATUS").unwrap();
+    let _handle = sim
+        .request_client_data::<Status>(RequestId(1), "STATUS")
+        .unwrap();
+
+    server.set_client_data("STATUS", &raw_status(9, 0));
+    sim.call_dispatch().unwrap();
+    server.set_client_data("STATUS", &raw_status(2, 2));
+    sim.call_dispatch().unwrap();
+    server.set_client_data("STATUS", &raw_status(2, 1));
+    sim.call_dispatch().unwrap();
+
+    let decoded = decoded.borrow_mut().drain(..).collect::<Vec<_>>();
+    assert!(matches!(
+        &decoded[0],
+        Err(DecodeError::InvalidValue { value, .. }) if value == "9"
+    ));
+    assert_eq!(
+        decoded[1],
+        Err(DecodeError::InvalidValue {
+            type_name: "bool",
+            value: "2".into()
+        })
+    );
+    assert_eq!(
+        decoded[2],
+        Ok(Status {
+            altitude: 1.0,
+            mode: Mode::On,
+            armed: true,
+            count: 0,
+        })
+    );
+}
+
+#[test]
+fn other_definitions() {
+    let server = MockServer::new();
+    let (mut sim, decoded) = open(&server);
+    sim.create_client_data::<Status>("STATUS").unwrap();
+    sim.create_client_data::<Other>("OTHER").unwrap();
+    let _handle = sim
+        .request_client_data::<Other>(RequestId(1), "OTHER")
+        .unwrap();
+    server.set_client_data("OTHER", &1.0f64.to_le_bytes());
+    sim.call_dispatch().unwrap();
+
+    // `Status` was never requested, so it has no definition yet.
+    assert!(matches!(
+        decoded.borrow_mut().remove(0),
+        Err(DecodeError::Unregistered { .. })
+    ));
+
+    let _handle = sim
+        .request_client_data::<Status>(RequestId(2), "STATUS")
+        .unwrap();
+    server.set_client_data("OTHER", &2.0f64.to_le_bytes());
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        decoded.borrow_mut().remove(0),
+        Err(DecodeError::DefinitionMismatch {
+            expected: DefineId(1),
+            received: DefineId(0)
+        })
+    );
+}
@@ -0,0 +1,138 @@
+use msfs::sim_connect::{
+    DecodeError, MockServer, ObjectId, Period, RequestId, SimConnect, SimConnectRecv,
+    data_definition,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Gear {
+    Up,
+    Down,
+}
+
+impl TryFrom<i32> for Gear {
+    type Error = ();
+
+    fn try_from(value: i32) -> Result<Self, ()> {
+        match value {
+            0 => Ok(Gear::Up),
+            1 => Ok(Gear::Down),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Gear> for i32 {
+    fn from(gear: Gear) -> i32 {
+        gear as i32
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Feet(f64);
+
+impl From<f64> for Feet {
+    fn from(feet: f64) -> Self {
+        Feet(feet)
+    }
+}
+
+impl From<Feet> for f64 {
+    fn from(feet: Feet) -> Self {
+        feet.0
+    }
+}
+
+/// Light flags, of which only the nav and beacon lights are known.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Lights(i64);
+
+impl Lights {
+    const NAV: i64 = 0x1;
+    const BEACON: i64 = 0x2;
+}
+
+impl TryFrom<i64> for Lights {
+    type Error = ();
+
+    fn try_from(bits: i64) -> Result<Self, ()> {
+        match bits & !(Lights::NAV | Lights::BEACON) {
+            0 => Ok(Lights(bits)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Lights> for i64 {
+    fn from(lights: Lights) -> i64 {
+        lights.0
+    }
+}
+
+#[data_definition]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Aircraft {
+    #[name = "GEAR HANDLE POSITION"]
+    #[unit = "Bool"]
+    #[convert = "i32"]
+    gear: Gear,
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    #[convert = "f64"]
+    altitude: Feet,
+    #[name = "GEAR POSITION:{}"]
+    #[unit = "Enum"]
+    #[convert = "i32"]
+    gears: [Gear; 2],
+    #[name = "LIGHT ON STATES"]
+    #[unit = "Mask"]
+    #[convert = "i64"]
+    lights: Lights,
+}
+
+#[test]
+fn convert() {
+    let server = MockServer::new();
+    let decoded: Rc<RefCell<Vec<Result<Aircraft, DecodeError>>>> = Rc::default();
+    let values = decoded.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |sim, recv| {
+        if let SimConnectRecv::SimObjectData(data) = recv {
+            values.borrow_mut().push(data.into(sim));
+        }
+    })
+    .unwrap();
+    let aircraft = Aircraft {
+        gear: Gear::Down,
+        altitude: Feet(12.0),
+        gears: [Gear::Up, Gear::Down],
+        lights: Lights(Lights::NAV | Lights::BEACON),
+    };
+    sim.set_data_on_sim_object(ObjectId::USER, &aircraft)
+        .unwrap();
+    assert_eq!(server.simvar(ObjectId::USER, "GEAR POSITION:2"), Some(1.0));
+    assert_eq!(server.simvar(ObjectId::USER, "LIGHT ON STATES"), Some(3.0));
+
+    let _handle = sim
+        .request_data_on_sim_object::<Aircraft>(RequestId(0), ObjectId::USER, Period::SimFrame)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(decoded.borrow_mut().remove(0), Ok(aircraft));
+
+    server.set_simvar(ObjectId::USER, "GEAR POSITION:2", 7.0);
+    sim.call_dispatch().unwrap();
+    let error = decoded.borrow_mut().remove(0).unwrap_err();
+    assert!(matches!(error, DecodeError::InvalidValue { .. }));
+    assert_eq!(error.to_string(), "7 is not a valid convert::Gear");
+
+    server.set_simvar(ObjectId::USER, "GEAR POSITION:2", 1.0);
+    server.set_simvar(ObjectId::USER, "LIGHT ON STATES", 4.0);
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        decoded.borrow_mut().remove(0),
+        Err(DecodeError::InvalidValue {
+            type_name: "convert::Lights",
+            value: "4".into()
+        })
+    );
+}
@@ -0,0 +1,219 @@
+use msfs::sim_connect::{
+    DataType, DecodeError, DefineId, DynamicDataDefinition, MockServer, ObjectId, Period,
+    RequestId, RequestOptions, SimConnect, SimConnectRecv, Value, data_definition,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[data_definition]
+struct Altitude {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+type Decoded = Result<(Vec<Value>, HashMap<String, Value>), DecodeError>;
+
+/// Open a session which decodes every SimObjectData event with `definition`.
+fn open(
+    server: &MockServer,
+    definition: &Rc<RefCell<DynamicDataDefinition>>,
+) -> (
+    std::pin::Pin<Box<SimConnect<'static>>>,
+    Rc<RefCell<Vec<Decoded>>>,
+) {
+    let decoded: Rc<RefCell<Vec<Decoded>>> = Rc::default();
+    let (definition, values) = (definition.clone(), decoded.clone());
+    let sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        if let SimConnectRecv::SimObjectData(data) = recv {
+            let definition = definition.borrow();
+            let decoded = definition.decode(data).and_then(|values| {
+                let map = definition.decode_map(data)?;
+                Ok((values, map))
+            });
+            values.borrow_mut().push(decoded);
+        }
+    })
+    .unwrap();
+    (sim, decoded)
+}
+
+#[test]
+fn dynamic() {
+    let server = MockServer::new();
+    let definition = Rc::new(RefCell::new(DynamicDataDefinition::new()));
+    definition
+        .borrow_mut()
+        .add("PLANE ALTITUDE", "feet", DataType::Float64)
+        .add("GEAR HANDLE POSITION", "bool", DataType::Int32)
+        .add("ATC ID", "", DataType::String32);
+    let (mut sim, decoded) = open(&server, &definition);
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 100.0);
+    server.set_simvar(ObjectId::USER, "GEAR HANDLE POSITION", 1.0);
+    server.set_simvar_string(ObjectId::USER, "ATC ID", "N123");
+
+    // It can't be requested before it is added.
+    assert!(
+        sim.request_dynamic_data_on_sim_object(
+            RequestId(1),
+            &definition.borrow(),
+            ObjectId::USER,
+            Period::Once,
+        )
+        .is_err()
+    );
+
+    sim.add_dynamic_data_definition(&mut definition.borrow_mut())
+        .unwrap();
+    assert_eq!(definition.borrow().define_id(), Some(DefineId(0)));
+    let _handle = sim
+        .request_dynamic_data_on_sim_object(
+            RequestId(1),
+            &definition.borrow(),
+            ObjectId::USER,
+            Period::Once,
+        )
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    let (values, map) = decoded.borrow_mut().remove(0).unwrap();
+    assert!(matches!(
+        values[..],
+        [Value::Float64(100.0), Value::Int32(1), _]
+    ));
+    assert_eq!(values[2].as_str(), Some("N123"));
+    assert_eq!(map["ATC ID"].as_str(), Some("N123"));
+    assert_eq!(map["PLANE ALTITUDE"].as_f64(), Some(100.0));
+    assert_eq!(map.len(), 3);
+
+    // Redefined under the same ID.
+    definition.borrow_mut().clear();
+    definition
+        .borrow_mut()
+        .add("GEAR HANDLE POSITION", "bool", DataType::Float32);
+    sim.add_dynamic_data_definition(&mut definition.borrow_mut())
+        .unwrap();
+    assert_eq!(definition.borrow().define_id(), Some(DefineId(0)));
+    let _handle = sim
+        .request_dynamic_data_on_sim_object(
+            RequestId(2),
+            &definition.borrow(),
+            ObjectId::USER,
+            Period::Once,
+        )
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    let (values, _) = decoded.borrow_mut().remove(0).unwrap();
+    assert!(matches!(values[..], [Value::Float32(1.0)]));
+}
+
+#[test]
+fn request_options() {
+    let server = MockServer::new();
+    let definition = Rc::new(RefCell::new(DynamicDataDefinition::new()));
+    definition
+        .borrow_mut()
+        .add("PLANE ALTITUDE", "feet", DataType::Float64);
+    let (mut sim, decoded) = open(&server, &definition);
+    sim.add_dynamic_data_definition(&mut definition.borrow_mut())
+        .unwrap();
+
+    // Sent every frame even though it doesn't change, twice.
+    let options = RequestOptions::new(Period::SimFrame)
+        .with_changed(false)
+        .with_limit(2);
+    let _handle = sim
+        .request_dynamic_data_on_sim_object_with(
+            RequestId(1),
+            &definition.borrow(),
+            ObjectId::USER,
+            options,
+        )
+        .unwrap();
+    for _ in 0..4 {
+        sim.call_dispatch().unwrap();
+    }
+    assert_eq!(decoded.borrow().len(), 2);
+    assert!(decoded.borrow().iter().all(Result::is_ok));
+}
+
+#[test]
+fn decode_errors() {
+    let server = MockServer::new();
+    let definition = Rc::new(RefCell::new(DynamicDataDefinition::new()));
+    definition
+        .borrow_mut()
+        .add("PLANE ALTITUDE", "feet", DataType::Float64);
+    let (mut sim, decoded) = open(&server, &definition);
+
+    // Data for another definition.
+    let mut other = DynamicDataDefinition::new();
+    other.add("PLANE ALTITUDE", "feet", DataType::Float64);
+    sim.add_dynamic_data_definition(&mut other).unwrap();
+    sim.add_dynamic_data_definition(&mut definition.borrow_mut())
+        .unwrap();
+    let _handle = sim
+        .request_dynamic_data_on_sim_object(RequestId(1), &other, ObjectId::USER, Period::Once)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        decoded.borrow_mut().remove(0).err(),
+        Some(DecodeError::DefinitionMismatch {
+            expected: DefineId(1),
+            received: DefineId(0)
+        })
+    );
+
+    // Tagged data.
+    let options = RequestOptions::new(Period::Once).with_tagged(true);
+    let _handle = sim
+        .request_dynamic_data_on_sim_object_with(
+            RequestId(2),
+            &definition.borrow(),
+            ObjectId::USER,
+            options,
+        )
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        decoded.borrow_mut().remove(0).err(),
+        Some(DecodeError::Tagged)
+    );
+
+    // A simvar added locally, but not to the session.
+    let _handle = sim
+        .request_dynamic_data_on_sim_object(
+            RequestId(3),
+            &definition.borrow(),
+            ObjectId::USER,
+            Period::Once,
+        )
+        .unwrap();
+    definition
+        .borrow_mut()
+        .add("GEAR HANDLE POSITION", "bool", DataType::Int32);
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        decoded.borrow_mut().remove(0).err(),
+        Some(DecodeError::DefineCountMismatch {
+            expected: 2,
+            received: 1
+        })
+    );
+
+    // A definition which was never added.
+    let unregistered = Rc::new(RefCell::new(DynamicDataDefinition::new()));
+    let (mut sim, decoded) = open(&server, &unregistered);
+    let _handle = sim
+        .request_data_on_sim_object_with::<Altitude>(
+            RequestId(1),
+            ObjectId::USER,
+            RequestOptions::new(Period::Once),
+        )
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert!(matches!(
+        decoded.borrow_mut().remove(0).err(),
+        Some(DecodeError::Unregistered { .. })
+    ));
+}
@@ -0,0 +1,87 @@
+use msfs::sim_connect::{
+    ClientEventId, Exception, ExceptionKind, MockServer, ObjectId, SimConnect, SimConnectRecv,
+};
+use msfs::sys;
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+
+fn open(server: &MockServer) -> (Pin<Box<SimConnect<'static>>>, Rc<RefCell<Vec<Exception>>>) {
+    let exceptions: Rc<RefCell<Vec<Exception>>> = Rc::default();
+    let received = exceptions.clone();
+    let sim = SimConnect::open_with_backend(server.backend(), move |sim, recv| {
+        if let SimConnectRecv::Exception(exception) = recv {
+            received.borrow_mut().push(exception.decode(sim));
+        }
+    })
+    .unwrap();
+    (sim, exceptions)
+}
+
+#[test]
+fn traced_to_call() {
+    let server = MockServer::new();
+    let (mut sim, exceptions) = open(&server);
+    server.fail_next(
+        sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_NAME_UNRECOGNIZED,
+        2,
+    );
+    sim.map_client_event_to_sim_event("NOT_AN_EVENT", false)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+
+    let exception = exceptions.borrow_mut().remove(0);
+    assert_eq!(exception.kind, ExceptionKind::NameUnrecognized);
+    assert_eq!(exception.index, 2);
+    let call = exception.call.as_ref().unwrap();
+    assert_eq!(call.packet_id, exception.send_id);
+    assert_eq!(call.method, "map_client_event_to_sim_event");
+    assert_eq!(call.call, "MapClientEventToSimEvent");
+    assert_eq!(
+        exception.to_string(),
+        "NAME_UNRECOGNIZED from `map_client_event_to_sim_event` \
+         (parameter 2 of MapClientEventToSimEvent)"
+    );
+}
+
+#[test]
+fn forgotten_call() {
+    let server = MockServer::new();
+    let (mut sim, exceptions) = open(&server);
+    server.fail_next(sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_ERROR, 0);
+    sim.transmit_client_event(ObjectId::USER, ClientEventId(0), 0)
+        .unwrap();
+    // Enough calls to push the failed one out of the history.
+    for _ in 0..1024 {
+        sim.transmit_client_event(ObjectId::USER, ClientEventId(0), 0)
+            .unwrap();
+    }
+    sim.call_dispatch().unwrap();
+
+    let exception = exceptions
+        .borrow_mut()
+        .drain(..)
+        .find(|exception| exception.kind == ExceptionKind::Error)
+        .unwrap();
+    assert!(exception.call.is_none());
+    assert_eq!(
+        exception.to_string(),
+        format!("ERROR from packet {}", exception.send_id)
+    );
+}
+
+#[test]
+fn kinds() {
+    let kind = |exception: sys::SIMCONNECT_EXCEPTION| ExceptionKind::from(exception as u32);
+    assert_eq!(
+        kind(sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_UNRECOGNIZED_ID),
+        ExceptionKind::UnrecognizedId
+    );
+    assert_eq!(
+        kind(sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_SET_INPUT_EVENT_FAILED),
+        ExceptionKind::SetInputEventFailed
+    );
+    assert_eq!(ExceptionKind::OutOfBounds.to_string(), "OUT_OF_BOUNDS");
+    assert_eq!(ExceptionKind::from(9999), ExceptionKind::Unknown(9999));
+    assert_eq!(ExceptionKind::Unknown(9999).to_string(), "UNKNOWN(9999)");
+}
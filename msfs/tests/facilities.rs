@@ -0,0 +1,156 @@
+use futures::StreamExt;
+use msfs::sim_connect::{
+    Airport, FacilityEvent, MockServer, Ndb, SimConnect, SimConnectRecv, Vor, Waypoint,
+};
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+
+fn airport(i: usize) -> Airport {
+    Airport {
+        ident: format!("K{i:03}"),
+        region: "K7".into(),
+        latitude: 40.0 + i as f64,
+        longitude: -70.0,
+        altitude: 10.0,
+    }
+}
+
+type Unrouted = Rc<RefCell<Vec<String>>>;
+
+/// Open a session which records every message that isn't routed to a handle.
+fn open(server: &MockServer) -> (Pin<Box<SimConnect<'static>>>, Unrouted) {
+    let unrouted = Unrouted::default();
+    let messages = unrouted.clone();
+    let sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        if !matches!(recv, SimConnectRecv::Open(_)) {
+            messages.borrow_mut().push(format!("{recv:?}"));
+        }
+    })
+    .unwrap();
+    (sim, unrouted)
+}
+
+#[test]
+fn lists_and_subscriptions() {
+    let server = MockServer::new();
+    let (mut sim, unrouted) = open(&server);
+    for i in 0..9 {
+        server.add_facility(&airport(i));
+    }
+    server.add_facility(&Waypoint {
+        ident: "LONGWAYPT".into(),
+        region: "ED".into(),
+        latitude: 1.0,
+        longitude: 2.0,
+        altitude: 0.0,
+        mag_var: 3.5,
+    });
+    let airports = sim.request_facilities_list::<Airport>().unwrap();
+    let vors = sim.request_facilities_list_ex1::<Vor>().unwrap();
+    let waypoints = sim.request_facilities_list::<Waypoint>().unwrap();
+    sim.call_dispatch().unwrap();
+    let mut ndbs = sim.subscribe_to_facilities::<Ndb>().unwrap();
+    let mut in_range = sim.subscribe_to_facilities_ex1::<Airport>().unwrap();
+    sim.call_dispatch().unwrap();
+    assert!(unrouted.borrow().is_empty(), "{:?}", unrouted.borrow());
+
+    futures::executor::block_on(async {
+        // Nine airports span three pages.
+        assert_eq!(
+            airports.await.unwrap(),
+            (0..9).map(airport).collect::<Vec<_>>()
+        );
+        // An empty page has no room for a facility.
+        assert!(vors.await.unwrap().is_empty());
+        let waypoints = waypoints.await.unwrap();
+        assert_eq!(waypoints[0].ident, "LONGW");
+        assert_eq!(waypoints[0].mag_var, 3.5);
+        for i in 0..9 {
+            assert_eq!(
+                in_range.next().await,
+                Some(FacilityEvent::InRange(airport(i)))
+            );
+        }
+    });
+
+    server.add_facility(&airport(9));
+    server.remove_facility::<Airport>("K003", "K7");
+    let vor = Vor {
+        ident: "BOS".into(),
+        region: "K6".into(),
+        latitude: 42.0,
+        longitude: -71.0,
+        altitude: 5.0,
+        mag_var: -14.0,
+        frequency: 112_700_000,
+        flags: 9,
+        localizer: 0.0,
+        glide_lat: 0.0,
+        glide_lon: 0.0,
+        glide_alt: 0.0,
+        glide_slope_angle: 0.0,
+    };
+    server.add_facility(&vor);
+    let ndb = Ndb {
+        ident: "AB".into(),
+        region: "K6".into(),
+        latitude: 1.0,
+        longitude: 1.0,
+        altitude: 1.0,
+        mag_var: 0.0,
+        frequency: 350_000,
+    };
+    server.add_facility(&ndb);
+    let vors = sim.request_facilities_list::<Vor>().unwrap();
+    sim.call_dispatch().unwrap();
+    assert!(unrouted.borrow().is_empty(), "{:?}", unrouted.borrow());
+    futures::executor::block_on(async {
+        assert_eq!(
+            in_range.next().await,
+            Some(FacilityEvent::InRange(airport(9)))
+        );
+        assert_eq!(
+            in_range.next().await,
+            Some(FacilityEvent::OutOfRange(airport(3)))
+        );
+        assert_eq!(ndbs.next().await, Some(FacilityEvent::InRange(ndb.clone())));
+        let vors = vors.await.unwrap();
+        assert_eq!(vors, vec![vor]);
+        assert!(vors[0].has_nav_signal() && vors[0].has_dme() && !vors[0].has_localizer());
+    });
+
+    // Nothing is sent once the subscriptions are dropped.
+    drop(in_range);
+    drop(ndbs);
+    sim.call_dispatch().unwrap();
+    server.add_facility(&airport(10));
+    server.add_facility(&ndb);
+    sim.call_dispatch().unwrap();
+    assert!(unrouted.borrow().is_empty(), "{:?}", unrouted.borrow());
+}
+
+#[test]
+fn one_subscription_per_type() {
+    let server = MockServer::new();
+    let (mut sim, unrouted) = open(&server);
+    let first = sim.subscribe_to_facilities::<Airport>().unwrap();
+    assert!(sim.subscribe_to_facilities::<Airport>().is_err());
+    assert!(sim.subscribe_to_facilities_ex1::<Airport>().is_err());
+    // Other types are separate.
+    let _vors = sim.subscribe_to_facilities::<Vor>().unwrap();
+    sim.call_dispatch().unwrap();
+
+    // Subscribing again before the dropped subscription is stopped keeps the
+    // new one.
+    drop(first);
+    let mut second = sim.subscribe_to_facilities_ex1::<Airport>().unwrap();
+    sim.call_dispatch().unwrap();
+    server.add_facility(&airport(1));
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        futures::executor::block_on(second.next()),
+        Some(FacilityEvent::InRange(airport(1)))
+    );
+    assert!(unrouted.borrow().is_empty(), "{:?}", unrouted.borrow());
+}
@@ -0,0 +1,152 @@
+use msfs::sim_connect::{
+    self, DecodeError, ExceptionKind, MockFacilityBlock, MockServer, RequestError, SimConnect,
+    SimConnectRecv, String8, String64, facility_definition,
+};
+use msfs::sys;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[facility_definition(AIRPORT)]
+#[derive(Debug)]
+struct Airport {
+    latitude: f64,
+    icao: String8,
+    #[name = "NAME64"]
+    name: sim_connect::String64,
+    n_runways: i32,
+    runways: Vec<Runway>,
+    frequencies: Vec<Frequency>,
+}
+
+#[facility_definition(RUNWAY)]
+#[derive(Debug)]
+struct Runway {
+    heading: f32,
+    #[name = "PRIMARY_THRESHOLD"]
+    primary_threshold: Option<Pavement>,
+    #[name = "SECONDARY_THRESHOLD"]
+    secondary_threshold: Pavement,
+}
+
+/// An airport read for its runways alone, with no fields of its own.
+#[facility_definition(AIRPORT)]
+#[derive(Debug)]
+struct Runways {
+    runways: Vec<Runway>,
+}
+
+#[facility_definition(PAVEMENT)]
+#[derive(Debug)]
+struct Pavement {
+    length: f32,
+}
+
+#[facility_definition(FREQUENCY)]
+#[derive(Debug)]
+struct Frequency {
+    frequency: i32,
+}
+
+fn runway(heading: f32, primary: f32, secondary: f32) -> MockFacilityBlock {
+    MockFacilityBlock::new(
+        "RUNWAY",
+        sys::SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_RUNWAY,
+    )
+    .with_field("HEADING", heading)
+    .with_child(
+        MockFacilityBlock::new(
+            "PRIMARY_THRESHOLD",
+            sys::SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_PAVEMENT,
+        )
+        .with_field("LENGTH", primary),
+    )
+    .with_child(
+        MockFacilityBlock::new(
+            "SECONDARY_THRESHOLD",
+            sys::SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_PAVEMENT,
+        )
+        .with_field("LENGTH", secondary),
+    )
+}
+
+#[test]
+fn facility_data() {
+    let server = MockServer::new();
+    let unrouted: Rc<RefCell<Vec<String>>> = Rc::default();
+    let messages = unrouted.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        if !matches!(recv, SimConnectRecv::Open(_)) {
+            messages.borrow_mut().push(format!("{recv:?}"));
+        }
+    })
+    .unwrap();
+    let airport = MockFacilityBlock::new(
+        "AIRPORT",
+        sys::SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_AIRPORT,
+    )
+    .with_field("LATITUDE", 47.45f64)
+    .with_field("ICAO", String8::new("KSEA"))
+    .with_field("NAME64", String64::new("Seattle-Tacoma Intl"))
+    .with_field("N_RUNWAYS", 2i32)
+    .with_item(runway(160.0, 100.0, 200.0))
+    .with_item(
+        MockFacilityBlock::new(
+            "FREQUENCY",
+            sys::SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_FREQUENCY,
+        )
+        .with_field("FREQUENCY", 119_900_000i32),
+    )
+    .with_item(runway(340.0, 300.0, 400.0));
+    server.add_facility_data("KSEA", "K1", airport);
+    server.add_facility_data(
+        "KBFI",
+        "K1",
+        MockFacilityBlock::new(
+            "AIRPORT",
+            sys::SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_AIRPORT,
+        )
+        .with_field("LATITUDE", 47.5f64),
+    );
+
+    let ksea = sim.request_facility_data::<Airport>("KSEA", None).unwrap();
+    let wrong_region = sim
+        .request_facility_data::<Airport>("KSEA", Some("ED"))
+        .unwrap();
+    let incomplete = sim.request_facility_data::<Airport>("KBFI", None).unwrap();
+    let runways = sim.request_facility_data::<Runways>("KSEA", None).unwrap();
+    sim.call_dispatch().unwrap();
+    assert!(unrouted.borrow().is_empty(), "{:?}", unrouted.borrow());
+
+    futures::executor::block_on(async {
+        let ksea = ksea.await.unwrap();
+        assert_eq!(ksea.latitude, 47.45);
+        assert_eq!(ksea.icao.to_string(), "KSEA");
+        assert_eq!(ksea.name.to_string(), "Seattle-Tacoma Intl");
+        assert_eq!(ksea.n_runways, 2);
+        assert_eq!(ksea.runways.len(), 2);
+        assert_eq!(ksea.runways[0].heading, 160.0);
+        assert_eq!(
+            ksea.runways[0].primary_threshold.as_ref().unwrap().length,
+            100.0
+        );
+        assert_eq!(ksea.runways[0].secondary_threshold.length, 200.0);
+        assert_eq!(ksea.runways[1].heading, 340.0);
+        assert_eq!(ksea.runways[1].secondary_threshold.length, 400.0);
+        assert_eq!(ksea.frequencies.len(), 1);
+        assert_eq!(ksea.frequencies[0].frequency, 119_900_000);
+        match wrong_region.await {
+            Err(RequestError::Decode(DecodeError::MissingBlock { block: "AIRPORT" })) => {}
+            other => panic!("{other:?}"),
+        }
+        match incomplete.await {
+            Err(RequestError::Exception(e)) => {
+                assert_eq!(e.kind, ExceptionKind::DataError);
+                assert_eq!(e.call.unwrap().method, "request_facility_data");
+            }
+            other => panic!("{other:?}"),
+        }
+        let runways = runways.await.unwrap().runways;
+        assert_eq!(runways.len(), 2);
+        assert_eq!(runways[1].primary_threshold.as_ref().unwrap().length, 300.0);
+    });
+}
@@ -0,0 +1,54 @@
+use msfs::sim_connect::{MockServer, NotificationGroupId, Priority, SimConnect, SimConnectRecv};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn notification_groups() {
+    let server = MockServer::new();
+    let log: Rc<RefCell<Vec<(u32, u32)>>> = Rc::default();
+    let events = log.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        if let SimConnectRecv::Event(event) = recv {
+            events.borrow_mut().push(({ event.uGroupID }, event.id().0));
+        }
+    })
+    .unwrap();
+    let autopilot = sim.map_client_event("AP_MASTER").unwrap();
+    let heading = sim.map_client_event("HEADING_BUG_INC").unwrap();
+    let autopilot_again = sim.map_client_event("AP_MASTER").unwrap();
+    let (high, low) = (NotificationGroupId(5), NotificationGroupId(2));
+    sim.add_client_event_to_notification_group(low, heading, false)
+        .unwrap();
+    sim.add_client_event_to_notification_group(low, autopilot, false)
+        .unwrap();
+    sim.add_client_event_to_notification_group(low, autopilot_again, false)
+        .unwrap();
+    sim.set_notification_group_priority(low, Priority::LOWEST)
+        .unwrap();
+    // Moving an event into another group.
+    sim.add_client_event_to_notification_group(high, autopilot, true)
+        .unwrap();
+    sim.set_notification_group_priority(high, Priority::HIGHEST_MASKABLE)
+        .unwrap();
+    sim.request_notification_group(high).unwrap();
+    sim.call_dispatch().unwrap();
+
+    // The higher priority group is notified first.
+    server.send_event("AP_MASTER", 1);
+    server.send_event("HEADING_BUG_INC", 2);
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        *log.borrow(),
+        [(5, autopilot.0), (2, autopilot_again.0), (2, heading.0)]
+    );
+    log.borrow_mut().clear();
+
+    // Nothing is left in either group.
+    sim.remove_client_event(high, autopilot).unwrap();
+    sim.clear_notification_group(low).unwrap();
+    sim.call_dispatch().unwrap();
+    server.send_event("AP_MASTER", 1);
+    server.send_event("HEADING_BUG_INC", 2);
+    sim.call_dispatch().unwrap();
+    assert!(log.borrow().is_empty());
+}
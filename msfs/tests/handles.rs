@@ -0,0 +1,107 @@
+use msfs::sim_connect::{
+    MockServer, ObjectId, Period, RequestId, SimConnect, SimConnectRecv, client_data_definition,
+    data_definition,
+};
+use msfs::sys;
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+
+#[data_definition]
+#[derive(Debug, Clone, Copy)]
+struct Altitude {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+#[client_data_definition]
+#[derive(Debug, Clone, Copy)]
+struct Shared {
+    value: f64,
+}
+
+const AIRCRAFT: sys::SIMCONNECT_SIMOBJECT_TYPE =
+    sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT;
+
+/// Open a session which logs the data and object events it receives.
+fn open(server: &MockServer) -> (Pin<Box<SimConnect<'static>>>, Rc<RefCell<Vec<String>>>) {
+    let log: Rc<RefCell<Vec<String>>> = Rc::default();
+    let messages = log.clone();
+    let sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        let message = match recv {
+            SimConnectRecv::SimObjectData(data) => format!("data {}", data.id()),
+            SimConnectRecv::ClientData(data) => format!("client data {}", data.id()),
+            SimConnectRecv::EventObjectAddRemove(event) => format!("event {}", event.id()),
+            _ => return,
+        };
+        messages.borrow_mut().push(message);
+    })
+    .unwrap();
+    (sim, log)
+}
+
+#[test]
+fn data_requests() {
+    let server = MockServer::new();
+    let (mut sim, log) = open(&server);
+    let mut request = |request_id| {
+        sim.request_data_on_sim_object::<Altitude>(
+            RequestId(request_id),
+            ObjectId::USER,
+            Period::SimFrame,
+        )
+        .unwrap()
+    };
+    let (dropped, cancelled, forgotten) = (request(1), request(2), request(3));
+    assert_eq!(dropped.id(), RequestId(1));
+    sim.call_dispatch().unwrap();
+    assert_eq!(*log.borrow(), ["data 1", "data 2", "data 3"]);
+
+    drop(dropped);
+    cancelled.cancel(&mut sim).unwrap();
+    assert_eq!(forgotten.forget(), RequestId(3));
+    sim.call_dispatch().unwrap();
+    log.borrow_mut().clear();
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 10.0);
+    sim.call_dispatch().unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(*log.borrow(), ["data 3"]);
+}
+
+#[test]
+fn client_data_requests() {
+    let server = MockServer::new();
+    let (mut sim, log) = open(&server);
+    let area = sim.create_client_data::<Shared>("SHARED").unwrap();
+    let handle = sim
+        .request_client_data::<Shared>(RequestId(4), "SHARED")
+        .unwrap();
+    sim.set_client_data(&area, &Shared { value: 1.0 }).unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(*log.borrow(), ["client data 4"]);
+
+    drop(handle);
+    sim.call_dispatch().unwrap();
+    sim.set_client_data(&area, &Shared { value: 2.0 }).unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(log.borrow().len(), 1);
+}
+
+#[test]
+fn system_events() {
+    let server = MockServer::new();
+    let (mut sim, log) = open(&server);
+    let handle = sim.subscribe_to_system_event("ObjectAdded").unwrap();
+    let event_id = handle.id();
+    sim.call_dispatch().unwrap();
+    server.add_object(ObjectId(5), AIRCRAFT);
+    sim.call_dispatch().unwrap();
+    assert_eq!(*log.borrow(), [format!("event {event_id}")]);
+
+    drop(handle);
+    sim.call_dispatch().unwrap();
+    server.add_object(ObjectId(6), AIRCRAFT);
+    sim.call_dispatch().unwrap();
+    assert_eq!(log.borrow().len(), 1);
+}
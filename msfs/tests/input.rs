@@ -0,0 +1,87 @@
+use msfs::sim_connect::{
+    InputGroupId, InputMapping, MockServer, Priority, SimConnect, SimConnectRecv,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn input_groups() {
+    let server = MockServer::new();
+    let log: Rc<RefCell<Vec<(u32, u32, u32)>>> = Rc::default();
+    let keys: Rc<RefCell<Vec<(String, String)>>> = Rc::default();
+    let (events, reserved) = (log.clone(), keys.clone());
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| match recv {
+        SimConnectRecv::Event(event) => {
+            events
+                .borrow_mut()
+                .push(({ event.uGroupID }, event.id().0, event.data()))
+        }
+        SimConnectRecv::ReservedKey(key) => reserved.borrow_mut().push((
+            key.choice_reserved().into_owned(),
+            key.reserved_key().into_owned(),
+        )),
+        _ => {}
+    })
+    .unwrap();
+    let brakes = sim.map_client_event("BRAKES").unwrap();
+    let toggle = sim.map_client_event("CUSTOM").unwrap();
+    let (brake_keys, custom_keys) = (InputGroupId(1), InputGroupId(2));
+    sim.map_input_event_to_client_event(
+        brake_keys,
+        "shift+B",
+        InputMapping::new(brakes, 1).with_up(brakes, 0),
+    )
+    .unwrap();
+    sim.map_input_event_to_client_event(
+        custom_keys,
+        "Shift+b",
+        InputMapping::new(toggle, 7).with_maskable(true),
+    )
+    .unwrap();
+    sim.set_input_group_priority(custom_keys, Priority::HIGHEST)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+
+    // Groups are off until turned on.
+    server.press_input("shift+b");
+    sim.call_dispatch().unwrap();
+    assert!(log.borrow().is_empty());
+
+    sim.set_input_group_state(brake_keys, true).unwrap();
+    sim.set_input_group_state(custom_keys, true).unwrap();
+    sim.call_dispatch().unwrap();
+    // Key names are matched regardless of case, and the higher priority group
+    // is notified first.
+    server.press_input("SHIFT+B");
+    server.release_input("shift+b");
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        *log.borrow(),
+        [(2, toggle.0, 7), (1, brakes.0, 1), (1, brakes.0, 0)]
+    );
+    log.borrow_mut().clear();
+
+    sim.remove_input_event(custom_keys, "shift+b").unwrap();
+    sim.clear_input_group(brake_keys).unwrap();
+    sim.call_dispatch().unwrap();
+    server.press_input("shift+b");
+    sim.call_dispatch().unwrap();
+    assert!(log.borrow().is_empty());
+
+    // The second request is given the alternative key.
+    sim.request_reserved_key(toggle, "ctrl+Q", Some("ctrl+W"), None)
+        .unwrap();
+    sim.request_reserved_key(brakes, "ctrl+Q", Some("ctrl+W"), None)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        *keys.borrow(),
+        [
+            ("ctrl+Q".into(), "ctrl+Q".into()),
+            ("ctrl+W".into(), "ctrl+W".into())
+        ]
+    );
+    server.press_input("ctrl+w");
+    sim.call_dispatch().unwrap();
+    assert_eq!(log.borrow()[0].1, brakes.0);
+}
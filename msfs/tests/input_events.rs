@@ -0,0 +1,113 @@
+use futures::{FutureExt, StreamExt};
+use msfs::sim_connect::{
+    ExceptionKind, InputEventHash, InputEventType, InputEventValue, MockServer, RequestError,
+    SimConnect, SimConnectRecv,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn input_events() {
+    let server = MockServer::new();
+    let unrouted: Rc<RefCell<usize>> = Rc::default();
+    let messages = unrouted.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        if !matches!(recv, SimConnectRecv::Open(_)) {
+            *messages.borrow_mut() += 1;
+        }
+    })
+    .unwrap();
+    for i in 0..6 {
+        server.add_input_event(&format!("KNOB_{i}"), i as f64, "FLOAT64");
+    }
+    server.add_input_event("RADIO_ID", "ABC", "STRING");
+
+    // Names are unknown before enumeration.
+    assert!(sim.get_input_event("KNOB_1").is_err());
+
+    let list = sim.enumerate_input_events().unwrap();
+    sim.call_dispatch().unwrap();
+    let list = futures::executor::block_on(list).unwrap();
+    assert_eq!(list.len(), 7);
+    assert_eq!(list[6].name, "RADIO_ID");
+    assert_eq!(list[6].r#type, InputEventType::String);
+    let knob = sim.input_event("KNOB_3").unwrap().clone();
+    assert_eq!(knob.r#type, InputEventType::Double);
+
+    let knob_by_name = sim.get_input_event("KNOB_3").unwrap();
+    let knob_by_hash = sim.get_input_event(knob.hash).unwrap();
+    let radio = sim.get_input_event("RADIO_ID").unwrap();
+    let unknown = sim.get_input_event(InputEventHash(42)).unwrap();
+    let knob_params = sim.enumerate_input_event_params("KNOB_1").unwrap();
+    let radio_params = sim.enumerate_input_event_params("RADIO_ID").unwrap();
+    let knob_params_again = sim.enumerate_input_event_params("KNOB_1").unwrap();
+    sim.call_dispatch().unwrap();
+    futures::executor::block_on(async {
+        assert_eq!(knob_by_name.await.unwrap(), InputEventValue::Double(3.0));
+        assert_eq!(knob_by_hash.await.unwrap(), InputEventValue::Double(3.0));
+        assert_eq!(radio.await.unwrap(), InputEventValue::String("ABC".into()));
+        match unknown.await {
+            Err(RequestError::Exception(exception)) => {
+                assert_eq!(exception.kind, ExceptionKind::GetInputEventFailed)
+            }
+            other => panic!("{other:?}"),
+        }
+        assert_eq!(knob_params.await.unwrap(), "FLOAT64");
+        assert_eq!(radio_params.await.unwrap(), "STRING");
+        assert_eq!(knob_params_again.await.unwrap(), "FLOAT64");
+    });
+
+    sim.set_input_event("KNOB_2", 7.5).unwrap();
+    sim.set_input_event("RADIO_ID", "XYZW1").unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        server.input_event("KNOB_2"),
+        Some(InputEventValue::Double(7.5))
+    );
+    assert_eq!(
+        server.input_event("RADIO_ID"),
+        Some(InputEventValue::String("XYZW1".into()))
+    );
+
+    let mut first = sim.subscribe_input_event("KNOB_2").unwrap();
+    let mut second = sim.subscribe_input_event("KNOB_2").unwrap();
+    let mut radio = sim.subscribe_input_event("RADIO_ID").unwrap();
+    sim.call_dispatch().unwrap();
+    assert!(server.input_event_subscribed("KNOB_2"));
+    server.set_input_event("KNOB_2", 1.0);
+    sim.set_input_event("RADIO_ID", "Q").unwrap();
+    sim.call_dispatch().unwrap();
+    futures::executor::block_on(async {
+        assert_eq!(first.next().await, Some(InputEventValue::Double(1.0)));
+        assert_eq!(second.next().await, Some(InputEventValue::Double(1.0)));
+        assert_eq!(
+            radio.next().await,
+            Some(InputEventValue::String("Q".into()))
+        );
+    });
+    // The event stays subscribed while another stream is open.
+    drop(first);
+    sim.call_dispatch().unwrap();
+    assert!(server.input_event_subscribed("KNOB_2"));
+    server.set_input_event("KNOB_2", 2.0);
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        second.next().now_or_never(),
+        Some(Some(InputEventValue::Double(2.0)))
+    );
+    drop(second);
+    sim.call_dispatch().unwrap();
+    assert!(!server.input_event_subscribed("KNOB_2"));
+    assert!(server.input_event_subscribed("RADIO_ID"));
+    assert_eq!(*unrouted.borrow(), 0);
+}
+
+/// A single empty page, with no padding for the missing element.
+#[test]
+fn empty_enumeration() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    let list = sim.enumerate_input_events().unwrap();
+    sim.call_dispatch().unwrap();
+    assert!(futures::executor::block_on(list).unwrap().is_empty());
+}
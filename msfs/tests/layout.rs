@@ -0,0 +1,111 @@
+//! The sizes and offsets of the SimConnect structs, as declared in
+//! `SimConnect.h` with `#pragma pack(1)`. Messages are read from and written
+//! to the wire with these layouts, so the pre-generated bindings used without
+//! the SDK must agree with the header.
+
+use msfs::sys::*;
+use std::mem::{offset_of, size_of};
+
+#[test]
+fn messages() {
+    assert_eq!(size_of::<SIMCONNECT_RECV>(), 12);
+    assert_eq!(offset_of!(SIMCONNECT_RECV, dwID), 8);
+
+    assert_eq!(size_of::<SIMCONNECT_RECV_EXCEPTION>(), 24);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_EXCEPTION, dwSendID), 16);
+    assert_eq!(size_of::<SIMCONNECT_RECV_OPEN>(), 308);
+    assert_eq!(
+        offset_of!(SIMCONNECT_RECV_OPEN, dwApplicationVersionMajor),
+        268
+    );
+    assert_eq!(size_of::<SIMCONNECT_RECV_QUIT>(), 12);
+    assert_eq!(size_of::<SIMCONNECT_RECV_ASSIGNED_OBJECT_ID>(), 20);
+    assert_eq!(size_of::<SIMCONNECT_RECV_RESERVED_KEY>(), 92);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_RESERVED_KEY, szReservedKey), 42);
+    assert_eq!(size_of::<SIMCONNECT_RECV_SYSTEM_STATE>(), 284);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_SYSTEM_STATE, szString), 24);
+}
+
+#[test]
+fn events() {
+    assert_eq!(size_of::<SIMCONNECT_RECV_EVENT>(), 24);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_EVENT, dwData), 20);
+    assert_eq!(size_of::<SIMCONNECT_RECV_EVENT_EX1>(), 40);
+    assert_eq!(size_of::<SIMCONNECT_RECV_EVENT_FILENAME>(), 288);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_EVENT_FILENAME, dwFlags), 284);
+    assert_eq!(size_of::<SIMCONNECT_RECV_EVENT_OBJECT_ADDREMOVE>(), 28);
+    assert_eq!(size_of::<SIMCONNECT_RECV_EVENT_FRAME>(), 32);
+    assert_eq!(
+        size_of::<SIMCONNECT_RECV_EVENT_MULTIPLAYER_SERVER_STARTED>(),
+        24
+    );
+    assert_eq!(size_of::<SIMCONNECT_DATA_RACE_RESULT>(), 1080);
+    assert_eq!(offset_of!(SIMCONNECT_DATA_RACE_RESULT, MissionGUID), 4);
+    assert_eq!(offset_of!(SIMCONNECT_DATA_RACE_RESULT, fTotalTime), 1060);
+    assert_eq!(size_of::<SIMCONNECT_RECV_EVENT_RACE_END>(), 1108);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_EVENT_RACE_END, RacerData), 28);
+    assert_eq!(size_of::<SIMCONNECT_RECV_EVENT_RACE_LAP>(), 1108);
+}
+
+#[test]
+fn data() {
+    assert_eq!(size_of::<SIMCONNECT_RECV_SIMOBJECT_DATA>(), 44);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_SIMOBJECT_DATA, dwDefineID), 20);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_SIMOBJECT_DATA, dwFlags), 24);
+    assert_eq!(
+        offset_of!(SIMCONNECT_RECV_SIMOBJECT_DATA, dwDefineCount),
+        36
+    );
+    assert_eq!(offset_of!(SIMCONNECT_RECV_SIMOBJECT_DATA, dwData), 40);
+    assert_eq!(size_of::<SIMCONNECT_RECV_SIMOBJECT_DATA_BYTYPE>(), 44);
+    assert_eq!(size_of::<SIMCONNECT_RECV_CLIENT_DATA>(), 44);
+
+    assert_eq!(size_of::<SIMCONNECT_DATA_INITPOSITION>(), 56);
+    assert_eq!(offset_of!(SIMCONNECT_DATA_INITPOSITION, OnGround), 48);
+    assert_eq!(size_of::<SIMCONNECT_DATA_LATLONALT>(), 24);
+    assert_eq!(size_of::<SIMCONNECT_DATA_XYZ>(), 24);
+    assert_eq!(size_of::<SIMCONNECT_DATA_MARKERSTATE>(), 68);
+    assert_eq!(size_of::<SIMCONNECT_DATA_WAYPOINT>(), 44);
+    assert_eq!(offset_of!(SIMCONNECT_DATA_WAYPOINT, ktsSpeed), 28);
+}
+
+#[test]
+fn facilities() {
+    assert_eq!(size_of::<SIMCONNECT_RECV_FACILITIES_LIST>(), 28);
+    assert_eq!(size_of::<SIMCONNECT_DATA_FACILITY_AIRPORT>(), 33);
+    assert_eq!(offset_of!(SIMCONNECT_DATA_FACILITY_AIRPORT, Latitude), 9);
+    assert_eq!(size_of::<SIMCONNECT_DATA_FACILITY_WAYPOINT>(), 37);
+    assert_eq!(size_of::<SIMCONNECT_DATA_FACILITY_NDB>(), 41);
+    assert_eq!(size_of::<SIMCONNECT_DATA_FACILITY_VOR>(), 77);
+    assert_eq!(offset_of!(SIMCONNECT_DATA_FACILITY_VOR, GlideLat), 49);
+    assert_eq!(size_of::<SIMCONNECT_RECV_AIRPORT_LIST>(), 28 + 33);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_AIRPORT_LIST, rgData), 28);
+    assert_eq!(size_of::<SIMCONNECT_RECV_WAYPOINT_LIST>(), 28 + 37);
+    assert_eq!(size_of::<SIMCONNECT_RECV_NDB_LIST>(), 28 + 41);
+    assert_eq!(size_of::<SIMCONNECT_RECV_VOR_LIST>(), 28 + 77);
+
+    assert_eq!(size_of::<SIMCONNECT_RECV_FACILITY_DATA>(), 44);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_FACILITY_DATA, Type), 24);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_FACILITY_DATA, Data), 40);
+    assert_eq!(size_of::<SIMCONNECT_RECV_FACILITY_DATA_END>(), 16);
+}
+
+#[test]
+fn input_events() {
+    assert_eq!(size_of::<SIMCONNECT_RECV_LIST_TEMPLATE>(), 28);
+    assert_eq!(size_of::<SIMCONNECT_INPUT_EVENT_DESCRIPTOR>(), 76);
+    assert_eq!(offset_of!(SIMCONNECT_INPUT_EVENT_DESCRIPTOR, Hash), 64);
+    assert_eq!(size_of::<SIMCONNECT_RECV_ENUMERATE_INPUT_EVENTS>(), 28 + 76);
+    assert_eq!(size_of::<SIMCONNECT_RECV_GET_INPUT_EVENT>(), 24);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_GET_INPUT_EVENT, Value), 20);
+    assert_eq!(size_of::<SIMCONNECT_RECV_SUBSCRIBE_INPUT_EVENT>(), 28);
+    assert_eq!(offset_of!(SIMCONNECT_RECV_SUBSCRIBE_INPUT_EVENT, Value), 24);
+    assert_eq!(
+        size_of::<SIMCONNECT_RECV_ENUMERATE_INPUT_EVENT_PARAMS>(),
+        280
+    );
+    assert_eq!(
+        offset_of!(SIMCONNECT_RECV_ENUMERATE_INPUT_EVENT_PARAMS, Value),
+        20
+    );
+}
@@ -0,0 +1,231 @@
+use msfs::sim_connect::{
+    DataType, DynamicDataDefinition, Exception, ExceptionKind, MockServer, ObjectId, Period,
+    RequestId, SimConnect, SimConnectRecv, client_data_definition, data_definition,
+};
+use msfs::sys;
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+
+#[data_definition]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Data {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+    #[name = "GEAR HANDLE POSITION"]
+    #[unit = "bool"]
+    gear: f64,
+}
+
+#[client_data_definition]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Shared {
+    a: f64,
+    b: f64,
+}
+
+#[client_data_definition]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Small {
+    a: f64,
+}
+
+#[derive(Debug, PartialEq)]
+enum Message {
+    Open,
+    Data(Data),
+    ClientData(Shared),
+    Exception(ExceptionKind, &'static str),
+    Quit,
+}
+
+type Log = Rc<RefCell<Vec<Message>>>;
+
+fn open(server: &MockServer) -> (Pin<Box<SimConnect<'static>>>, Log) {
+    let log = Log::default();
+    let messages = log.clone();
+    let sim = SimConnect::open_with_backend(server.backend(), move |sim, recv| {
+        let message = match recv {
+            SimConnectRecv::Open(_) => Message::Open,
+            SimConnectRecv::SimObjectData(data) => Message::Data(data.into(sim).unwrap()),
+            SimConnectRecv::ClientData(data) => Message::ClientData(data.into(sim).unwrap()),
+            SimConnectRecv::Exception(exception) => {
+                let Exception { kind, call, .. } = exception.decode(sim);
+                Message::Exception(kind, call.map(|call| call.method).unwrap_or_default())
+            }
+            SimConnectRecv::Quit(_) => Message::Quit,
+            recv => panic!("unexpected {recv:?}"),
+        };
+        messages.borrow_mut().push(message);
+    })
+    .unwrap();
+    (sim, log)
+}
+
+#[test]
+fn sim_object_data() {
+    let server = MockServer::new();
+    let (mut sim, log) = open(&server);
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        log.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [Message::Open]
+    );
+
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 5000.0);
+    let _handle = sim
+        .request_data_on_sim_object::<Data>(RequestId(7), ObjectId::USER, Period::SimFrame)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    // Only changes are sent.
+    sim.call_dispatch().unwrap();
+    server.set_simvar(ObjectId::USER, "GEAR HANDLE POSITION", 1.0);
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        log.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [
+            Message::Data(Data {
+                altitude: 5000.0,
+                gear: 0.0
+            }),
+            Message::Data(Data {
+                altitude: 5000.0,
+                gear: 1.0
+            }),
+        ]
+    );
+
+    sim.set_data_on_sim_object(
+        ObjectId::USER,
+        &Data {
+            altitude: 1.0,
+            gear: 0.0,
+        },
+    )
+    .unwrap();
+    assert_eq!(server.simvar(ObjectId::USER, "PLANE ALTITUDE"), Some(1.0));
+
+    server.quit();
+    sim.call_dispatch().unwrap();
+    assert!(log.borrow().contains(&Message::Quit));
+}
+
+#[test]
+fn client_events() {
+    let server = MockServer::new();
+    let (mut sim, _log) = open(&server);
+    let event = sim
+        .map_client_event_to_sim_event("AP_MASTER", false)
+        .unwrap();
+    sim.transmit_client_event(ObjectId::USER, event, 3).unwrap();
+    let events = server.transmitted_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_name.as_deref(), Some("AP_MASTER"));
+    assert_eq!(events[0].data[0], 3);
+}
+
+#[test]
+fn client_data() {
+    let server = MockServer::new();
+    let (mut sim, log) = open(&server);
+    sim.call_dispatch().unwrap();
+    log.borrow_mut().clear();
+
+    let area = sim.create_client_data::<Shared>("SHARED").unwrap();
+    let _handle = sim
+        .request_client_data::<Shared>(RequestId(9), "SHARED")
+        .unwrap();
+    sim.set_client_data(&area, &Shared { a: 1.0, b: 2.5 })
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        log.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [Message::ClientData(Shared { a: 1.0, b: 2.5 })]
+    );
+    assert_eq!(
+        server.client_data("SHARED").unwrap().len(),
+        std::mem::size_of::<Shared>()
+    );
+}
+
+#[test]
+fn injected_failure() {
+    let server = MockServer::new();
+    let (mut sim, log) = open(&server);
+    sim.call_dispatch().unwrap();
+    log.borrow_mut().clear();
+
+    server.fail_next(sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_ERROR, 4);
+    let event = sim
+        .map_client_event_to_sim_event("AP_MASTER", false)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        log.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [Message::Exception(
+            ExceptionKind::Error,
+            "map_client_event_to_sim_event"
+        )]
+    );
+    // The failed call had no effect.
+    sim.transmit_client_event(ObjectId::USER, event, 3).unwrap();
+    sim.call_dispatch().unwrap();
+    assert!(server.transmitted_events().is_empty());
+    assert!(matches!(
+        log.borrow().last(),
+        Some(Message::Exception(ExceptionKind::UnrecognizedId, _))
+    ));
+}
+
+#[test]
+fn cleared_definition_raises_exception() {
+    let server = MockServer::new();
+    let (mut sim, log) = open(&server);
+    sim.call_dispatch().unwrap();
+    log.borrow_mut().clear();
+
+    let mut definition = DynamicDataDefinition::new();
+    definition.add("PLANE ALTITUDE", "feet", DataType::Float64);
+    sim.add_dynamic_data_definition(&mut definition).unwrap();
+    let _handle = sim
+        .request_dynamic_data_on_sim_object(
+            RequestId(1),
+            &definition,
+            ObjectId::USER,
+            Period::SimFrame,
+        )
+        .unwrap();
+    sim.clear_dynamic_data_definition(&mut definition).unwrap();
+    sim.call_dispatch().unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        log.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [Message::Exception(
+            ExceptionKind::UnrecognizedId,
+            "request_dynamic_data_on_sim_object"
+        )]
+    );
+}
+
+#[test]
+fn client_data_out_of_bounds_raises_exception() {
+    let server = MockServer::new();
+    let (mut sim, log) = open(&server);
+    sim.call_dispatch().unwrap();
+    log.borrow_mut().clear();
+
+    sim.create_client_data::<Small>("SMALL").unwrap();
+    let _handle = sim
+        .request_client_data::<Shared>(RequestId(2), "SMALL")
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        log.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [Message::Exception(
+            ExceptionKind::OutOfBounds,
+            "request_client_data"
+        )]
+    );
+}
@@ -0,0 +1,109 @@
+use msfs::sim_connect::{
+    self, DataDefinition, MockServer, ObjectId, Period, RequestId, SimConnect, SimConnectRecv,
+    data_definition,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[data_definition]
+#[derive(Debug, Clone, PartialEq)]
+struct Engine {
+    #[name = "ENG N1 RPM:{}"]
+    #[unit = "Percent"]
+    n1: f64,
+    #[name = "GENERAL ENG THROTTLE LEVER POSITION:{}"]
+    #[unit = "Percent"]
+    throttle: f64,
+}
+
+#[data_definition]
+#[derive(Debug, Clone, PartialEq)]
+struct Light {
+    #[name = "LIGHT ON"]
+    #[unit = "Bool"]
+    on: bool,
+}
+
+#[data_definition]
+#[derive(Debug, Clone, PartialEq)]
+struct Aircraft {
+    #[name = "ATC ID"]
+    #[unit = ""]
+    atc_id: sim_connect::String64,
+    #[index = 3]
+    third: Engine,
+    engines: [Engine; 2],
+    #[prefix = "NAV "]
+    nav: Light,
+}
+
+#[test]
+fn definitions() {
+    let definitions = Aircraft::definitions();
+    let names: Vec<_> = definitions.iter().map(|d| d.0.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "ATC ID",
+            "ENG N1 RPM:3",
+            "GENERAL ENG THROTTLE LEVER POSITION:3",
+            "ENG N1 RPM:1",
+            "GENERAL ENG THROTTLE LEVER POSITION:1",
+            "ENG N1 RPM:2",
+            "GENERAL ENG THROTTLE LEVER POSITION:2",
+            "NAV LIGHT ON",
+        ]
+    );
+    // The path resolves to the fixed-length string.
+    assert_eq!(
+        definitions[0].3,
+        msfs::sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING64
+    );
+    assert_eq!(Aircraft::DATUM_COUNT, 8);
+    const { assert!(Engine::INDEXED) };
+    const { assert!(!Aircraft::INDEXED) };
+}
+
+#[test]
+fn round_trip() {
+    let server = MockServer::new();
+    let received: Rc<RefCell<Vec<Aircraft>>> = Rc::default();
+    let aircraft = received.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |sim, recv| {
+        if let SimConnectRecv::SimObjectData(data) = recv {
+            aircraft.borrow_mut().push(data.into(sim).unwrap());
+        }
+    })
+    .unwrap();
+
+    let sent = Aircraft {
+        atc_id: "N123".into(),
+        third: Engine {
+            n1: 3.0,
+            throttle: 33.0,
+        },
+        engines: [
+            Engine {
+                n1: 1.0,
+                throttle: 11.0,
+            },
+            Engine {
+                n1: 2.0,
+                throttle: 22.0,
+            },
+        ],
+        nav: Light { on: true },
+    };
+    sim.set_data_on_sim_object(ObjectId::USER, &sent).unwrap();
+    assert_eq!(
+        server.simvar(ObjectId::USER, "GENERAL ENG THROTTLE LEVER POSITION:2"),
+        Some(22.0)
+    );
+    assert_eq!(server.simvar(ObjectId::USER, "NAV LIGHT ON"), Some(1.0));
+
+    let _handle = sim
+        .request_data_on_sim_object::<Aircraft>(RequestId(1), ObjectId::USER, Period::Once)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(*received.borrow(), [sent]);
+}
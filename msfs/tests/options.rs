@@ -0,0 +1,72 @@
+use msfs::sim_connect::{
+    ClientDataPeriod, MockServer, ObjectId, Period, RequestId, RequestOptions, SimConnect,
+    SimConnectRecv, client_data_definition, data_definition,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[data_definition]
+#[derive(Debug)]
+struct Altitude {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+#[client_data_definition]
+#[derive(Debug, Clone, Copy)]
+struct Shared {
+    value: u32,
+}
+
+#[test]
+fn options() {
+    let server = MockServer::new();
+    let received: Rc<RefCell<Vec<RequestId>>> = Rc::default();
+    let requests = received.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_, recv| match recv {
+        SimConnectRecv::SimObjectData(data) => requests.borrow_mut().push(data.id()),
+        SimConnectRecv::ClientData(data) => requests.borrow_mut().push(data.id()),
+        _ => {}
+    })
+    .unwrap();
+
+    // Every other frame from the second, three times.
+    let options = RequestOptions::new(Period::SimFrame)
+        .with_changed(false)
+        .with_origin(1)
+        .with_interval(1)
+        .with_limit(3);
+    let _altitude = sim
+        .request_data_on_sim_object_with::<Altitude>(RequestId(0), ObjectId::USER, options)
+        .unwrap();
+    // Every frame, twice.
+    let area = sim.create_client_data::<Shared>("SHARED").unwrap();
+    let options = RequestOptions::new(ClientDataPeriod::VisualFrame)
+        .with_changed(false)
+        .with_limit(2);
+    let _shared = sim
+        .request_client_data_with::<Shared>(RequestId(1), "SHARED", options)
+        .unwrap();
+    sim.set_client_data(&area, &Shared { value: 1 }).unwrap();
+
+    let mut frames = Vec::new();
+    for _ in 0..8 {
+        sim.call_dispatch().unwrap();
+        frames.push(std::mem::take(&mut *received.borrow_mut()));
+    }
+    let [altitude, shared] = [RequestId(0), RequestId(1)];
+    assert_eq!(
+        frames,
+        [
+            vec![shared],
+            vec![altitude, shared],
+            vec![],
+            vec![altitude],
+            vec![],
+            vec![altitude],
+            vec![],
+            vec![],
+        ]
+    );
+}
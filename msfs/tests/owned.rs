@@ -0,0 +1,45 @@
+use msfs::sim_connect::{
+    MockServer, ObjectId, OwnedSimConnectRecv, Period, RequestId, SimConnect, data_definition,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[data_definition]
+#[derive(Debug, Clone, Copy)]
+struct Altitude {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+#[test]
+fn owned() {
+    let server = MockServer::new();
+    let queue: Rc<RefCell<Vec<OwnedSimConnectRecv>>> = Rc::default();
+    let messages = queue.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_, recv| {
+        messages.borrow_mut().push(recv.into_owned());
+    })
+    .unwrap();
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 42.0);
+    let _handle = sim
+        .request_data_on_sim_object::<Altitude>(RequestId(3), ObjectId::USER, Period::Once)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+
+    // The messages are read after the dispatch that delivered them.
+    let messages = std::mem::take(&mut *queue.borrow_mut());
+    assert!(matches!(
+        messages[..],
+        [
+            OwnedSimConnectRecv::Open(_),
+            OwnedSimConnectRecv::SimObjectData(_)
+        ]
+    ));
+    let OwnedSimConnectRecv::SimObjectData(data) = messages[1].clone() else {
+        unreachable!()
+    };
+    assert_eq!(data.id(), RequestId(3));
+    assert_eq!(data.data().len(), 8);
+    assert_eq!(data.into::<Altitude>(&sim).unwrap().altitude, 42.0);
+}
@@ -0,0 +1,100 @@
+use futures::FutureExt;
+use msfs::sim_connect::{
+    DecodeError, MockServer, ObjectId, SimConnect, SnapshotError, data_definition,
+};
+use msfs::sys;
+use std::time::Duration;
+
+const AIRCRAFT: sys::SIMCONNECT_SIMOBJECT_TYPE =
+    sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT;
+
+#[data_definition]
+#[derive(Debug, PartialEq)]
+struct Altitude {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+#[data_definition]
+#[derive(Debug, PartialEq)]
+struct Engines {
+    #[name = "NUMBER OF ENGINES"]
+    #[unit = "number"]
+    #[convert = "i32"]
+    count: u8,
+}
+
+#[test]
+fn snapshot() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    server.add_object(ObjectId(5), AIRCRAFT);
+    server.add_object(
+        ObjectId(6),
+        sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_BOAT,
+    );
+    server.set_simvar(ObjectId(5), "PLANE ALTITUDE", 10.0);
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 1.0);
+
+    let mut snapshot = sim
+        .snapshot_sim_objects::<Altitude>(1000, AIRCRAFT, Duration::from_secs(60))
+        .unwrap();
+    assert!((&mut snapshot).now_or_never().is_none());
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        snapshot.now_or_never(),
+        Some(Ok(vec![
+            (ObjectId::USER, Altitude { altitude: 1.0 }),
+            (ObjectId(5), Altitude { altitude: 10.0 }),
+        ]))
+    );
+
+    // No objects of the type.
+    let snapshot = sim
+        .snapshot_sim_objects::<Altitude>(
+            1000,
+            sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_GROUND,
+            Duration::from_secs(60),
+        )
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(snapshot.now_or_never(), Some(Ok(vec![])));
+}
+
+#[test]
+fn decode_error() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    server.set_simvar(ObjectId::USER, "NUMBER OF ENGINES", 300.0);
+    let snapshot = sim
+        .snapshot_sim_objects::<Engines>(1000, AIRCRAFT, Duration::from_secs(60))
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert!(matches!(
+        snapshot.now_or_never(),
+        Some(Err(SnapshotError::Decode(DecodeError::InvalidValue { .. })))
+    ));
+}
+
+#[test]
+fn timeout() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    // The request fails, so nothing is ever sent.
+    server.fail_next(sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_ERROR, 1);
+    let mut snapshot = sim
+        .snapshot_sim_objects::<Altitude>(1000, AIRCRAFT, Duration::from_millis(50))
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert!((&mut snapshot).now_or_never().is_none());
+    std::thread::sleep(Duration::from_millis(60));
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        snapshot.now_or_never(),
+        Some(Err(SnapshotError::Timeout {
+            received: 0,
+            expected: None
+        }))
+    );
+}
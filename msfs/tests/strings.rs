@@ -0,0 +1,128 @@
+use msfs::sim_connect::{
+    DataLatLonAlt, DataType, DynamicDataDefinition, MockServer, ObjectId, Period, RequestId,
+    SimConnect, SimConnectRecv, String32, Value, data_definition,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[data_definition]
+#[derive(Debug, Clone, PartialEq)]
+struct Info {
+    #[name = "TITLE"]
+    #[unit = ""]
+    title: String,
+    #[name = "ATC ID"]
+    #[unit = ""]
+    atc_id: String32,
+    #[name = "SIM ON GROUND"]
+    #[unit = "Bool"]
+    on_ground: bool,
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+#[data_definition]
+#[derive(Debug, Clone, Copy)]
+struct Position(
+    #[name = "STRUCT LATLONALT"]
+    #[unit = ""]
+    DataLatLonAlt,
+    #[name = "ATC ID"]
+    #[unit = ""]
+    String32,
+);
+
+#[test]
+fn strings() {
+    let server = MockServer::new();
+    let infos: Rc<RefCell<Vec<Info>>> = Rc::default();
+    let positions: Rc<RefCell<Vec<Position>>> = Rc::default();
+    let (received_infos, received_positions) = (infos.clone(), positions.clone());
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |sim, recv| {
+        if let SimConnectRecv::SimObjectData(data) = recv {
+            match data.id() {
+                RequestId(0) => received_infos.borrow_mut().push(data.into(sim).unwrap()),
+                _ => received_positions
+                    .borrow_mut()
+                    .push(data.into(sim).unwrap()),
+            }
+        }
+    })
+    .unwrap();
+    server.set_simvar_string(ObjectId::USER, "TITLE", "Cessna 172");
+    server.set_simvar_string(ObjectId::USER, "ATC ID", "N123");
+    server.set_simvar(ObjectId::USER, "SIM ON GROUND", 1.0);
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 5.0);
+    let _info = sim
+        .request_data_on_sim_object::<Info>(RequestId(0), ObjectId::USER, Period::Once)
+        .unwrap();
+    let _position = sim
+        .request_data_on_sim_object::<Position>(RequestId(1), ObjectId::USER, Period::Once)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    let info = infos.borrow_mut().remove(0);
+    assert_eq!(info.title, "Cessna 172");
+    assert_eq!(info.atc_id.to_str().unwrap(), "N123");
+    assert!(info.on_ground);
+    assert_eq!(info.altitude, 5.0);
+    assert_eq!(positions.borrow()[0].1.to_str().unwrap(), "N123");
+
+    sim.set_data_on_sim_object(
+        ObjectId::USER,
+        &Info {
+            title: "A320".into(),
+            atc_id: "FBW1".into(),
+            on_ground: false,
+            altitude: 9.0,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        server.simvar_string(ObjectId::USER, "TITLE").as_deref(),
+        Some("A320")
+    );
+    assert_eq!(
+        server.simvar_string(ObjectId::USER, "ATC ID").as_deref(),
+        Some("FBW1")
+    );
+    assert_eq!(server.simvar(ObjectId::USER, "SIM ON GROUND"), Some(0.0));
+    assert_eq!(server.simvar(ObjectId::USER, "PLANE ALTITUDE"), Some(9.0));
+}
+
+#[test]
+fn variable_length_strings() {
+    let server = MockServer::new();
+    let definition = Rc::new(RefCell::new(DynamicDataDefinition::new()));
+    definition
+        .borrow_mut()
+        .add("TITLE", "", DataType::StringV)
+        .add("PLANE ALTITUDE", "feet", DataType::Float64);
+    let decoded: Rc<RefCell<Vec<Vec<Value>>>> = Rc::default();
+    let (received, values) = (definition.clone(), decoded.clone());
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_, recv| {
+        if let SimConnectRecv::SimObjectData(data) = recv {
+            values
+                .borrow_mut()
+                .push(received.borrow().decode(data).unwrap());
+        }
+    })
+    .unwrap();
+    sim.add_dynamic_data_definition(&mut definition.borrow_mut())
+        .unwrap();
+    server.set_simvar_string(ObjectId::USER, "TITLE", "A longer title");
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 7.0);
+    let _handle = sim
+        .request_dynamic_data_on_sim_object(
+            RequestId(0),
+            &definition.borrow(),
+            ObjectId::USER,
+            Period::Once,
+        )
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    let values = decoded.borrow_mut().remove(0);
+    assert_eq!(values[0].as_str(), Some("A longer title"));
+    // The value after the string is found past its end.
+    assert_eq!(values[1].as_f64(), Some(7.0));
+}
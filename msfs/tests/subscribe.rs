@@ -0,0 +1,107 @@
+use futures::StreamExt;
+use msfs::sim_connect::{
+    DecodeError, MockServer, ObjectId, Period, SimConnect, SimConnectRecv, data_definition,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[data_definition]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Altitude {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Gear {
+    Up,
+    Down,
+}
+
+impl TryFrom<i32> for Gear {
+    type Error = ();
+
+    fn try_from(value: i32) -> Result<Self, ()> {
+        match value {
+            0 => Ok(Gear::Up),
+            1 => Ok(Gear::Down),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Gear> for i32 {
+    fn from(gear: Gear) -> i32 {
+        gear as i32
+    }
+}
+
+#[data_definition]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Landing {
+    #[name = "GEAR HANDLE POSITION"]
+    #[unit = "enum"]
+    #[convert = "i32"]
+    gear: Gear,
+}
+
+#[test]
+fn subscribe() {
+    let server = MockServer::new();
+    let unrouted: Rc<RefCell<Vec<String>>> = Rc::default();
+    let messages = unrouted.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        if !matches!(recv, SimConnectRecv::Open(_)) {
+            messages.borrow_mut().push(format!("{recv:?}"));
+        }
+    })
+    .unwrap();
+
+    let mut altitude = sim
+        .subscribe::<Altitude>(ObjectId::USER, Period::SimFrame)
+        .unwrap();
+    assert!(altitude.request_id().0 >= 0x8000_0000);
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 10.0);
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        futures::executor::block_on(altitude.next()),
+        Some(Ok(Altitude { altitude: 10.0 }))
+    );
+
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 20.0);
+    sim.call_dispatch().unwrap();
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 30.0);
+    sim.call_dispatch().unwrap();
+    assert_eq!(altitude.latest(), Some(Ok(Altitude { altitude: 30.0 })));
+    assert_eq!(altitude.latest(), None);
+
+    // Nothing is sent once the subscription is dropped.
+    drop(altitude);
+    sim.call_dispatch().unwrap();
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 40.0);
+    sim.call_dispatch().unwrap();
+    assert!(unrouted.borrow().is_empty(), "{:?}", unrouted.borrow());
+}
+
+#[test]
+fn decode_errors_are_yielded() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    let mut landing = sim
+        .subscribe::<Landing>(ObjectId::USER, Period::SimFrame)
+        .unwrap();
+    server.set_simvar(ObjectId::USER, "GEAR HANDLE POSITION", 7.0);
+    sim.call_dispatch().unwrap();
+    server.set_simvar(ObjectId::USER, "GEAR HANDLE POSITION", 1.0);
+    sim.call_dispatch().unwrap();
+
+    futures::executor::block_on(async {
+        match landing.next().await {
+            Some(Err(DecodeError::InvalidValue { value, .. })) => assert_eq!(value, "7"),
+            other => panic!("{other:?}"),
+        }
+        // The stream carries on after an error.
+        assert_eq!(landing.next().await, Some(Ok(Landing { gear: Gear::Down })));
+    });
+}
@@ -0,0 +1,129 @@
+use futures::StreamExt;
+use msfs::sim_connect::{
+    MockServer, ObjectId, PauseFlags, RaceResult, SimConnect, SimConnectRecv, SystemEvent,
+    SystemEventKind,
+};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+#[test]
+fn typed_system_events() {
+    let server = MockServer::new();
+    let unrouted: Rc<RefCell<usize>> = Rc::default();
+    let messages = unrouted.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        if !matches!(recv, SimConnectRecv::Open(_)) {
+            *messages.borrow_mut() += 1;
+        }
+    })
+    .unwrap();
+    let mut pause = sim.system_events(SystemEventKind::Pause).unwrap();
+    let mut unpaused = sim.system_events(SystemEventKind::Unpaused).unwrap();
+    let mut pause_ex1 = sim.system_events(SystemEventKind::PauseEx1).unwrap();
+    let mut loaded = sim.system_events(SystemEventKind::FlightLoaded).unwrap();
+    let mut frame = sim.system_events(SystemEventKind::Frame).unwrap();
+    let mut added = sim.system_events(SystemEventKind::ObjectAdded).unwrap();
+    assert_eq!(pause.kind().name(), "Pause");
+    sim.call_dispatch().unwrap();
+
+    server.send_event("Pause", 1);
+    server.send_event("Unpaused", 0);
+    server.send_event("Pause_EX1", 4);
+    server.send_filename_event("FlightLoaded", "flights/circuit.flt");
+    server.send_frame_event("Frame", 30.0, 1.0);
+    server.add_object(ObjectId(7), 2);
+    sim.call_dispatch().unwrap();
+    assert_eq!(*unrouted.borrow(), 0);
+
+    futures::executor::block_on(async {
+        assert_eq!(pause.next().await, Some(SystemEvent::Paused(true)));
+        assert_eq!(unpaused.next().await, Some(SystemEvent::Paused(false)));
+        let Some(SystemEvent::PauseEx1(flags)) = pause_ex1.next().await else {
+            panic!("expected Pause_EX1");
+        };
+        assert!(flags.contains(PauseFlags::ACTIVE_PAUSE) && flags.is_paused());
+        assert_eq!(
+            loaded.next().await,
+            Some(SystemEvent::FlightLoaded(PathBuf::from(
+                "flights/circuit.flt"
+            )))
+        );
+        assert_eq!(
+            frame.next().await,
+            Some(SystemEvent::Frame {
+                fps: 30.0,
+                sim_speed: 1.0
+            })
+        );
+        assert_eq!(
+            added.next().await,
+            Some(SystemEvent::ObjectAdded {
+                id: ObjectId(7),
+                kind: 2
+            })
+        );
+    });
+
+    // Dropping the stream unsubscribes.
+    drop(pause);
+    sim.call_dispatch().unwrap();
+    server.send_event("Pause", 0);
+    sim.call_dispatch().unwrap();
+    assert_eq!(*unrouted.borrow(), 0);
+}
+
+#[test]
+fn multiplayer_and_race_events() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    let mut server_started = sim
+        .system_events(SystemEventKind::MultiplayerServerStarted)
+        .unwrap();
+    let mut session_ended = sim
+        .system_events(SystemEventKind::MultiplayerSessionEnded)
+        .unwrap();
+    let mut lap = sim.system_events(SystemEventKind::RaceLap).unwrap();
+    let mut end = sim.system_events(SystemEventKind::RaceEnd).unwrap();
+    sim.call_dispatch().unwrap();
+
+    let mut result = RaceResult {
+        racers: 2,
+        mission_guid: [7; 16],
+        player_name: "Pilot".into(),
+        session_type: "Race".into(),
+        aircraft: "Extra 330".into(),
+        player_role: "Racer".into(),
+        total_time: 61.5,
+        penalty_time: 0.0,
+        disqualified: false,
+    };
+    server.send_event("MultiplayerServerStarted", 0);
+    server.send_race_event("RaceLap", 0, &result);
+    result.total_time = 120.25;
+    result.penalty_time = 2.0;
+    result.disqualified = true;
+    server.send_race_event("RaceEnd", 1, &result);
+    server.send_event("MultiplayerSessionEnded", 0);
+    sim.call_dispatch().unwrap();
+
+    futures::executor::block_on(async {
+        assert_eq!(
+            server_started.next().await,
+            Some(SystemEvent::MultiplayerServerStarted)
+        );
+        let Some(SystemEvent::RaceLap { lap, result: first }) = lap.next().await else {
+            panic!("expected RaceLap");
+        };
+        assert_eq!((lap, first.total_time), (0, 61.5));
+        assert_eq!(first.aircraft, "Extra 330");
+        assert_eq!(
+            end.next().await,
+            Some(SystemEvent::RaceEnd { racer: 1, result })
+        );
+        assert_eq!(
+            session_ended.next().await,
+            Some(SystemEvent::MultiplayerSessionEnded)
+        );
+    });
+}
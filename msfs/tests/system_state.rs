@@ -0,0 +1,60 @@
+use msfs::sim_connect::{
+    ExceptionKind, MockServer, RequestError, SimConnect, SimConnectRecv, SystemState,
+    SystemStateQuery,
+};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+#[test]
+fn system_state() {
+    let server = MockServer::new();
+    let unrouted: Rc<RefCell<usize>> = Rc::default();
+    let messages = unrouted.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_sim, recv| {
+        if !matches!(recv, SimConnectRecv::Open(_)) {
+            *messages.borrow_mut() += 1;
+        }
+    })
+    .unwrap();
+    server.set_system_state(
+        "AircraftLoaded",
+        0,
+        "SimObjects/Airplanes/A32NX/aircraft.cfg",
+    );
+    server.set_system_state("Sim", 1, "");
+    let aircraft = sim.system_state(SystemStateQuery::AircraftLoaded).unwrap();
+    let running = sim.system_state(SystemStateQuery::Sim).unwrap();
+    let dialog = sim.system_state(SystemStateQuery::DialogMode).unwrap();
+    server.fail_next(
+        msfs::sys::SIMCONNECT_EXCEPTION::SIMCONNECT_EXCEPTION_NAME_UNRECOGNIZED,
+        2,
+    );
+    let failed = sim.system_state(SystemStateQuery::FlightPlan).unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(*unrouted.borrow(), 0);
+
+    futures::executor::block_on(async {
+        assert_eq!(
+            aircraft.await.unwrap(),
+            SystemState::AircraftLoaded(PathBuf::from("SimObjects/Airplanes/A32NX/aircraft.cfg"))
+        );
+        assert_eq!(running.await.unwrap(), SystemState::Running(true));
+        assert_eq!(dialog.await.unwrap(), SystemState::DialogMode(false));
+        match failed.await {
+            Err(RequestError::Exception(exception)) => {
+                assert_eq!(exception.kind, ExceptionKind::NameUnrecognized);
+                assert_eq!(exception.call.unwrap().method, "system_state");
+            }
+            other => panic!("{other:?}"),
+        }
+    });
+
+    // Pending queries fail once the session is closed.
+    let pending = sim.system_state(SystemStateQuery::Sim).unwrap();
+    drop(sim);
+    assert!(matches!(
+        futures::executor::block_on(pending),
+        Err(RequestError::Closed)
+    ));
+}
@@ -0,0 +1,205 @@
+use futures::StreamExt;
+use msfs::sim_connect::{
+    DataDefinition, DecodeError, MockServer, ObjectId, Period, RequestId, RequestOptions,
+    SimConnect, SimConnectRecv, data_definition,
+};
+use msfs::sys;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[data_definition]
+#[derive(Debug, PartialEq)]
+struct Engine {
+    #[name = "ENG N1 RPM:{}"]
+    #[unit = "Percent"]
+    n1: f64,
+}
+
+#[data_definition]
+#[derive(Debug, PartialEq)]
+struct Plane {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+    #[name = "TITLE"]
+    #[unit = ""]
+    title: String,
+    engines: [Engine; 2],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Gear {
+    Up,
+    Down,
+}
+
+impl TryFrom<i32> for Gear {
+    type Error = ();
+
+    fn try_from(value: i32) -> Result<Self, ()> {
+        match value {
+            0 => Ok(Gear::Up),
+            1 => Ok(Gear::Down),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Gear> for i32 {
+    fn from(gear: Gear) -> i32 {
+        gear as i32
+    }
+}
+
+#[data_definition]
+#[derive(Debug, PartialEq)]
+struct Landing {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+    #[name = "GEAR HANDLE POSITION"]
+    #[unit = "enum"]
+    #[convert = "i32"]
+    gear: Gear,
+}
+
+/// A definition with a simvar of a type which can't be received.
+struct Invalid;
+
+impl DataDefinition for Invalid {
+    const DATUM_COUNT: usize = 1;
+    const FIELDS: &'static [(&'static str, usize)] = &[("0", 1)];
+    const INDEXED: bool = false;
+
+    fn definitions() -> Vec<(String, &'static str, f32, sys::SIMCONNECT_DATATYPE)> {
+        vec![(
+            "PLANE ALTITUDE".into(),
+            "feet",
+            0.0,
+            sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_INVALID,
+        )]
+    }
+
+    fn decode(_data: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Invalid)
+    }
+
+    fn encode(&self, _out: &mut Vec<u8>) {}
+}
+
+#[test]
+fn tagged() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 100.0);
+    server.set_simvar_string(ObjectId::USER, "TITLE", "A320");
+    let mut plane = sim
+        .subscribe_tagged::<Plane>(ObjectId::USER, Period::SimFrame)
+        .unwrap();
+    assert!(plane.update().is_none());
+
+    sim.call_dispatch().unwrap();
+    let changes = plane.update().unwrap().unwrap();
+    assert_eq!(
+        changes.fields().collect::<Vec<_>>(),
+        ["altitude", "title", "engines"]
+    );
+    assert_eq!(plane.value().unwrap().title, "A320");
+    assert_eq!(plane.value().unwrap().altitude, 100.0);
+
+    // Nothing changed.
+    sim.call_dispatch().unwrap();
+    assert!(plane.update().is_none());
+
+    server.set_simvar(ObjectId::USER, "ENG N1 RPM:2", 50.0);
+    sim.call_dispatch().unwrap();
+    let changes = plane.update().unwrap().unwrap();
+    assert_eq!(changes.fields().collect::<Vec<_>>(), ["engines"]);
+    assert_eq!(changes.datums().collect::<Vec<_>>(), [3]);
+    assert!(changes.contains("engines") && !changes.contains("title"));
+    assert_eq!(plane.value().unwrap().engines[1].n1, 50.0);
+
+    server.set_simvar_string(ObjectId::USER, "TITLE", "B");
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 5.0);
+    sim.call_dispatch().unwrap();
+    let changes = futures::executor::block_on(plane.next()).unwrap().unwrap();
+    assert_eq!(changes.fields().collect::<Vec<_>>(), ["altitude", "title"]);
+    assert_eq!(
+        plane.value().unwrap(),
+        &Plane {
+            altitude: 5.0,
+            title: "B".into(),
+            engines: [Engine { n1: 0.0 }, Engine { n1: 50.0 }]
+        }
+    );
+}
+
+#[test]
+fn merge_errors_are_yielded() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 100.0);
+    let mut landing = sim
+        .subscribe_tagged::<Landing>(ObjectId::USER, Period::SimFrame)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert!(landing.update().unwrap().is_ok());
+
+    server.set_simvar(ObjectId::USER, "PLANE ALTITUDE", 200.0);
+    server.set_simvar(ObjectId::USER, "GEAR HANDLE POSITION", 7.0);
+    sim.call_dispatch().unwrap();
+    match landing.update() {
+        Some(Err(DecodeError::InvalidValue { value, .. })) => assert_eq!(value, "7"),
+        other => panic!("{other:?}"),
+    }
+    // The last value is kept until one can be decoded.
+    assert_eq!(landing.value().unwrap().altitude, 100.0);
+
+    // The altitude which changed alongside the invalid value is reported
+    // once the gear is valid again.
+    server.set_simvar(ObjectId::USER, "GEAR HANDLE POSITION", 1.0);
+    sim.call_dispatch().unwrap();
+    let changes = futures::executor::block_on(landing.next())
+        .unwrap()
+        .unwrap();
+    assert_eq!(changes.fields().collect::<Vec<_>>(), ["altitude", "gear"]);
+    assert_eq!(
+        landing.value().unwrap(),
+        &Landing {
+            altitude: 200.0,
+            gear: Gear::Down
+        }
+    );
+}
+
+#[test]
+fn unreceivable_types_are_rejected() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    assert!(
+        sim.subscribe_tagged::<Invalid>(ObjectId::USER, Period::SimFrame)
+            .is_err()
+    );
+}
+
+#[test]
+fn tagged_data_is_not_decoded_whole() {
+    let server = MockServer::new();
+    let decoded: Rc<RefCell<Vec<Result<Plane, DecodeError>>>> = Rc::default();
+    let planes = decoded.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |sim, recv| {
+        if let SimConnectRecv::SimObjectData(data) = recv {
+            planes.borrow_mut().push(data.into(sim));
+        }
+    })
+    .unwrap();
+    let _handle = sim
+        .request_data_on_sim_object_with::<Plane>(
+            RequestId(1),
+            ObjectId::USER,
+            RequestOptions::new(Period::Once).with_tagged(true),
+        )
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(*decoded.borrow(), [Err(DecodeError::Tagged)]);
+}
@@ -0,0 +1,264 @@
+use msfs::sim_connect::{
+    Backend, ExceptionKind, ObjectId, Period, RequestId, SimConnect, SimConnectRecv, TcpBackend,
+    data_definition,
+};
+use std::cell::RefCell;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+const RECV_ID_EXCEPTION: u32 = 1;
+const RECV_ID_OPEN: u32 = 2;
+const RECV_ID_EVENT: u32 = 4;
+const RECV_ID_SIMOBJECT_DATA: u32 = 8;
+
+#[data_definition]
+#[derive(Debug)]
+struct Altitude {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+/// A packet sent by the client.
+struct Packet(Vec<u8>);
+
+impl Packet {
+    fn read(stream: &mut TcpStream) -> Self {
+        let mut size = [0; 4];
+        stream.read_exact(&mut size).unwrap();
+        let mut packet = size.to_vec();
+        packet.resize(u32::from_le_bytes(size) as usize, 0);
+        stream.read_exact(&mut packet[4..]).unwrap();
+        Packet(packet)
+    }
+
+    fn u32(&self, offset: usize) -> u32 {
+        u32::from_le_bytes(self.0[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn version(&self) -> u32 {
+        self.u32(4)
+    }
+
+    fn function(&self) -> u32 {
+        self.u32(8)
+    }
+
+    fn packet_id(&self) -> u32 {
+        self.u32(12)
+    }
+}
+
+/// Build a message sent by the server.
+fn message(id: u32, body: &[u32]) -> Vec<u8> {
+    let mut message = Vec::new();
+    let size = 12 + 4 * body.len() as u32;
+    for value in [size, 5, id].iter().chain(body) {
+        message.extend_from_slice(&value.to_le_bytes());
+    }
+    message
+}
+
+fn open_message(name: &str) -> Vec<u8> {
+    let mut message = message(RECV_ID_OPEN, &[]);
+    let mut application_name = [0; 256];
+    application_name[..name.len()].copy_from_slice(name.as_bytes());
+    message.extend_from_slice(&application_name);
+    for value in [11, 0, 62651, 3, 11, 0, 62651, 3, 0, 0] {
+        message.extend_from_slice(&u32::to_le_bytes(value));
+    }
+    let size = message.len() as u32;
+    message[0..4].copy_from_slice(&size.to_le_bytes());
+    message
+}
+
+/// Run `server` on the other end of a new connection. The connection stays
+/// open until the thread is joined.
+fn listen(
+    server: impl FnOnce(&mut TcpStream) + Send + 'static,
+) -> (std::net::SocketAddr, std::thread::JoinHandle<TcpStream>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let thread = std::thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+        server(&mut stream);
+        stream
+    });
+    (addr, thread)
+}
+
+/// Dispatch until `done` returns true.
+fn dispatch_until(sim: &mut SimConnect, mut done: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !done() {
+        assert!(Instant::now() < deadline, "timed out");
+        sim.call_dispatch().unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn open_handshake() {
+    let (addr, server) = listen(|stream| {
+        let open = Packet::read(stream);
+        assert_eq!(open.0.len(), 16 + 256 + 4 + 4 + 16);
+        assert_eq!(open.version(), 5);
+        assert_eq!(open.function(), 0xF000_0001);
+        assert_eq!(open.packet_id(), 1);
+        assert_eq!(&open.0[16..20], b"TEST");
+        assert!(open.0[20..272].iter().all(|&b| b == 0));
+        assert_eq!(&open.0[276..280], b"\0XSF");
+        assert_eq!(
+            [open.u32(280), open.u32(284), open.u32(288), open.u32(292)],
+            [11, 0, 62651, 3]
+        );
+        stream.write_all(&open_message("KittyHawk")).unwrap();
+    });
+
+    let names: Rc<RefCell<Vec<String>>> = Rc::default();
+    let received = names.clone();
+    let backend = TcpBackend::connect(addr, "TEST").unwrap();
+    let mut sim = SimConnect::open_with_backend(backend, move |_sim, recv| {
+        if let SimConnectRecv::Open(open) = recv {
+            let name = open.szApplicationName.map(|c| c as u8);
+            let name = std::ffi::CStr::from_bytes_until_nul(&name).unwrap();
+            received
+                .borrow_mut()
+                .push(name.to_string_lossy().into_owned());
+        }
+    })
+    .unwrap();
+    dispatch_until(&mut sim, || !names.borrow().is_empty());
+    assert_eq!(*names.borrow(), ["KittyHawk"]);
+    server.join().unwrap();
+}
+
+#[test]
+fn several_messages_in_one_read() {
+    let (addr, server) = listen(|stream| {
+        Packet::read(stream);
+        let mut messages = Vec::new();
+        for data in 1..=3 {
+            messages.extend(message(RECV_ID_EVENT, &[0, 0, data]));
+        }
+        stream.write_all(&messages).unwrap();
+    });
+
+    let events: Rc<RefCell<Vec<u32>>> = Rc::default();
+    let received = events.clone();
+    let backend = TcpBackend::connect(addr, "TEST").unwrap();
+    let mut sim = SimConnect::open_with_backend(backend, move |_sim, recv| {
+        if let SimConnectRecv::Event(event) = recv {
+            received.borrow_mut().push(event.data());
+        }
+    })
+    .unwrap();
+    dispatch_until(&mut sim, || events.borrow().len() == 3);
+    assert_eq!(*events.borrow(), [1, 2, 3]);
+    server.join().unwrap();
+}
+
+#[test]
+fn message_split_across_reads() {
+    let (sent, written) = mpsc::channel();
+    let (dispatched, wait) = mpsc::channel();
+    let (addr, server) = listen(move |stream| {
+        Packet::read(stream);
+        // Part of the first header, then the rest of the first message with the
+        // start of the second.
+        let mut messages = message(RECV_ID_EVENT, &[0, 0, 1]);
+        messages.extend(message(RECV_ID_EVENT, &[0, 0, 2]));
+        for chunk in [&messages[..6], &messages[6..30], &messages[30..]] {
+            stream.write_all(chunk).unwrap();
+            sent.send(()).unwrap();
+            wait.recv().unwrap();
+        }
+    });
+
+    let events: Rc<RefCell<Vec<u32>>> = Rc::default();
+    let received = events.clone();
+    let backend = TcpBackend::connect(addr, "TEST").unwrap();
+    let mut sim = SimConnect::open_with_backend(backend, move |_sim, recv| {
+        if let SimConnectRecv::Event(event) = recv {
+            received.borrow_mut().push(event.data());
+        }
+    })
+    .unwrap();
+
+    written.recv().unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    sim.call_dispatch().unwrap();
+    assert!(events.borrow().is_empty());
+    dispatched.send(()).unwrap();
+
+    written.recv().unwrap();
+    dispatch_until(&mut sim, || events.borrow().len() == 1);
+    dispatched.send(()).unwrap();
+
+    written.recv().unwrap();
+    dispatch_until(&mut sim, || events.borrow().len() == 2);
+    dispatched.send(()).unwrap();
+    assert_eq!(*events.borrow(), [1, 2]);
+    server.join().unwrap();
+}
+
+#[test]
+fn exceptions_reference_last_sent_packet_id() {
+    let (addr, server) = listen(|stream| {
+        assert_eq!(Packet::read(stream).packet_id(), 1);
+        // The data definition, then the request.
+        let add = Packet::read(stream);
+        assert_eq!(add.function(), 0xF000_000C);
+        assert_eq!(add.packet_id(), 2);
+        let request = Packet::read(stream);
+        assert_eq!(request.function(), 0xF000_000E);
+        assert_eq!(request.packet_id(), 3);
+        assert_eq!(request.u32(16), 7);
+
+        let mut data = message(RECV_ID_SIMOBJECT_DATA, &[7, 0, 0, 0, 1, 1, 1]);
+        data.extend_from_slice(&1234.5f64.to_le_bytes());
+        let size = data.len() as u32;
+        data[0..4].copy_from_slice(&size.to_le_bytes());
+        stream.write_all(&data).unwrap();
+        stream
+            .write_all(&message(RECV_ID_EXCEPTION, &[3, request.packet_id(), 2]))
+            .unwrap();
+    });
+
+    let backend = TcpBackend::connect(addr, "TEST").unwrap();
+    assert_eq!(backend.last_sent_packet_id().unwrap(), 1);
+    let log: Rc<RefCell<Vec<String>>> = Rc::default();
+    let messages = log.clone();
+    let mut sim = SimConnect::open_with_backend(backend, move |sim, recv| match recv {
+        SimConnectRecv::SimObjectData(data) => {
+            let altitude = data.into::<Altitude>(sim).unwrap().altitude;
+            messages.borrow_mut().push(format!("altitude {altitude}"));
+        }
+        SimConnectRecv::Exception(exception) => {
+            let exception = exception.decode(sim);
+            assert_eq!(exception.kind, ExceptionKind::UnrecognizedId);
+            assert_eq!(exception.index, 2);
+            let call = exception.call.unwrap();
+            messages
+                .borrow_mut()
+                .push(format!("{} {}", call.method, call.call));
+        }
+        _ => {}
+    })
+    .unwrap();
+    let _handle = sim
+        .request_data_on_sim_object::<Altitude>(RequestId(7), ObjectId::USER, Period::SimFrame)
+        .unwrap();
+    dispatch_until(&mut sim, || log.borrow().len() == 2);
+    assert_eq!(
+        *log.borrow(),
+        [
+            "altitude 1234.5",
+            "request_data_on_sim_object RequestDataOnSimObject",
+        ]
+    );
+    server.join().unwrap();
+}
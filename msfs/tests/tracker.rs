@@ -0,0 +1,98 @@
+use msfs::sim_connect::{
+    MockServer, ObjectId, SimConnect, SimConnectRecv, TrackerEvent, data_definition,
+};
+use msfs::sys;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+#[data_definition]
+#[derive(Debug, PartialEq)]
+struct Altitude {
+    #[name = "PLANE ALTITUDE"]
+    #[unit = "feet"]
+    altitude: f64,
+}
+
+const BOAT: sys::SIMCONNECT_SIMOBJECT_TYPE =
+    sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_BOAT;
+
+#[test]
+fn tracker() {
+    let server = MockServer::new();
+    let unrouted = Rc::new(Cell::new(0));
+    let messages = unrouted.clone();
+    let mut sim = SimConnect::open_with_backend(server.backend(), move |_, recv| {
+        if !matches!(recv, SimConnectRecv::Open(_)) {
+            messages.set(messages.get() + 1);
+        }
+    })
+    .unwrap();
+    server.add_object(ObjectId(5), BOAT);
+    server.set_simvar(ObjectId(5), "PLANE ALTITUDE", 1.0);
+    let mut boats = sim
+        .track_sim_objects::<Altitude>(0, BOAT, Duration::from_secs(3600))
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(boats.update(), [TrackerEvent::Added(ObjectId(5))]);
+    assert_eq!(boats.get(ObjectId(5)), Some(&Altitude { altitude: 1.0 }));
+    // The user's aircraft isn't a boat.
+    assert!(boats.get(ObjectId::USER).is_none());
+
+    // An added object is requested again without waiting for the interval,
+    // and the answer arrives with the next dispatch.
+    server.add_object(ObjectId(6), BOAT);
+    server.set_simvar(ObjectId(5), "PLANE ALTITUDE", 2.0);
+    sim.call_dispatch().unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(
+        boats.update(),
+        [
+            TrackerEvent::Updated(ObjectId(5)),
+            TrackerEvent::Added(ObjectId(6))
+        ]
+    );
+    assert_eq!(boats.len(), 2);
+
+    server.remove_object(ObjectId(5));
+    sim.call_dispatch().unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(boats.update(), [TrackerEvent::Removed(ObjectId(5))]);
+    assert_eq!(
+        boats
+            .iter()
+            .map(|(object_id, _)| object_id)
+            .collect::<Vec<_>>(),
+        [ObjectId(6)]
+    );
+
+    // Nothing reaches the callback, even once the tracker is dropped.
+    drop(boats);
+    sim.call_dispatch().unwrap();
+    server.add_object(ObjectId(7), BOAT);
+    sim.call_dispatch().unwrap();
+    assert_eq!(unrouted.get(), 0);
+}
+
+#[test]
+fn missing_objects_are_evicted() {
+    let server = MockServer::new();
+    let mut sim = SimConnect::open_with_backend(server.backend(), |_, _| {}).unwrap();
+    server.add_object(ObjectId(5), BOAT);
+    let mut boats = sim
+        .track_sim_objects::<Altitude>(0, BOAT, Duration::ZERO)
+        .unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(boats.update(), [TrackerEvent::Added(ObjectId(5))]);
+
+    // The object is no longer a boat, so it is missing from the next response
+    // without being removed from the sim.
+    server.add_object(
+        ObjectId(5),
+        sys::SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_GROUND,
+    );
+    sim.call_dispatch().unwrap();
+    sim.call_dispatch().unwrap();
+    assert_eq!(boats.update(), [TrackerEvent::Removed(ObjectId(5))]);
+    assert!(boats.is_empty());
+}
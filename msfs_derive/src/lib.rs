@@ -38,6 +38,7 @@ pub fn standalone_module(_args: TokenStream, item: TokenStream) -> TokenStream {
                 handle: |m| std::boxed::Box::pin(#rusty_name(m)),
                 future: None,
                 tx: None,
+                wake: None,
             },
         };
 
@@ -122,6 +123,7 @@ pub fn gauge(args: TokenStream, item: TokenStream) -> TokenStream {
                 handle: |gauge| std::boxed::Box::pin(#rusty_name(gauge)),
                 tx: None,
                 future: None,
+                wake: None,
             },
         };
 
@@ -174,7 +176,28 @@ fn parse_struct_fields(
 
         if let Some(get_type) = get_type {
             let ty = match &field.ty {
-                Type::Path(p) => p.path.get_ident().unwrap().to_string(),
+                Type::Array(a) => {
+                    let len = match &a.len {
+                        Expr::Lit(l) => match &l.lit {
+                            Lit::Int(i) => i.base10_digits().to_string(),
+                            _ => panic!("array length must be an integer"),
+                        },
+                        _ => panic!("array length must be an integer literal"),
+                    };
+                    meta.insert("len".to_string(), len);
+                    &*a.elem
+                }
+                ty => ty,
+            };
+            // Matched by the last segment, so `sim_connect::String64` is the
+            // same as `String64`.
+            let ty = match ty {
+                Type::Path(p) => p
+                    .path
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string())
+                    .unwrap_or_default(),
                 _ => panic!("Unsupported type"),
             };
 
@@ -199,9 +222,10 @@ fn parse_struct_fields(
                             Expr::Lit(l) => match &l.lit {
                                 Lit::Str(s) => s.value(),
                                 Lit::Float(f) => f.base10_digits().to_string(),
-                                _ => panic!("argument must be a string or float"),
+                                Lit::Int(i) => i.base10_digits().to_string(),
+                                _ => panic!("argument must be a string or number"),
                             },
-                            _ => panic!("argument must be a string or float"),
+                            _ => panic!("argument must be a string or number"),
                         };
                         (name, value)
                     }
@@ -221,6 +245,31 @@ fn parse_struct_fields(
     data
 }
 
+/// The `SIMCONNECT_DATATYPE` a `data_definition` field type is sent as, if it
+/// is one the sim knows.
+fn data_type(ty: &str) -> Option<&'static str> {
+    Some(match ty {
+        "bool" => "INT32",
+        "i32" => "INT32",
+        "i64" => "INT64",
+        "f32" => "FLOAT32",
+        "f64" => "FLOAT64",
+        "DataXYZ" => "XYZ",
+        "DataLatLonAlt" => "LATLONALT",
+        "DataWaypoint" => "WAYPOINT",
+        "DataMarkerState" => "MARKERSTATE",
+        "InitPosition" => "INITPOSITION",
+        "String8" => "STRING8",
+        "String32" => "STRING32",
+        "String64" => "STRING64",
+        "String128" => "STRING128",
+        "String256" => "STRING256",
+        "String260" => "STRING260",
+        "String" => "STRINGV",
+        _ => return None,
+    })
+}
+
 /// Generate a struct which can be used with SimConnect's data definitions.
 /// ```rs
 /// #[sim_connect::data_definition]
@@ -238,6 +287,71 @@ fn parse_struct_fields(
 ///
 /// sim.add_data_definition::<ControlSurfaces>();
 /// ```
+///
+/// Fields may be `bool`, `i32`, `i64`, `f32`, `f64`, `DataXYZ`,
+/// `DataLatLonAlt`, `DataWaypoint`, `DataMarkerState`, `InitPosition`, the
+/// fixed-length strings `String8` through `String260`, or `String` for a
+/// variable-length `STRINGV`. They are matched by name, so they may also be
+/// written as paths such as `sim_connect::String64`.
+///
+/// Fields may also be arrays of these, for indexed simvars. The `{}` in the
+/// name is replaced by each index, counting from `start_index` (1 by default).
+/// ```rs
+/// #[sim_connect::data_definition]
+/// struct Engines {
+///     #[name = "ENG N1 RPM:{}"]
+///     #[unit = "Percent"]
+///     n1: [f64; 4],
+/// }
+/// ```
+///
+/// C-like enums, bitflags and newtypes can be sent as an `i32`, `i64`, `f32` or
+/// `f64` with `convert`. They are converted with `TryFrom` when decoded, which
+/// fails with `DecodeError::InvalidValue` for values out of range, and into the
+/// number with `From` when set.
+/// ```rs
+/// #[derive(Clone, TryFromPrimitive, IntoPrimitive)]
+/// #[repr(i32)]
+/// enum GearHandle {
+///     Up = 0,
+///     Down = 1,
+/// }
+///
+/// #[sim_connect::data_definition]
+/// struct Gear {
+///     #[name = "GEAR HANDLE POSITION"]
+///     #[unit = "Bool"]
+///     #[convert = "i32"]
+///     handle: GearHandle,
+/// }
+/// ```
+///
+/// A field of any other type must itself be a `data_definition`, and its
+/// simvars are flattened into this one. The `{}` in their names is replaced by
+/// the field's `index`, or by each index for an array, and their names can be
+/// given a `prefix`. A nested definition with a `{}` in its names fails to
+/// compile without an `index`.
+/// ```rs
+/// #[sim_connect::data_definition]
+/// struct Engine {
+///     #[name = "ENG N1 RPM:{}"]
+///     #[unit = "Percent"]
+///     n1: f64,
+///     #[name = "GENERAL ENG THROTTLE LEVER POSITION:{}"]
+///     #[unit = "Percent"]
+///     throttle: f64,
+/// }
+///
+/// #[sim_connect::data_definition]
+/// struct Aircraft {
+///     #[index = 1]
+///     left: Engine,
+///     #[index = 2]
+///     right: Engine,
+///     // or
+///     engines: [Engine; 2],
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn sim_connect_data_definition(_args: TokenStream, item: TokenStream) -> TokenStream {
     let mut input = parse_macro_input!(item as ItemStruct);
@@ -245,43 +359,191 @@ pub fn sim_connect_data_definition(_args: TokenStream, item: TokenStream) -> Tok
 
     let data = parse_struct_fields(
         &mut input,
-        &["name", "unit", "epsilon"],
-        Some(|ty| match ty {
-            "bool" => "INT32",
-            "i32" => "INT32",
-            "i64" => "INT64",
-            "f32" => "FLOAT32",
-            "f64" => "FLOAT64",
-            "DataXYZ" => "XYZ",
-            _ => panic!("Unsupported type {}", ty),
-        }),
+        &[
+            "name",
+            "unit",
+            "epsilon",
+            "start_index",
+            "prefix",
+            "index",
+            "convert",
+        ],
+        Some(|ty| data_type(ty).unwrap_or("")),
     );
 
-    let mut array = String::from("&[\n");
-    for meta in data {
-        let name = meta["name"].clone();
+    let mut definitions = Vec::new();
+    let mut datum_counts = Vec::new();
+    let mut indexed = Vec::new();
+    let mut checks = Vec::new();
+    let mut fields = Vec::new();
+    let mut field_names = Vec::new();
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+    for (i, (meta, field)) in data.iter().zip(input.fields.iter()).enumerate() {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(i);
+                quote!(#index)
+            }
+        };
+        let start = meta
+            .get("start_index")
+            .map_or(1, |start| start.parse::<usize>().unwrap());
+        let len = meta.get("len").map(|len| len.parse::<usize>().unwrap());
+        let indices = len.map(|len| start..start + len);
+
+        // Fields are read and written in order, as `STRINGV` fields shift the
+        // offsets of the ones after them.
+        match (meta.get("convert"), len) {
+            (Some(raw), None) => {
+                let raw = format_ident!("{}", raw);
+                reads.push(quote!(::msfs::sim_connect::read_converted::<#raw, _>(data)?));
+                writes.push(
+                    quote!(::msfs::sim_connect::write_converted::<#raw, _>(&self.#member, out)),
+                );
+            }
+            (Some(raw), Some(len)) => {
+                let raw = format_ident!("{}", raw);
+                reads.push(
+                    quote!(::msfs::sim_connect::read_converted_array::<#raw, _, #len>(data)?),
+                );
+                writes.push(quote!(::msfs::sim_connect::write_converted_array::<#raw, _, #len>(&self.#member, out)));
+            }
+            (None, _) => {
+                reads.push(quote!(::msfs::sim_connect::DataField::read(data)?));
+                writes.push(quote!(::msfs::sim_connect::DataField::write(&self.#member, out)));
+            }
+        }
+        field_names.push(member.to_string());
+        fields.push(member);
+
+        // Any other type is a nested data definition.
+        if meta["type"].is_empty() && !meta.contains_key("convert") {
+            let ty = match &field.ty {
+                Type::Array(a) => &*a.elem,
+                ty => ty,
+            };
+            let prefix = meta.get("prefix").cloned().unwrap_or_default();
+            let indices = match (indices, meta.get("index")) {
+                (Some(indices), _) => {
+                    let (start, end) = (indices.start, indices.end);
+                    quote!((#start..#end).map(Some))
+                }
+                (None, Some(index)) => {
+                    let index = index.parse::<usize>().unwrap();
+                    quote!([Some(#index)])
+                }
+                (None, None) => {
+                    let message = format!(
+                        "{} has a {{{{}}}} in its simvar names, and needs an #[index]",
+                        meta["field_name"]
+                    );
+                    checks.push(quote! {
+                        const _: () = ::std::assert!(
+                            !<#ty as ::msfs::sim_connect::DataDefinition>::INDEXED,
+                            #message
+                        );
+                    });
+                    indexed.push(quote!(<#ty as ::msfs::sim_connect::DataDefinition>::INDEXED));
+                    quote!([None])
+                }
+            };
+            definitions.push(quote! {
+                for index in #indices {
+                    ::msfs::sim_connect::nest_definitions::<#ty>(&mut definitions, #prefix, index);
+                }
+            });
+            let count = len.unwrap_or(1);
+            datum_counts
+                .push(quote!(#count * <#ty as ::msfs::sim_connect::DataDefinition>::DATUM_COUNT));
+            continue;
+        }
+
+        let name = meta
+            .get("name")
+            .unwrap_or_else(|| panic!("{} needs a #[name] decorator", meta["field_name"]));
         let unit = meta
             .get("unit")
             .unwrap_or_else(|| panic!("{} needs a #[unit] decorator", name));
-
-        let fallback = "0.0".to_string();
-        let epsilon = meta.get("epsilon").unwrap_or(&fallback);
-
-        let ty = meta["type"].clone();
-        array += &format!(
-            "  ({name:?}, {unit:?}, {epsilon}, ::msfs::sys::SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_{ty}),\n"
-        );
+        let epsilon = meta
+            .get("epsilon")
+            .map_or(0.0, |epsilon| epsilon.parse::<f32>().unwrap());
+        let ty = match meta.get("convert").map(String::as_str) {
+            Some(raw @ ("i32" | "i64" | "f32" | "f64")) => data_type(raw).unwrap(),
+            Some(raw) => panic!("{} can't be converted from {}", name, raw),
+            None => &meta["type"],
+        };
+        let ty = format_ident!("SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_{}", ty);
+        let names = match indices {
+            Some(indices) => {
+                if !name.contains("{}") {
+                    panic!(
+                        "{} is an array, and needs a {{}} in its name for the index",
+                        name
+                    );
+                }
+                indices
+                    .map(|index| name.replace("{}", &index.to_string()))
+                    .collect()
+            }
+            None => {
+                indexed.push(if name.contains("{}") {
+                    quote!(true)
+                } else {
+                    quote!(false)
+                });
+                vec![name.clone()]
+            }
+        };
+        let count = names.len();
+        datum_counts.push(quote!(#count));
+        for name in names {
+            definitions.push(quote! {
+                definitions.push((#name.to_string(), #unit, #epsilon, ::msfs::sys::#ty));
+            });
+        }
     }
-    array += "]";
-    let array = syn::parse_str::<Expr>(&array).unwrap();
 
     let output = quote! {
         #[repr(C)]
         #input
 
         impl ::msfs::sim_connect::DataDefinition for #name {
-            const DEFINITIONS: &'static [(&'static str, &'static str, f32, ::msfs::sys::SIMCONNECT_DATATYPE)] = #array;
+            const DATUM_COUNT: usize = 0 #(+ #datum_counts)*;
+
+            const FIELDS: &'static [(&'static str, usize)] = &[#((#field_names, #datum_counts)),*];
+
+            const INDEXED: bool = false #(|| #indexed)*;
+
+            fn definitions() -> Vec<(String, &'static str, f32, ::msfs::sys::SIMCONNECT_DATATYPE)> {
+                let mut definitions = Vec::new();
+                #(#definitions)*
+                definitions
+            }
+
+            fn decode(mut data: &[u8]) -> ::std::result::Result<Self, ::msfs::sim_connect::DecodeError> {
+                ::msfs::sim_connect::DataField::read(&mut data)
+            }
+
+            fn encode(&self, out: &mut Vec<u8>) {
+                ::msfs::sim_connect::DataField::write(self, out);
+            }
         }
+
+        impl ::msfs::sim_connect::DataField for #name {
+            fn read(data: &mut &[u8]) -> ::std::result::Result<Self, ::msfs::sim_connect::DecodeError> {
+                Ok(Self {
+                    #(#fields: #reads,)*
+                })
+            }
+
+            fn write(&self, out: &mut Vec<u8>) {
+                #(#writes;)*
+            }
+        }
+
+        #(#checks)*
     };
 
     TokenStream::from(output)
@@ -297,16 +559,71 @@ pub fn sim_connect_data_definition(_args: TokenStream, item: TokenStream) -> Tok
 ///     baz: i8,
 /// }
 /// ```
+///
+/// Fields may be integers, floats, `bool`, fixed-length strings, or arrays of
+/// these. A C-like enum or newtype can be stored as an integer with `convert`,
+/// as in a `data_definition`, and fails to decode with
+/// `DecodeError::InvalidValue` if the area holds a value out of range.
+/// ```rs
+/// #[sim_connect::client_data_definition]
+/// struct Status {
+///     #[convert = "u8"]
+///     mode: Mode,
+///     armed: bool,
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn sim_connect_client_data_definition(_args: TokenStream, item: TokenStream) -> TokenStream {
     let mut input = parse_macro_input!(item as ItemStruct);
     let name = input.ident.clone();
 
-    let data = parse_struct_fields(&mut input, &["epsilon"], None);
+    let data = parse_struct_fields(&mut input, &["epsilon", "convert"], None);
 
     let mut array = String::from("vec![\n");
+    let mut fields = Vec::new();
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+
+    for (i, (meta, field)) in data.iter().zip(input.fields.iter()).enumerate() {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(i);
+                quote!(#index)
+            }
+        };
+
+        // Fields are read from and written to the bytes at their offsets. A
+        // `bool` is a single byte here, not an `INT32` as in a data definition.
+        match (meta.get("convert"), &field.ty) {
+            (Some(raw), _) => {
+                let raw = format_ident!("{}", raw);
+                reads.push(quote!(::msfs::sim_connect::read_converted::<#raw, _>(&mut data)?));
+                writes.push(
+                    quote!(::msfs::sim_connect::write_converted::<#raw, _>(&self.#member, &mut field)),
+                );
+            }
+            (None, Type::Path(p)) if p.path.is_ident("bool") => {
+                reads.push(quote!(::msfs::sim_connect::read_client_bool(&mut data)?));
+                writes.push(
+                    quote!(::msfs::sim_connect::write_client_bool(&self.#member, &mut field)),
+                );
+            }
+            (None, Type::Array(a)) if matches!(&*a.elem, Type::Path(p) if p.path.is_ident("bool")) =>
+            {
+                panic!(
+                    "{} can't be an array of bool, use u8 instead",
+                    meta["field_name"]
+                );
+            }
+            (None, _) => {
+                reads.push(quote!(::msfs::sim_connect::DataField::read(&mut data)?));
+                writes
+                    .push(quote!(::msfs::sim_connect::DataField::write(&self.#member, &mut field)));
+            }
+        }
+        fields.push(member);
 
-    for meta in data {
         let fallback = "0.0".to_string();
         let epsilon = meta.get("epsilon").unwrap_or(&fallback);
 
@@ -330,6 +647,199 @@ pub fn sim_connect_client_data_definition(_args: TokenStream, item: TokenStream)
 
         impl ::msfs::sim_connect::ClientDataDefinition for #name {
             fn get_definitions() -> Vec<(usize, usize, f32)> { #array }
+
+            fn decode(bytes: &[u8]) -> ::std::result::Result<Self, ::msfs::sim_connect::DecodeError> {
+                Ok(Self {
+                    #(#fields: {
+                        let offset = ::std::mem::offset_of!(Self, #fields);
+                        let mut data = bytes
+                            .get(offset..)
+                            .ok_or(::msfs::sim_connect::DecodeError::Truncated)?;
+                        #reads
+                    },)*
+                })
+            }
+
+            fn encode(&self, out: &mut Vec<u8>) {
+                let start = out.len();
+                out.resize(start + ::std::mem::size_of::<Self>(), 0);
+                let mut field = Vec::new();
+                #(
+                    field.clear();
+                    #writes;
+                    let offset = start + ::std::mem::offset_of!(Self, #fields);
+                    out[offset..offset + field.len()].copy_from_slice(&field);
+                )*
+            }
+        }
+    };
+
+    TokenStream::from(output)
+}
+
+/// The type of the element of a `Vec<T>` or `Option<T>` field.
+fn generic_argument<'a>(ty: &'a Type, container: &str) -> Option<&'a Type> {
+    let Type::Path(p) = ty else {
+        return None;
+    };
+    let segment = p.path.segments.last()?;
+    if segment.ident != container {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Generate a struct which can be used with SimConnect's facility data
+/// definitions. The argument is the `SIMCONNECT_FACILITY_DATA_TYPE` of the
+/// block the struct describes, which is also the name the block is opened
+/// with.
+/// ```rs
+/// #[sim_connect::facility_definition(AIRPORT)]
+/// struct Airport {
+///     latitude: f64,
+///     longitude: f64,
+///     icao: String8,
+///     #[name = "NAME64"]
+///     name: String64,
+///     runways: Vec<Runway>,
+/// }
+///
+/// #[sim_connect::facility_definition(RUNWAY)]
+/// struct Runway {
+///     heading: f32,
+///     length: f32,
+///     #[name = "PRIMARY_THRESHOLD"]
+///     primary_threshold: Option<Pavement>,
+/// }
+///
+/// #[sim_connect::facility_definition(PAVEMENT)]
+/// struct Pavement {
+///     length: f32,
+/// }
+///
+/// let airport = sim.request_facility_data::<Airport>("LFPG", None)?.await?;
+/// ```
+///
+/// Fields are named after the facility field they hold, in upper case,
+/// unless given a `name`. They may be any type a `data_definition` field can
+/// be, and may be converted with `convert` in the same way.
+///
+/// A field of any other type is a nested block, opened with the name of its
+/// type's block or the field's `name`. `Vec<T>` fields hold the items of a
+/// list, such as the runways of an airport, `Option<T>` fields a block which
+/// may be missing, and fields of other types a block which must be present.
+#[proc_macro_attribute]
+pub fn sim_connect_facility_definition(args: TokenStream, item: TokenStream) -> TokenStream {
+    let block = parse_macro_input!(args as Ident);
+    let mut input = parse_macro_input!(item as ItemStruct);
+    let name = input.ident.clone();
+
+    let data = parse_struct_fields(&mut input, &["name", "convert"], None);
+
+    let mut definitions = Vec::new();
+    let mut fields = Vec::new();
+    let mut reads = Vec::new();
+    let mut has_data = false;
+    for (i, (meta, field)) in data.iter().zip(input.fields.iter()).enumerate() {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(i);
+                quote!(#index)
+            }
+        };
+        fields.push(member);
+
+        let list = generic_argument(&field.ty, "Vec");
+        let optional = generic_argument(&field.ty, "Option");
+        let scalar = match &field.ty {
+            Type::Path(p) => p
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| data_type(&segment.ident.to_string()).is_some()),
+            _ => false,
+        };
+
+        // Fields are read in order from the data of the block, while nested
+        // blocks are taken from its children.
+        if scalar || meta.contains_key("convert") {
+            has_data = true;
+            let name = meta
+                .get("name")
+                .cloned()
+                .unwrap_or_else(|| meta["field_name"].trim_start_matches("r#").to_uppercase());
+            definitions.push(quote!(definitions.push(#name.to_string());));
+            match meta.get("convert") {
+                Some(raw) => {
+                    let raw = format_ident!("{}", raw);
+                    reads.push(quote!(::msfs::sim_connect::read_converted::<#raw, _>(&mut data)?));
+                }
+                None => reads.push(quote!(::msfs::sim_connect::DataField::read(&mut data)?)),
+            }
+            continue;
+        }
+
+        let ty = list.or(optional).unwrap_or(&field.ty);
+        let block_name = match meta.get("name") {
+            Some(name) => quote!(#name),
+            None => quote!(<#ty as ::msfs::sim_connect::FacilityDefinition>::BLOCK),
+        };
+        definitions.push(quote! {
+            <#ty as ::msfs::sim_connect::FacilityDefinition>::definitions(#block_name, definitions);
+        });
+        reads.push(match (list, optional) {
+            (Some(_), _) => quote!(block.take_list::<#ty>()?),
+            (None, Some(_)) => quote!(block.take_one::<#ty>()?),
+            (None, None) => quote! {
+                block.take_one::<#ty>()?.ok_or(::msfs::sim_connect::DecodeError::MissingBlock {
+                    block: #block_name,
+                })?
+            },
+        });
+    }
+
+    // A block with only nested blocks has no data of its own to read.
+    let take_data = has_data.then(|| {
+        quote! {
+            let data = block.take_data();
+            let mut data = &data[..];
+        }
+    });
+
+    let block_name = block.to_string();
+    let data_type = format_ident!(
+        "SIMCONNECT_FACILITY_DATA_TYPE_SIMCONNECT_FACILITY_DATA_{}",
+        block
+    );
+    let output = quote! {
+        #input
+
+        impl ::msfs::sim_connect::FacilityDefinition for #name {
+            const BLOCK: &'static str = #block_name;
+
+            const DATA_TYPE: ::msfs::sys::SIMCONNECT_FACILITY_DATA_TYPE = ::msfs::sys::#data_type;
+
+            fn definitions(block: &str, definitions: &mut Vec<String>) {
+                definitions.push(format!("OPEN {block}"));
+                #(#definitions)*
+                definitions.push(format!("CLOSE {block}"));
+            }
+
+            fn assemble(
+                block: &mut ::msfs::sim_connect::FacilityBlock,
+            ) -> ::std::result::Result<Self, ::msfs::sim_connect::DecodeError> {
+                #take_data
+                Ok(Self {
+                    #(#fields: #reads,)*
+                })
+            }
         }
     };
 